@@ -0,0 +1,92 @@
+//! Base64 with the standard alphabet and padding.
+//! [RFC4648, Section 4](https://tools.ietf.org/html/rfc4648#section-4)
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let group = (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2]);
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(ALPHABET[(group >> (18 - index * 6)) as usize & 0x3f] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+/// Returns `None` if the input contains characters outside of the alphabet
+/// or is not correctly padded.
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    let input = input.as_bytes();
+
+    if input.len() % 4 != 0 {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+
+    for (chunk_index, chunk) in input.chunks(4).enumerate() {
+        let is_last = chunk_index == input.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|&&byte| byte == b'=').count();
+
+        if padding > 2 || (padding > 0 && !is_last) {
+            return None;
+        }
+
+        let mut group = 0u32;
+
+        for &byte in &chunk[..4 - padding] {
+            let value = ALPHABET.iter().position(|&c| c == byte)?;
+
+            group = (group << 6) | value as u32;
+        }
+
+        group <<= 6 * padding as u32;
+
+        for index in 0..3 - padding {
+            output.push((group >> (16 - index * 8)) as u8);
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const VECTORS: [(&str, &str); 7] = [("", ""),
+                                        ("f", "Zg=="),
+                                        ("fo", "Zm8="),
+                                        ("foo", "Zm9v"),
+                                        ("foob", "Zm9vYg=="),
+                                        ("fooba", "Zm9vYmE="),
+                                        ("foobar", "Zm9vYmFy")];
+
+    #[test]
+    fn test_encode() {
+        for &(input, output) in VECTORS.iter() {
+            assert_eq!(output, encode(input.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        for &(output, input) in VECTORS.iter() {
+            assert_eq!(Some(output.as_bytes().to_vec()), decode(input));
+        }
+
+        assert_eq!(None, decode("Zg="));
+        assert_eq!(None, decode("Zg==Zg=="));
+        assert_eq!(None, decode("Zm9v!A=="));
+        assert_eq!(None, decode("Z==="));
+    }
+}
@@ -0,0 +1,102 @@
+//! Helpers for caches storing more than one variant of a resource.
+//! [RFC7234, Section 4.1](http://www.iana.org/go/rfc7234)
+
+use super::headers::{CommaDelimited, Headers, Vary, VaryHeader};
+
+/// Computes the secondary cache key of a request from the request headers
+/// listed in the `Vary` header of the stored response.
+/// A stored response may only be used for a request with the same key.
+///
+/// Field names are compared case-insensitively and their order doesn't matter.
+/// Values are normalized by combining multiple header lines, removing the
+/// whitespace around commas and dropping empty list elements.
+/// An absent header results in a different key than an empty one.
+///
+/// Returns `None` for `Vary: *`, such a response never matches another request.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::cache::cache_key;
+/// use teapot::http::headers::{Headers, RawHeader, VaryHeader};
+///
+/// let vary = VaryHeader::fields(vec!["Accept-Encoding".to_string()]);
+/// let mut first = Headers::new();
+/// let mut second = Headers::new();
+///
+/// first.append_raw(RawHeader::new("Accept-Encoding", "gzip, br"));
+/// second.append_raw(RawHeader::new("accept-encoding", "gzip"));
+/// second.append_raw(RawHeader::new("accept-encoding", "br"));
+///
+/// assert_eq!(cache_key(&first, &vary), cache_key(&second, &vary));
+/// assert_eq!(None, cache_key(&first, &VaryHeader::any()));
+/// ```
+pub fn cache_key(headers: &Headers, vary: &VaryHeader) -> Option<String> {
+    let mut names: Vec<String> = match *vary.value() {
+        Vary::Any => return None,
+        Vary::Fields(ref names) => names.iter().map(|name| name.to_lowercase()).collect(),
+    };
+
+    names.sort();
+    names.dedup();
+
+    let fields: Vec<String> = names.into_iter()
+        .map(|name| {
+            let raw = headers.get_raw(&name);
+
+            if raw.is_empty() {
+                return name;
+            }
+
+            let elements: CommaDelimited<String> = CommaDelimited::from_raw(&raw)
+                .unwrap_or_default();
+            let elements: Vec<&str> = elements.iter().map(String::as_str).collect();
+
+            format!("{}:{}", name, elements.join(","))
+        })
+        .collect();
+
+    Some(fields.join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::RawHeader;
+
+    fn headers(raw: &[(&str, &str)]) -> Headers {
+        let mut headers = Headers::new();
+
+        for &(name, value) in raw {
+            headers.append_raw(RawHeader::new(name, value));
+        }
+
+        headers
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let vary = VaryHeader::fields(vec!["Accept-Language".to_string(),
+                                           "accept-encoding".to_string()]);
+        let reordered = VaryHeader::fields(vec!["Accept-Encoding".to_string(),
+                                                "Accept-Language".to_string()]);
+        let request = headers(&[("Accept-Encoding", "gzip ,br"), ("Accept-Language", "de")]);
+
+        assert_eq!(Some("accept-encoding:gzip,br\naccept-language:de".to_string()),
+                   cache_key(&request, &vary));
+        assert_eq!(cache_key(&request, &vary), cache_key(&request, &reordered));
+    }
+
+    #[test]
+    fn test_cache_key_differs() {
+        let vary = VaryHeader::fields(vec!["Accept-Encoding".to_string()]);
+        let gzip = headers(&[("Accept-Encoding", "gzip")]);
+        let br = headers(&[("Accept-Encoding", "br")]);
+        let empty = headers(&[("Accept-Encoding", "")]);
+        let absent = headers(&[("Accept", "text/html")]);
+
+        assert_ne!(cache_key(&gzip, &vary), cache_key(&br, &vary));
+        assert_ne!(cache_key(&empty, &vary), cache_key(&absent, &vary));
+        assert_eq!(Some(String::new()), cache_key(&absent, &VaryHeader::fields(vec![])));
+    }
+}
@@ -0,0 +1,333 @@
+//! The chunked transfer coding, which allows sending a body of unknown length
+//! followed by a trailer section, e.g. with a checksum of the body.
+//! [RFC7230, Section 4.1](http://www.iana.org/go/rfc7230)
+
+use std::io::{self, Read, Write};
+use super::headers::{Headers, RawHeader};
+use super::parse;
+
+/// Fields that must not be sent in a trailer section, since they are needed
+/// for framing, routing, authentication, request modification or
+/// processing of the body before it is received.
+/// [RFC7230, Section 4.1.2](http://www.iana.org/go/rfc7230)
+const DISALLOWED_TRAILERS: [&str; 30] = ["age",
+                                         "authorization",
+                                         "cache-control",
+                                         "connection",
+                                         "content-encoding",
+                                         "content-length",
+                                         "content-range",
+                                         "content-type",
+                                         "cookie",
+                                         "date",
+                                         "expect",
+                                         "expires",
+                                         "host",
+                                         "keep-alive",
+                                         "location",
+                                         "max-forwards",
+                                         "pragma",
+                                         "proxy-authenticate",
+                                         "proxy-authorization",
+                                         "proxy-connection",
+                                         "range",
+                                         "retry-after",
+                                         "set-cookie",
+                                         "te",
+                                         "trailer",
+                                         "transfer-encoding",
+                                         "upgrade",
+                                         "vary",
+                                         "warning",
+                                         "www-authenticate"];
+
+/// Checks whether the field `name` may be sent in a trailer section.
+/// Conditional request fields (`If-*`) are not allowed either.
+pub fn is_allowed_trailer(name: &str) -> bool {
+    let name = name.to_lowercase();
+
+    !name.starts_with("if-") && !DISALLOWED_TRAILERS.contains(&name.as_str())
+}
+
+/// Fails with `InvalidData` if a field of `trailers` is not allowed in trailers.
+pub(crate) fn check_trailers(trailers: &Headers) -> io::Result<()> {
+    match trailers.iter().find(|header| !is_allowed_trailer(header.name())) {
+        Some(header) => {
+            Err(io::Error::new(io::ErrorKind::InvalidData,
+                               format!("{} is not allowed in trailers", header.name())))
+        }
+        None => Ok(()),
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+    Size,
+    Data(u64),
+    DataEnd,
+    Done,
+}
+
+/// Decodes a chunked body. Chunk extensions are ignored.
+///
+/// Reading fails with `InvalidData` if the trailer section contains
+/// a field that is not allowed in trailers.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use teapot::http::chunked::ChunkedReader;
+///
+/// let mut reader = ChunkedReader::new("5\r\nHello\r\n0\r\ngrpc-status: 0\r\n\r\n".as_bytes());
+/// let mut body = String::new();
+///
+/// reader.read_to_string(&mut body).unwrap();
+///
+/// assert_eq!("Hello", body);
+/// assert_eq!("0", reader.trailers().unwrap().get_raw("grpc-status")[0].value());
+/// ```
+#[derive(Debug)]
+pub struct ChunkedReader<R> {
+    inner: R,
+    state: State,
+    trailers: Option<Headers>,
+}
+
+impl<R: Read> ChunkedReader<R> {
+    pub fn new(inner: R) -> Self {
+        ChunkedReader {
+            inner,
+            state: State::Size,
+            trailers: None,
+        }
+    }
+
+    /// The trailer section, available once the whole body was read.
+    pub fn trailers(&self) -> Option<&Headers> {
+        self.trailers.as_ref()
+    }
+
+    pub fn into_trailers(self) -> Option<Headers> {
+        self.trailers
+    }
+
+    fn read_size(&mut self) -> io::Result<u64> {
+        let line = parse::read_line(&mut self.inner)?;
+        let size = match line.find(';') {
+            Some(index) => &line[..index],
+            None => &line,
+        };
+        let size = size.trim_end_matches([' ', '\t']);
+
+        if size.is_empty() || size.len() > 16 ||
+           !size.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(invalid_data("invalid chunk size"));
+        }
+
+        u64::from_str_radix(size, 16).map_err(|_| invalid_data("invalid chunk size"))
+    }
+
+    fn read_trailers(&mut self) -> io::Result<Headers> {
+        let mut trailers = Headers::new();
+
+        loop {
+            let line = parse::read_line(&mut self.inner)?;
+
+            if line.is_empty() {
+                check_trailers(&trailers)?;
+
+                return Ok(trailers);
+            }
+
+            trailers.append_raw(RawHeader::parse(line));
+        }
+    }
+}
+
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.state {
+                State::Size => {
+                    self.state = match self.read_size()? {
+                        0 => {
+                            self.trailers = Some(self.read_trailers()?);
+                            State::Done
+                        }
+                        size => State::Data(size),
+                    };
+                }
+                State::Data(remaining) => {
+                    if buf.is_empty() {
+                        return Ok(0);
+                    }
+
+                    let length = remaining.min(buf.len() as u64) as usize;
+                    let read = self.inner.read(&mut buf[..length])?;
+
+                    if read == 0 {
+                        return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+                    }
+
+                    self.state = match remaining - read as u64 {
+                        0 => State::DataEnd,
+                        remaining => State::Data(remaining),
+                    };
+
+                    return Ok(read);
+                }
+                State::DataEnd => {
+                    if !parse::read_line(&mut self.inner)?.is_empty() {
+                        return Err(invalid_data("missing CRLF after chunk data"));
+                    }
+
+                    self.state = State::Size;
+                }
+                State::Done => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Encodes a body with the chunked transfer coding. Every write is sent
+/// as a single chunk, [`finish`] ends the body and writes the trailers.
+///
+/// [`finish`]: #method.finish
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use teapot::http::chunked::ChunkedWriter;
+/// use teapot::http::headers::{Headers, RawHeader};
+///
+/// let mut writer = ChunkedWriter::new(Vec::new());
+/// let mut trailers = Headers::new();
+///
+/// writer.write_all(b"Hello").unwrap();
+/// trailers.append_raw(RawHeader::new("grpc-status", "0"));
+///
+/// assert_eq!(b"5\r\nHello\r\n0\r\ngrpc-status: 0\r\n\r\n".to_vec(),
+///            writer.finish(&trailers).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct ChunkedWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> ChunkedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        ChunkedWriter { inner }
+    }
+
+    /// Writes the last chunk and the trailer section and returns the inner writer.
+    /// Fails with `InvalidData` without writing anything if `trailers` contains
+    /// a field that is not allowed in trailers.
+    pub fn finish(mut self, trailers: &Headers) -> io::Result<W> {
+        check_trailers(trailers)?;
+        self.inner.write_all(b"0\r\n")?;
+        parse::write_headers(&mut self.inner, trailers)?;
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for ChunkedWriter<W> {
+    /// Empty writes are ignored, since an empty chunk would end the body.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        write!(self.inner, "{:X}\r\n", buf.len())?;
+        self.inner.write_all(buf)?;
+        self.inner.write_all(b"\r\n")?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode(encoded: &str) -> io::Result<(String, Headers)> {
+        let mut reader = ChunkedReader::new(encoded.as_bytes());
+        let mut body = String::new();
+
+        reader.read_to_string(&mut body)?;
+
+        Ok((body, reader.into_trailers().unwrap()))
+    }
+
+    #[test]
+    fn test_is_allowed_trailer() {
+        assert!(is_allowed_trailer("Digest"));
+        assert!(is_allowed_trailer("grpc-message"));
+        assert!(!is_allowed_trailer("Content-Length"));
+        assert!(!is_allowed_trailer("If-None-Match"));
+        assert!(!is_allowed_trailer("SET-COOKIE"));
+    }
+
+    #[test]
+    fn test_decode() {
+        let (body, trailers) = decode("4;ext=1\r\nWiki\r\n9\r\npedia in \r\n\
+                                       0\r\nDigest: sha-256=abc\r\nX-Foo: bar\r\n\r\nrest")
+            .unwrap();
+
+        assert_eq!("Wikipedia in ", body);
+        assert_eq!(2, trailers.len());
+        assert_eq!("sha-256=abc", trailers.get_raw("digest")[0].value());
+
+        let (body, trailers) = decode("0\r\n\r\n").unwrap();
+
+        assert_eq!("", body);
+        assert!(trailers.is_empty());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let kind = |encoded| decode(encoded).unwrap_err().kind();
+
+        assert_eq!(io::ErrorKind::InvalidData, kind("x\r\n"));
+        assert_eq!(io::ErrorKind::InvalidData, kind("2\r\nabc\r\n0\r\n\r\n"));
+        assert_eq!(io::ErrorKind::InvalidData, kind("11111111111111111\r\n"));
+        assert_eq!(io::ErrorKind::InvalidData,
+                   kind("1\r\na\r\n0\r\nContent-Length: 1\r\n\r\n"));
+        assert_eq!(io::ErrorKind::UnexpectedEof, kind("5\r\nab"));
+        assert_eq!(io::ErrorKind::UnexpectedEof, kind("1\r\na\r\n"));
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut writer = ChunkedWriter::new(Vec::new());
+        let mut trailers = Headers::new();
+
+        writer.write_all(b"0123456789").unwrap();
+        writer.write_all(b"").unwrap();
+
+        let encoded = writer.finish(&trailers).unwrap();
+
+        assert_eq!(b"A\r\n0123456789\r\n0\r\n\r\n".to_vec(), encoded);
+        assert_eq!(("0123456789".to_string(), Headers::new().len()),
+                   decode(std::str::from_utf8(&encoded).unwrap())
+                       .map(|(body, trailers)| (body, trailers.len()))
+                       .unwrap());
+
+        trailers.append_raw(RawHeader::new("Transfer-Encoding", "chunked"));
+
+        let writer = ChunkedWriter::new(Vec::new());
+
+        assert_eq!(io::ErrorKind::InvalidData, writer.finish(&trailers).unwrap_err().kind());
+    }
+}
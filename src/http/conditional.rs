@@ -0,0 +1,172 @@
+use super::date::HttpDate;
+use super::headers::{EntityTag, Headers, IfMatchHeader, IfModifiedSinceHeader,
+                     IfNoneMatchHeader, IfUnmodifiedSinceHeader, TypedHeader};
+use super::method::Method;
+use super::status::StatusCode;
+
+/// The outcome of [`evaluate_preconditions`].
+///
+/// [`evaluate_preconditions`]: fn.evaluate_preconditions.html
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Precondition {
+    /// All preconditions passed, the request method should be performed.
+    Continue,
+    /// The request must be answered with this status code
+    /// (either `NotModified` or `PreconditionFailed`) without performing the method.
+    Respond(StatusCode),
+}
+
+/// Evaluates the conditional request headers in the order defined by
+/// [RFC7232, Section 6](http://www.iana.org/go/rfc7232).
+///
+/// `etag` and `last_modified` are the validators of the selected representation.
+/// `etag` must be `None` if there is no current representation.
+/// An `If-Match` header that can't be parsed fails the request,
+/// so an invalid precondition never lets a write through.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::conditional::{evaluate_preconditions, Precondition};
+/// use teapot::http::headers::{EntityTag, Headers, RawHeader};
+/// use teapot::http::method::Method;
+/// use teapot::http::status::StatusCode;
+///
+/// let mut headers = Headers::new();
+/// let etag = EntityTag::strong("v2").unwrap();
+///
+/// headers.append_raw(RawHeader::new("If-Match", "\"v1\""));
+///
+/// assert_eq!(Precondition::Respond(StatusCode::PreconditionFailed),
+///            evaluate_preconditions(&headers, Some(&etag), None, &Method::Put));
+/// ```
+pub fn evaluate_preconditions(headers: &Headers,
+                              etag: Option<&EntityTag>,
+                              last_modified: Option<HttpDate>,
+                              method: &Method)
+                              -> Precondition {
+    let is_get_or_head = *method == Method::Get || *method == Method::Head;
+
+    // Step 1 and 2
+    if headers.contains(IfMatchHeader::name()) {
+        match headers.get::<IfMatchHeader>() {
+            Some(ref if_match) if if_match.matches(etag) => {}
+            _ => return Precondition::Respond(StatusCode::PreconditionFailed),
+        }
+    } else if let (Some(if_unmodified_since), Some(last_modified)) =
+        (headers.get::<IfUnmodifiedSinceHeader>(), last_modified) {
+        if last_modified > if_unmodified_since.value() {
+            return Precondition::Respond(StatusCode::PreconditionFailed);
+        }
+    }
+
+    // Step 3 and 4
+    if let Some(if_none_match) = headers.get::<IfNoneMatchHeader>() {
+        if if_none_match.matches(etag) {
+            return Precondition::Respond(if is_get_or_head {
+                StatusCode::NotModified
+            } else {
+                StatusCode::PreconditionFailed
+            });
+        }
+    } else if let (true, Some(if_modified_since), Some(last_modified)) =
+        (is_get_or_head, headers.get::<IfModifiedSinceHeader>(), last_modified) {
+        if last_modified <= if_modified_since.value() {
+            return Precondition::Respond(StatusCode::NotModified);
+        }
+    }
+
+    Precondition::Continue
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::RawHeader;
+
+    fn headers(raw: &[(&str, &str)]) -> Headers {
+        let mut headers = Headers::new();
+
+        for &(name, value) in raw {
+            headers.append_raw(RawHeader::new(name, value));
+        }
+
+        headers
+    }
+
+    fn date(value: &str) -> HttpDate {
+        value.parse().unwrap()
+    }
+
+    const EARLIER: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+    const LATER: &str = "Mon, 07 Nov 1994 08:49:37 GMT";
+
+    #[test]
+    fn test_no_preconditions() {
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&Headers::new(), None, None, &Method::Get));
+    }
+
+    #[test]
+    fn test_if_match() {
+        let etag = EntityTag::strong("a").unwrap();
+        let matching = headers(&[("If-Match", "\"a\""), ("If-Unmodified-Since", EARLIER)]);
+        let weak = headers(&[("If-Match", "W/\"a\"")]);
+        let any = headers(&[("If-Match", "*")]);
+
+        // If-Unmodified-Since is ignored when If-Match is present
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&matching, Some(&etag), Some(date(LATER)), &Method::Put));
+        assert_eq!(Precondition::Respond(StatusCode::PreconditionFailed),
+                   evaluate_preconditions(&weak, Some(&etag), None, &Method::Put));
+        assert_eq!(Precondition::Respond(StatusCode::PreconditionFailed),
+                   evaluate_preconditions(&any, None, None, &Method::Put));
+
+        let invalid = headers(&[("If-Match", "a")]);
+
+        assert_eq!(Precondition::Respond(StatusCode::PreconditionFailed),
+                   evaluate_preconditions(&invalid, Some(&etag), None, &Method::Put));
+    }
+
+    #[test]
+    fn test_if_unmodified_since() {
+        let headers = headers(&[("If-Unmodified-Since", EARLIER)]);
+
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&headers, None, Some(date(EARLIER)), &Method::Delete));
+        assert_eq!(Precondition::Respond(StatusCode::PreconditionFailed),
+                   evaluate_preconditions(&headers, None, Some(date(LATER)), &Method::Delete));
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&headers, None, None, &Method::Delete));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = EntityTag::strong("a").unwrap();
+        let headers = headers(&[("If-None-Match", "W/\"a\""), ("If-Modified-Since", EARLIER)]);
+
+        assert_eq!(Precondition::Respond(StatusCode::NotModified),
+                   evaluate_preconditions(&headers, Some(&etag), None, &Method::Head));
+        assert_eq!(Precondition::Respond(StatusCode::PreconditionFailed),
+                   evaluate_preconditions(&headers, Some(&etag), None, &Method::Post));
+
+        // If-Modified-Since is ignored when If-None-Match is present
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&headers,
+                                          Some(&EntityTag::strong("b").unwrap()),
+                                          Some(date(EARLIER)),
+                                          &Method::Get));
+    }
+
+    #[test]
+    fn test_if_modified_since() {
+        let headers = headers(&[("If-Modified-Since", EARLIER)]);
+
+        assert_eq!(Precondition::Respond(StatusCode::NotModified),
+                   evaluate_preconditions(&headers, None, Some(date(EARLIER)), &Method::Get));
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&headers, None, Some(date(LATER)), &Method::Get));
+        assert_eq!(Precondition::Continue,
+                   evaluate_preconditions(&headers, None, Some(date(EARLIER)), &Method::Post));
+    }
+}
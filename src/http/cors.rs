@@ -0,0 +1,412 @@
+use super::headers::{AccessControlAllowCredentialsHeader, AccessControlAllowHeadersHeader,
+                     AccessControlAllowMethodsHeader, AccessControlAllowOriginHeader,
+                     AccessControlExposeHeadersHeader, AccessControlMaxAgeHeader,
+                     AccessControlRequestHeadersHeader, AccessControlRequestMethodHeader,
+                     AllowOrigin, Headers, OriginHeader, VaryHeader};
+use super::method::Method;
+
+/// Request headers that never need to be listed in `Access-Control-Allow-Headers`.
+const SAFELISTED_HEADERS: [&str; 3] = ["accept", "accept-language", "content-language"];
+
+/// The outcome of [`CorsPolicy::evaluate`].
+///
+/// [`CorsPolicy::evaluate`]: struct.CorsPolicy.html#method.evaluate
+#[derive(Debug)]
+pub enum CorsDecision {
+    /// The request has no `Origin` header and is not subject to CORS.
+    NotCors,
+    /// The request is an allowed preflight request.
+    /// It should be answered directly with these headers and a successful status.
+    Preflight(Headers),
+    /// The request is an allowed cross-origin request.
+    /// These headers must be added to the response.
+    Allowed(Headers),
+    /// The origin, method or one of the headers is not allowed.
+    /// The response must not contain any CORS headers,
+    /// but these headers (`Vary: Origin` for an origin list) must be added.
+    Rejected(Headers),
+}
+
+#[derive(Clone, Debug)]
+enum Origins {
+    Any,
+    List(Vec<String>),
+}
+
+/// Server side configuration of the
+/// [CORS protocol](https://fetch.spec.whatwg.org/#http-cors-protocol).
+///
+/// A new policy allows no origins at all.
+/// `GET`, `HEAD` and `POST` as well as the safelisted request headers
+/// are always allowed and don't need to be configured.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::cors::{CorsDecision, CorsPolicy};
+/// use teapot::http::headers::{Headers, RawHeader};
+/// use teapot::http::method::Method;
+///
+/// let mut policy = CorsPolicy::new();
+/// let mut headers = Headers::new();
+///
+/// policy.allow_origin("https://example.com");
+/// policy.allow_method(Method::Put);
+///
+/// headers.append_raw(RawHeader::new("Origin", "https://example.com"));
+/// headers.append_raw(RawHeader::new("Access-Control-Request-Method", "PUT"));
+///
+/// match policy.evaluate(&Method::Options, &headers) {
+///     CorsDecision::Preflight(response) => {
+///         assert_eq!("https://example.com",
+///                    response.get_raw("access-control-allow-origin")[0].value());
+///     }
+///     _ => panic!("expected a preflight response"),
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CorsPolicy {
+    origins: Origins,
+    methods: Vec<Method>,
+    headers: Vec<String>,
+    exposed_headers: Vec<String>,
+    allow_credentials: bool,
+    max_age: Option<u64>,
+}
+
+impl Default for CorsPolicy {
+    fn default() -> Self {
+        CorsPolicy::new()
+    }
+}
+
+impl CorsPolicy {
+    pub fn new() -> Self {
+        CorsPolicy {
+            origins: Origins::List(Vec::new()),
+            methods: Vec::new(),
+            headers: Vec::new(),
+            exposed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+
+    /// Allows a serialized origin, e.g. `https://example.com:8080`.
+    /// Has no effect if any origin is already allowed.
+    pub fn allow_origin<S: Into<String>>(&mut self, origin: S) {
+        if let Origins::List(ref mut origins) = self.origins {
+            origins.push(origin.into());
+        }
+    }
+
+    pub fn allow_any_origin(&mut self) {
+        self.origins = Origins::Any;
+    }
+
+    pub fn allow_method(&mut self, method: Method) {
+        self.methods.push(method);
+    }
+
+    pub fn allow_header<S: Into<String>>(&mut self, name: S) {
+        self.headers.push(name.into().to_lowercase());
+    }
+
+    pub fn expose_header<S: Into<String>>(&mut self, name: S) {
+        self.exposed_headers.push(name.into());
+    }
+
+    /// When credentials are allowed, the request origin is echoed back.
+    /// Credentials are only allowed for an explicit origin list and never for
+    /// the `null` origin, which is shared by sandboxed documents of all sites.
+    /// If any origin is allowed, `*` is sent without
+    /// `Access-Control-Allow-Credentials`.
+    pub fn set_allow_credentials(&mut self, allow_credentials: bool) {
+        self.allow_credentials = allow_credentials;
+    }
+
+    pub fn set_max_age(&mut self, max_age: Option<u64>) {
+        self.max_age = max_age;
+    }
+
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        match self.origins {
+            Origins::Any => true,
+            Origins::List(ref origins) => origins.iter().any(|allowed| allowed == origin),
+        }
+    }
+
+    pub fn allows_method(&self, method: &Method) -> bool {
+        match *method {
+            Method::Get | Method::Head | Method::Post => true,
+            _ => self.methods.contains(method),
+        }
+    }
+
+    pub fn allows_header(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+
+        SAFELISTED_HEADERS.contains(&name.as_str()) || self.headers.contains(&name)
+    }
+
+    /// Evaluates a request given its method and headers.
+    ///
+    /// A request is treated as a preflight request if it uses `OPTIONS`
+    /// and carries both `Origin` and `Access-Control-Request-Method`.
+    pub fn evaluate(&self, method: &Method, headers: &Headers) -> CorsDecision {
+        let origin = match headers.get::<OriginHeader>() {
+            Some(origin) => origin,
+            None => return CorsDecision::NotCors,
+        };
+
+        if !self.allows_origin(origin.value()) || (self.allows_credentials() && origin.is_null()) {
+            return CorsDecision::Rejected(self.rejected_headers());
+        }
+
+        let request_method = headers.get::<AccessControlRequestMethodHeader>();
+
+        match request_method {
+            Some(ref request_method) if *method == Method::Options => {
+                self.evaluate_preflight(&origin, request_method.method(), headers)
+            }
+            _ => {
+                let mut response = self.response_headers(&origin);
+
+                if !self.exposed_headers.is_empty() {
+                    response.append(AccessControlExposeHeadersHeader::new(self.exposed_headers
+                                                                              .clone()));
+                }
+
+                CorsDecision::Allowed(response)
+            }
+        }
+    }
+
+    fn evaluate_preflight(&self,
+                          origin: &OriginHeader,
+                          method: &Method,
+                          headers: &Headers)
+                          -> CorsDecision {
+        if !self.allows_method(method) {
+            return CorsDecision::Rejected(self.rejected_headers());
+        }
+
+        let request_headers = headers.get::<AccessControlRequestHeadersHeader>();
+        let request_headers = request_headers.as_ref().map_or(&[][..], |header| header.names());
+
+        if !request_headers.iter().all(|name| self.allows_header(name)) {
+            return CorsDecision::Rejected(self.rejected_headers());
+        }
+
+        let mut response = self.response_headers(origin);
+
+        response.append(AccessControlAllowMethodsHeader::new(vec![method.clone()]));
+
+        if !request_headers.is_empty() {
+            response.append(AccessControlAllowHeadersHeader::new(request_headers.to_vec()));
+        }
+
+        if let Some(max_age) = self.max_age {
+            response.append(AccessControlMaxAgeHeader::new(max_age));
+        }
+
+        CorsDecision::Preflight(response)
+    }
+
+    fn allows_credentials(&self) -> bool {
+        match self.origins {
+            Origins::Any => false,
+            Origins::List(_) => self.allow_credentials,
+        }
+    }
+
+    /// The response depends on the origin unless any origin is allowed.
+    fn rejected_headers(&self) -> Headers {
+        let mut response = Headers::new();
+
+        if let Origins::List(_) = self.origins {
+            response.append(VaryHeader::fields(vec!["Origin".to_string()]));
+        }
+
+        response
+    }
+
+    fn response_headers(&self, origin: &OriginHeader) -> Headers {
+        let mut response = self.rejected_headers();
+
+        let allow_origin = match self.origins {
+            Origins::Any => AllowOrigin::Any,
+            Origins::List(_) if origin.is_null() => AllowOrigin::Null,
+            Origins::List(_) => AllowOrigin::Origin(origin.value().to_string()),
+        };
+
+        response.append(AccessControlAllowOriginHeader::new(allow_origin));
+
+        if self.allows_credentials() {
+            response.append(AccessControlAllowCredentialsHeader);
+        }
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::RawHeader;
+
+    fn request(origin: &str, request_method: Option<&str>, request_headers: Option<&str>) -> Headers {
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Origin", origin));
+
+        if let Some(method) = request_method {
+            headers.append_raw(RawHeader::new("Access-Control-Request-Method", method));
+        }
+
+        if let Some(names) = request_headers {
+            headers.append_raw(RawHeader::new("Access-Control-Request-Headers", names));
+        }
+
+        headers
+    }
+
+    fn value<'a>(headers: &'a Headers, name: &str) -> Option<&'a str> {
+        headers.get_raw(name).first().map(|header| header.value())
+    }
+
+    #[test]
+    fn test_not_cors() {
+        let policy = CorsPolicy::new();
+
+        match policy.evaluate(&Method::Get, &Headers::new()) {
+            CorsDecision::NotCors => {}
+            decision => panic!("unexpected {:?}", decision),
+        }
+    }
+
+    #[test]
+    fn test_preflight() {
+        let mut policy = CorsPolicy::new();
+
+        policy.allow_origin("https://a.test");
+        policy.allow_method(Method::Delete);
+        policy.allow_header("X-Custom");
+        policy.set_max_age(Some(600));
+
+        let headers = request("https://a.test", Some("DELETE"), Some("x-custom, accept"));
+
+        match policy.evaluate(&Method::Options, &headers) {
+            CorsDecision::Preflight(response) => {
+                assert_eq!(Some("https://a.test"), value(&response, "access-control-allow-origin"));
+                assert_eq!(Some("DELETE"), value(&response, "access-control-allow-methods"));
+                assert_eq!(Some("x-custom, accept"),
+                           value(&response, "access-control-allow-headers"));
+                assert_eq!(Some("600"), value(&response, "access-control-max-age"));
+                assert_eq!(Some("Origin"), value(&response, "vary"));
+                assert_eq!(None, value(&response, "access-control-allow-credentials"));
+            }
+            decision => panic!("unexpected {:?}", decision),
+        }
+    }
+
+    #[test]
+    fn test_preflight_rejected() {
+        let mut policy = CorsPolicy::new();
+
+        policy.allow_origin("https://a.test");
+
+        let rejected = |headers: Headers| {
+            match policy.evaluate(&Method::Options, &headers) {
+                CorsDecision::Rejected(response) => {
+                    assert_eq!(Some("Origin"), value(&response, "vary"));
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        assert!(rejected(request("https://b.test", Some("GET"), None)));
+        assert!(rejected(request("https://a.test", Some("PUT"), None)));
+        assert!(rejected(request("https://a.test", Some("GET"), Some("x-custom"))));
+        assert!(!rejected(request("https://a.test", Some("GET"), Some("Accept-Language"))));
+    }
+
+    #[test]
+    fn test_actual_request() {
+        let mut policy = CorsPolicy::new();
+
+        policy.allow_any_origin();
+        policy.expose_header("X-Total-Count");
+
+        match policy.evaluate(&Method::Get, &request("https://b.test", None, None)) {
+            CorsDecision::Allowed(response) => {
+                assert_eq!(Some("*"), value(&response, "access-control-allow-origin"));
+                assert_eq!(Some("X-Total-Count"),
+                           value(&response, "access-control-expose-headers"));
+                assert_eq!(None, value(&response, "vary"));
+            }
+            decision => panic!("unexpected {:?}", decision),
+        }
+    }
+
+    #[test]
+    fn test_credentials_echo_origin() {
+        let mut policy = CorsPolicy::new();
+
+        policy.allow_origin("https://b.test");
+        policy.set_allow_credentials(true);
+
+        match policy.evaluate(&Method::Post, &request("https://b.test", None, None)) {
+            CorsDecision::Allowed(response) => {
+                assert_eq!(Some("https://b.test"), value(&response, "access-control-allow-origin"));
+                assert_eq!(Some("true"), value(&response, "access-control-allow-credentials"));
+                assert_eq!(Some("Origin"), value(&response, "vary"));
+            }
+            decision => panic!("unexpected {:?}", decision),
+        }
+    }
+
+    #[test]
+    fn test_credentials_any_origin() {
+        let mut policy = CorsPolicy::new();
+
+        policy.allow_any_origin();
+        policy.set_allow_credentials(true);
+
+        for origin in &["https://evil.test", "null"] {
+            match policy.evaluate(&Method::Get, &request(origin, None, None)) {
+                CorsDecision::Allowed(response) => {
+                    assert_eq!(Some("*"), value(&response, "access-control-allow-origin"));
+                    assert_eq!(None, value(&response, "access-control-allow-credentials"));
+                    assert_eq!(None, value(&response, "vary"));
+                }
+                decision => panic!("unexpected {:?}", decision),
+            }
+        }
+    }
+
+    #[test]
+    fn test_credentials_null_origin() {
+        let mut policy = CorsPolicy::new();
+
+        policy.allow_origin("null");
+        policy.set_allow_credentials(true);
+
+        match policy.evaluate(&Method::Get, &request("null", None, None)) {
+            CorsDecision::Rejected(response) => {
+                assert_eq!(Some("Origin"), value(&response, "vary"));
+            }
+            decision => panic!("unexpected {:?}", decision),
+        }
+
+        policy.set_allow_credentials(false);
+
+        match policy.evaluate(&Method::Get, &request("null", None, None)) {
+            CorsDecision::Allowed(response) => {
+                assert_eq!(Some("null"), value(&response, "access-control-allow-origin"));
+            }
+            decision => panic!("unexpected {:?}", decision),
+        }
+    }
+}
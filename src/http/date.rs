@@ -0,0 +1,405 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+const LONG_WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday",
+                                  "Saturday", "Sunday"];
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep",
+                                "Oct", "Nov", "Dec"];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct HttpDateError;
+
+/// A timestamp as used in HTTP headers, with a precision of one second.
+/// [RFC7231, Section 7.1.1.1](http://www.iana.org/go/rfc7231)
+///
+/// All three formats are accepted when parsing,
+/// but the date is always formatted as an IMF-fixdate.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::date::HttpDate;
+///
+/// let date: HttpDate = "Sunday, 06-Nov-94 08:49:37 GMT".parse().unwrap();
+///
+/// assert_eq!("Sun, 06 Nov 1994 08:49:37 GMT", date.to_string());
+/// assert_eq!(date, "Sun Nov  6 08:49:37 1994".parse().unwrap());
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct HttpDate {
+    seconds: i64,
+}
+
+impl HttpDate {
+    pub fn now() -> Self {
+        HttpDate::from(SystemTime::now())
+    }
+
+    pub(crate) fn from_civil(civil: Civil) -> Self {
+        HttpDate { seconds: civil.to_unix() }
+    }
+
+    fn parse_imf_fixdate(value: &str) -> Option<Self> {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let (weekday, rest) = split_once(value, ", ")?;
+        let mut parts = rest.split(' ');
+        let day = digits(parts.next()?, 2)?;
+        let month = parse_month(parts.next()?)?;
+        let year = i64::from(digits(parts.next()?, 4)?);
+        let (hour, minute, second) = parse_time(parts.next()?)?;
+
+        if !WEEKDAYS.contains(&weekday) || parts.next()? != "GMT" || parts.next().is_some() {
+            return None;
+        }
+
+        Civil::new(year, month, day, hour, minute, second).map(HttpDate::from_civil)
+    }
+
+    fn parse_rfc850(value: &str, now: i64) -> Option<Self> {
+        // Sunday, 06-Nov-94 08:49:37 GMT
+        let (weekday, rest) = split_once(value, ", ")?;
+        let mut parts = rest.split(' ');
+        let mut date = parts.next()?.split('-');
+        let day = digits(date.next()?, 2)?;
+        let month = parse_month(date.next()?)?;
+        let year = i64::from(digits(date.next()?, 2)?);
+        let (hour, minute, second) = parse_time(parts.next()?)?;
+
+        if !LONG_WEEKDAYS.contains(&weekday) || date.next().is_some() ||
+           parts.next()? != "GMT" || parts.next().is_some() {
+            return None;
+        }
+
+        // A two digit year more than 50 years in the future
+        // is interpreted as the most recent year in the past
+        // that had the same last two digits.
+        let current_year = Civil::from_unix(now).year;
+        let mut year = current_year - current_year % 100 + year;
+        let civil = Civil::new(year, month, day, hour, minute, second)?;
+
+        if civil.to_unix() - now > 50 * 365 * SECONDS_PER_DAY {
+            year -= 100;
+        }
+
+        Civil::new(year, month, day, hour, minute, second).map(HttpDate::from_civil)
+    }
+
+    fn parse_asctime(value: &str) -> Option<Self> {
+        // Sun Nov  6 08:49:37 1994
+        if value.len() != 24 || !value.is_ascii() {
+            return None;
+        }
+
+        let bytes = value.as_bytes();
+        let day = &value[8..10];
+        let day = match day.strip_prefix(' ') {
+            Some(day) => digits(day, 1)?,
+            None => digits(day, 2)?,
+        };
+
+        if !WEEKDAYS.contains(&&value[..3]) ||
+           [bytes[3], bytes[7], bytes[10], bytes[19]] != [b' '; 4] {
+            return None;
+        }
+
+        let (hour, minute, second) = parse_time(&value[11..19])?;
+        let year = i64::from(digits(&value[20..], 4)?);
+
+        Civil::new(year, parse_month(&value[4..7])?, day, hour, minute, second)
+            .map(HttpDate::from_civil)
+    }
+}
+
+impl FromStr for HttpDate {
+    type Err = HttpDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HttpDate::parse_imf_fixdate(s)
+            .or_else(|| HttpDate::parse_rfc850(s, to_unix(SystemTime::now())))
+            .or_else(|| HttpDate::parse_asctime(s))
+            .ok_or(HttpDateError)
+    }
+}
+
+impl fmt::Display for HttpDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Civil::from_unix(self.seconds).to_imf_fixdate())
+    }
+}
+
+impl From<SystemTime> for HttpDate {
+    fn from(time: SystemTime) -> Self {
+        HttpDate { seconds: to_unix(time) }
+    }
+}
+
+impl From<HttpDate> for SystemTime {
+    fn from(date: HttpDate) -> Self {
+        from_unix(date.seconds)
+    }
+}
+
+/// A broken down UTC timestamp.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Civil {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl Civil {
+    /// Returns `None` if any of the fields is out of range.
+    pub fn new(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Option<Self> {
+        let civil = Civil {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        };
+
+        if civil.is_valid() { Some(civil) } else { None }
+    }
+
+    pub fn from_unix(seconds: i64) -> Self {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let rest = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        Civil {
+            year,
+            month,
+            day,
+            hour: (rest / 3600) as u32,
+            minute: (rest % 3600 / 60) as u32,
+            second: (rest % 60) as u32,
+        }
+    }
+
+    pub fn to_unix(self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * SECONDS_PER_DAY +
+        i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second)
+    }
+
+    /// Checks the ranges of all fields, including the length of the month.
+    pub fn is_valid(self) -> bool {
+        self.month >= 1 && self.month <= 12 && self.day >= 1 &&
+        self.day <= days_in_month(self.year, self.month) && self.hour < 24 &&
+        self.minute < 60 && self.second < 60
+    }
+
+    /// Day of the week, `0` being Monday.
+    pub fn weekday(self) -> usize {
+        // 1970-01-01 was a Thursday
+        (days_from_civil(self.year, self.month, self.day) + 3).rem_euclid(7) as usize
+    }
+
+    /// Formats the timestamp as an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+    pub fn to_imf_fixdate(self) -> String {
+        format!("{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+                WEEKDAYS[self.weekday()],
+                self.day,
+                MONTHS[self.month as usize - 1],
+                self.year,
+                self.hour,
+                self.minute,
+                self.second)
+    }
+}
+
+fn to_unix(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(err) => {
+            let duration = err.duration();
+            let seconds = duration.as_secs() as i64;
+
+            if duration.subsec_nanos() > 0 {
+                -seconds - 1
+            } else {
+                -seconds
+            }
+        }
+    }
+}
+
+fn from_unix(seconds: i64) -> SystemTime {
+    if seconds >= 0 {
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    }
+}
+
+/// Looks up a three letter month name, ignoring case.
+pub(crate) fn parse_month(value: &str) -> Option<u32> {
+    MONTHS.iter()
+        .position(|month| month.eq_ignore_ascii_case(value))
+        .map(|index| index as u32 + 1)
+}
+
+fn split_once<'a>(value: &'a str, separator: &str) -> Option<(&'a str, &'a str)> {
+    value.find(separator).map(|index| (&value[..index], &value[index + separator.len()..]))
+}
+
+/// Parses exactly `length` digits.
+fn digits(value: &str, length: usize) -> Option<u32> {
+    if value.len() != length || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    value.parse().ok()
+}
+
+fn parse_time(value: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = value.split(':');
+    let hour = digits(parts.next()?, 2)?;
+    let minute = digits(parts.next()?, 2)?;
+    let second = digits(parts.next()?, 2)?;
+
+    match parts.next() {
+        Some(_) => None,
+        None => Some((hour, minute, second)),
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// The following two conversions are taken from
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 +
+                      i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 -
+                       day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_civil_roundtrip() {
+        let civil = Civil::from_unix(784_111_777);
+
+        assert_eq!(Civil {
+                       year: 1994,
+                       month: 11,
+                       day: 6,
+                       hour: 8,
+                       minute: 49,
+                       second: 37,
+                   },
+                   civil);
+        assert_eq!(784_111_777, civil.to_unix());
+        assert_eq!("Sun, 06 Nov 1994 08:49:37 GMT", civil.to_imf_fixdate());
+    }
+
+    #[test]
+    fn test_before_epoch() {
+        let civil = Civil::from_unix(-1);
+
+        assert_eq!("Wed, 31 Dec 1969 23:59:59 GMT", civil.to_imf_fixdate());
+        assert_eq!(-1, to_unix(from_unix(-1)));
+    }
+
+    #[test]
+    fn test_parse_formats() {
+        let expected = HttpDate { seconds: 784_111_777 };
+
+        assert_eq!(Ok(expected), "Sun, 06 Nov 1994 08:49:37 GMT".parse());
+        assert_eq!(Ok(expected), "Sunday, 06-Nov-94 08:49:37 GMT".parse());
+        assert_eq!(Ok(expected), "Sun Nov  6 08:49:37 1994".parse());
+        assert_eq!(Ok(HttpDate { seconds: 1_510_737_000 }),
+                   "Wed Nov 15 09:10:00 2017".parse());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let invalid = ["",
+                       "0",
+                       "Sun, 06 Nov 1994 08:49:37 UTC",
+                       "Sun, 6 Nov 1994 08:49:37 GMT",
+                       "Sun, 31 Feb 1994 08:49:37 GMT",
+                       "Sun, 06 Nov 1994 24:00:00 GMT",
+                       "Sun, 06-Nov-94 08:49:37 GMT",
+                       "Sun Nov 6 08:49:37 1994"];
+
+        for value in invalid.iter() {
+            assert_eq!(Err(HttpDateError), value.parse::<HttpDate>(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_rfc850_century() {
+        let now = Civil::new(2026, 10, 19, 0, 0, 0).unwrap().to_unix();
+
+        let recent = HttpDate::parse_rfc850("Monday, 01-Jan-35 00:00:00 GMT", now).unwrap();
+        let past = HttpDate::parse_rfc850("Friday, 01-Jan-99 00:00:00 GMT", now).unwrap();
+
+        assert_eq!("Mon, 01 Jan 2035 00:00:00 GMT", recent.to_string());
+        assert_eq!("Fri, 01 Jan 1999 00:00:00 GMT", past.to_string());
+    }
+
+    #[test]
+    fn test_system_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(784_111_777_500);
+        let date = HttpDate::from(time);
+
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(784_111_777),
+                   SystemTime::from(date));
+    }
+
+    #[test]
+    fn test_is_valid() {
+        let mut civil = Civil::from_unix(0);
+
+        civil.month = 2;
+        civil.day = 29;
+        assert!(!civil.is_valid());
+
+        civil.year = 2000;
+        assert!(civil.is_valid());
+    }
+}
@@ -0,0 +1,515 @@
+//! The HTTP Digest Access Authentication scheme.
+//! [RFC7616](http://www.iana.org/go/rfc7616)
+//!
+//! Only the `auth` quality of protection is supported, `auth-int` is not.
+//!
+//! # Examples
+//!
+//! ```
+//! use teapot::http::digest::{DigestAlgorithm, DigestChallenge, DigestCredentials};
+//! use teapot::http::method::Method;
+//!
+//! let challenge = DigestChallenge::new("api@example.org", "dcd98b7102dd2f0e",
+//!                                      DigestAlgorithm::Sha256);
+//!
+//! // The client answers the challenge ...
+//! let credentials = DigestCredentials::new(&challenge, "Mufasa", "Circle of Life",
+//!                                          &Method::Get, "/dir/index.html",
+//!                                          "0a4f113b", 1);
+//!
+//! // ... and the server checks the response against the challenge it issued
+//! assert!(credentials.verify_challenge(&challenge, &Method::Get, "Circle of Life"));
+//! assert!(!credentials.verify_challenge(&challenge, &Method::Post, "Circle of Life"));
+//! ```
+
+use std::fmt;
+use super::headers::{AuthParams, Challenge, Credentials};
+use super::method::Method;
+use super::super::hash;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DigestAlgorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    /// Algorithm names are compared case-insensitively.
+    /// A missing `algorithm` parameter means `MD5`.
+    fn parse(value: Option<&str>) -> Option<Self> {
+        let value = match value {
+            Some(value) => value.to_ascii_uppercase(),
+            None => return Some(DigestAlgorithm::Md5),
+        };
+
+        match value.as_str() {
+            "MD5" => Some(DigestAlgorithm::Md5),
+            "MD5-SESS" => Some(DigestAlgorithm::Md5Sess),
+            "SHA-256" => Some(DigestAlgorithm::Sha256),
+            "SHA-256-SESS" => Some(DigestAlgorithm::Sha256Sess),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            DigestAlgorithm::Md5 => "MD5",
+            DigestAlgorithm::Md5Sess => "MD5-sess",
+            DigestAlgorithm::Sha256 => "SHA-256",
+            DigestAlgorithm::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session(self) -> bool {
+        self == DigestAlgorithm::Md5Sess || self == DigestAlgorithm::Sha256Sess
+    }
+
+    fn hash(self, data: &str) -> String {
+        match self {
+            DigestAlgorithm::Md5 | DigestAlgorithm::Md5Sess => {
+                hash::to_hex(&hash::md5(data.as_bytes()))
+            }
+            DigestAlgorithm::Sha256 | DigestAlgorithm::Sha256Sess => {
+                hash::to_hex(&hash::sha256(data.as_bytes()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A `Digest` challenge, offering the `auth` quality of protection.
+/// [RFC7616, Section 3.3](http://www.iana.org/go/rfc7616)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DigestChallenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: DigestAlgorithm,
+    qop: bool,
+    stale: bool,
+}
+
+impl DigestChallenge {
+    /// The `nonce` must be generated by the server and should be unique for each challenge.
+    pub fn new<R: Into<String>, N: Into<String>>(realm: R,
+                                                 nonce: N,
+                                                 algorithm: DigestAlgorithm)
+                                                 -> Self {
+        DigestChallenge {
+            realm: realm.into(),
+            nonce: nonce.into(),
+            opaque: None,
+            algorithm,
+            qop: true,
+            stale: false,
+        }
+    }
+
+    /// Reads a `Digest` challenge. Returns `None` if the scheme does not match,
+    /// required parameters are missing or the algorithm is not supported.
+    pub fn from_challenge(challenge: &Challenge) -> Option<Self> {
+        if !challenge.is_scheme("Digest") {
+            return None;
+        }
+
+        let qop = match challenge.param("qop") {
+            Some(qop) => {
+                let offers_auth = qop.split(',').any(|qop| qop.trim().eq_ignore_ascii_case("auth"));
+
+                if !offers_auth {
+                    return None;
+                }
+
+                true
+            }
+            None => false,
+        };
+
+        Some(DigestChallenge {
+            realm: challenge.realm()?.to_string(),
+            nonce: challenge.param("nonce")?.to_string(),
+            opaque: challenge.param("opaque").map(|opaque| opaque.to_string()),
+            algorithm: DigestAlgorithm::parse(challenge.param("algorithm"))?,
+            qop,
+            stale: challenge.param("stale").is_some_and(|stale| stale.eq_ignore_ascii_case("true")),
+        })
+    }
+
+    pub fn to_challenge(&self) -> Challenge {
+        let mut params = vec![("realm".to_string(), self.realm.clone())];
+
+        if self.qop {
+            params.push(("qop".to_string(), "auth".to_string()));
+        }
+
+        params.push(("algorithm".to_string(), self.algorithm.to_string()));
+        params.push(("nonce".to_string(), self.nonce.clone()));
+
+        if let Some(ref opaque) = self.opaque {
+            params.push(("opaque".to_string(), opaque.clone()));
+        }
+
+        if self.stale {
+            params.push(("stale".to_string(), "true".to_string()));
+        }
+
+        Challenge::new("Digest", AuthParams::Params(params))
+    }
+
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    pub fn set_opaque<S: Into<String>>(&mut self, opaque: Option<S>) {
+        self.opaque = opaque.map(|opaque| opaque.into());
+    }
+
+    /// A stale challenge tells the client that only the nonce was rejected
+    /// and it may retry without asking the user again.
+    pub fn stale(&self) -> bool {
+        self.stale
+    }
+
+    pub fn set_stale(&mut self, stale: bool) {
+        self.stale = stale;
+    }
+}
+
+/// `Digest` credentials.
+/// [RFC7616, Section 3.4](http://www.iana.org/go/rfc7616)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DigestCredentials {
+    username: String,
+    realm: String,
+    nonce: String,
+    uri: String,
+    response: String,
+    algorithm: DigestAlgorithm,
+    opaque: Option<String>,
+    /// `cnonce` and `nc`, only present when `qop=auth` is used
+    qop: Option<(String, String)>,
+}
+
+impl DigestCredentials {
+    /// Answers `challenge` for a request with `method` to `uri`.
+    /// `cnonce` should be a random value and `nonce_count` must be incremented
+    /// for every request with the same nonce.
+    pub fn new(challenge: &DigestChallenge,
+               username: &str,
+               password: &str,
+               method: &Method,
+               uri: &str,
+               cnonce: &str,
+               nonce_count: u32)
+               -> Self {
+        let mut credentials = DigestCredentials {
+            username: username.to_string(),
+            realm: challenge.realm.clone(),
+            nonce: challenge.nonce.clone(),
+            uri: uri.to_string(),
+            response: String::new(),
+            algorithm: challenge.algorithm,
+            opaque: challenge.opaque.clone(),
+            qop: if challenge.qop {
+                Some((cnonce.to_string(), format!("{:08x}", nonce_count)))
+            } else {
+                None
+            },
+        };
+
+        credentials.response = credentials.compute_response(method, password);
+        credentials
+    }
+
+    /// Reads `Digest` credentials. Returns `None` if the scheme does not match,
+    /// required parameters are missing or the algorithm is not supported.
+    pub fn from_credentials(credentials: &Credentials) -> Option<Self> {
+        if !credentials.is_scheme("Digest") {
+            return None;
+        }
+
+        let param = |name| credentials.param(name).map(|value| value.to_string());
+
+        let qop = match credentials.param("qop") {
+            Some(qop) if qop.eq_ignore_ascii_case("auth") => Some((param("cnonce")?, param("nc")?)),
+            Some(_) => return None,
+            None => None,
+        };
+
+        Some(DigestCredentials {
+            username: param("username")?,
+            realm: param("realm")?,
+            nonce: param("nonce")?,
+            uri: param("uri")?,
+            response: param("response")?,
+            algorithm: DigestAlgorithm::parse(credentials.param("algorithm"))?,
+            opaque: param("opaque"),
+            qop,
+        })
+    }
+
+    pub fn to_credentials(&self) -> Credentials {
+        let mut params = vec![("username".to_string(), self.username.clone()),
+                              ("realm".to_string(), self.realm.clone()),
+                              ("uri".to_string(), self.uri.clone()),
+                              ("algorithm".to_string(), self.algorithm.to_string()),
+                              ("nonce".to_string(), self.nonce.clone())];
+
+        if let Some((ref cnonce, ref nc)) = self.qop {
+            params.push(("nc".to_string(), nc.clone()));
+            params.push(("cnonce".to_string(), cnonce.clone()));
+            params.push(("qop".to_string(), "auth".to_string()));
+        }
+
+        params.push(("response".to_string(), self.response.clone()));
+
+        if let Some(ref opaque) = self.opaque {
+            params.push(("opaque".to_string(), opaque.clone()));
+        }
+
+        Credentials::new("Digest", AuthParams::Params(params))
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    pub fn nonce(&self) -> &str {
+        &self.nonce
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    pub fn response(&self) -> &str {
+        &self.response
+    }
+
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        self.algorithm
+    }
+
+    pub fn opaque(&self) -> Option<&str> {
+        self.opaque.as_deref()
+    }
+
+    /// Checks that the credentials answer `challenge`, which the server issued,
+    /// before checking the response against the password of the user.
+    /// The realm, nonce, opaque value, algorithm and quality of protection
+    /// must match, so a client can't downgrade to a weaker algorithm or skip `qop`.
+    /// The server is still responsible for tracking the nonce count.
+    pub fn verify_challenge(&self,
+                            challenge: &DigestChallenge,
+                            method: &Method,
+                            password: &str)
+                            -> bool {
+        self.realm == challenge.realm && self.nonce == challenge.nonce &&
+        self.opaque == challenge.opaque && self.algorithm == challenge.algorithm &&
+        self.qop.is_some() == challenge.qop && self.verify(method, password)
+    }
+
+    /// Checks the response against the password of the user.
+    /// The server is responsible for validating the nonce, nonce count and realm,
+    /// see [`verify_challenge`].
+    ///
+    /// [`verify_challenge`]: #method.verify_challenge
+    pub fn verify(&self, method: &Method, password: &str) -> bool {
+        let expected = self.compute_response(method, password);
+
+        // Compare without returning early to avoid leaking timing information
+        expected.len() == self.response.len() &&
+        expected.bytes()
+            .zip(self.response.to_ascii_lowercase().bytes())
+            .fold(0, |result, (a, b)| result | (a ^ b)) == 0
+    }
+
+    fn compute_response(&self, method: &Method, password: &str) -> String {
+        let algorithm = self.algorithm;
+        let mut ha1 = algorithm.hash(&format!("{}:{}:{}", self.username, self.realm, password));
+        let ha2 = algorithm.hash(&format!("{}:{}", method, self.uri));
+
+        match self.qop {
+            Some((ref cnonce, ref nc)) => {
+                if algorithm.is_session() {
+                    ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+                }
+
+                algorithm.hash(&format!("{}:{}:{}:{}:auth:{}", ha1, self.nonce, nc, cnonce, ha2))
+            }
+            None => algorithm.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::{AuthorizationHeader, RawHeader, TypedHeader,
+                                WwwAuthenticateHeader};
+
+    const NONCE: &str = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn credentials(algorithm: DigestAlgorithm) -> DigestCredentials {
+        let mut challenge = DigestChallenge::new("http-auth@example.org", NONCE, algorithm);
+
+        challenge.set_opaque(Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS"));
+
+        DigestCredentials::new(&challenge,
+                               "Mufasa",
+                               "Circle of Life",
+                               &Method::Get,
+                               "/dir/index.html",
+                               CNONCE,
+                               1)
+    }
+
+    // Examples from RFC7616, Section 3.9.1
+    #[test]
+    fn test_rfc_examples() {
+        assert_eq!("8ca523f5e9506fed4657c9700eebdbec",
+                   credentials(DigestAlgorithm::Md5).response());
+        assert_eq!("753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1",
+                   credentials(DigestAlgorithm::Sha256).response());
+    }
+
+    #[test]
+    fn test_verify() {
+        for &algorithm in [DigestAlgorithm::Md5,
+                           DigestAlgorithm::Md5Sess,
+                           DigestAlgorithm::Sha256,
+                           DigestAlgorithm::Sha256Sess]
+            .iter() {
+            let credentials = credentials(algorithm);
+
+            assert!(credentials.verify(&Method::Get, "Circle of Life"));
+            assert!(!credentials.verify(&Method::Get, "circle of life"));
+        }
+    }
+
+    #[test]
+    fn test_verify_challenge() {
+        let mut challenge = DigestChallenge::new("http-auth@example.org",
+                                                 NONCE,
+                                                 DigestAlgorithm::Sha256);
+
+        challenge.set_opaque(Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS"));
+
+        let verify = |credentials: &DigestCredentials| {
+            credentials.verify_challenge(&challenge, &Method::Get, "Circle of Life")
+        };
+
+        assert!(verify(&credentials(DigestAlgorithm::Sha256)));
+        assert!(!verify(&credentials(DigestAlgorithm::Md5)));
+
+        let mut other = challenge.clone();
+
+        other.set_opaque(None::<String>);
+
+        assert!(!credentials(DigestAlgorithm::Sha256)
+            .verify_challenge(&other, &Method::Get, "Circle of Life"));
+
+        let mut without_qop = challenge.clone();
+
+        without_qop.qop = false;
+
+        let downgraded = DigestCredentials::new(&without_qop,
+                                                "Mufasa",
+                                                "Circle of Life",
+                                                &Method::Get,
+                                                "/dir/index.html",
+                                                CNONCE,
+                                                1);
+
+        assert!(downgraded.verify(&Method::Get, "Circle of Life"));
+        assert!(!verify(&downgraded));
+
+        let other_nonce = DigestChallenge::new("http-auth@example.org",
+                                               "other",
+                                               DigestAlgorithm::Sha256);
+
+        assert!(!credentials(DigestAlgorithm::Sha256)
+            .verify_challenge(&other_nonce, &Method::Get, "Circle of Life"));
+    }
+
+    #[test]
+    fn test_without_qop() {
+        let header = WwwAuthenticateHeader::parse(&[&RawHeader::new("WWW-Authenticate",
+                                                                    "Digest \
+                                                                     realm=\"testrealm@host.com\", \
+                                                                     nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\"")])
+            .unwrap();
+        let challenge = DigestChallenge::from_challenge(&header.challenges()[0]).unwrap();
+        let credentials = DigestCredentials::new(&challenge,
+                                                 "Mufasa",
+                                                 "Circle Of Life",
+                                                 &Method::Get,
+                                                 "/dir/index.html",
+                                                 "",
+                                                 1);
+
+        // The inputs of the RFC2069 example, whose published response is known to be wrong
+        assert_eq!("670fd8c2df070c60b045671b8b24ff02", credentials.response());
+        assert!(!credentials.to_credentials().to_string().contains("qop"));
+    }
+
+    #[test]
+    fn test_headers_roundtrip() {
+        let header = WwwAuthenticateHeader::new(vec![DigestChallenge::new("realm",
+                                                                          "abc",
+                                                                          DigestAlgorithm::Sha256)
+                                                         .to_challenge()]);
+
+        assert_eq!(vec!["Digest realm=\"realm\", qop=\"auth\", algorithm=SHA-256, \
+                         nonce=\"abc\""
+                            .to_string()],
+                   header.raw_values());
+
+        let original = credentials(DigestAlgorithm::Md5Sess);
+        let raw = AuthorizationHeader::new(original.to_credentials()).to_raw();
+        let parsed = AuthorizationHeader::parse(&[&raw[0]]).unwrap();
+
+        assert_eq!(Some(original),
+                   DigestCredentials::from_credentials(parsed.credentials()));
+        assert!(raw[0].value().contains("algorithm=MD5-sess, nonce="));
+        assert!(raw[0].value().contains("nc=00000001, cnonce="));
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let challenge = Challenge::new("Digest",
+                                       AuthParams::Params(vec![("realm".to_string(),
+                                                                "a".to_string()),
+                                                               ("nonce".to_string(),
+                                                                "b".to_string()),
+                                                               ("qop".to_string(),
+                                                                "auth-int".to_string())]));
+
+        assert_eq!(None, DigestChallenge::from_challenge(&challenge));
+        assert_eq!(None, DigestChallenge::from_challenge(&Challenge::basic("a")));
+    }
+}
@@ -0,0 +1,431 @@
+//! Content codings for message bodies.
+//!
+//! Codecs for `gzip` and `deflate` are available with the `gzip` and `deflate`
+//! features, `br` with the `brotli` feature.
+//! [RFC7231, Section 3.1.2](http://www.iana.org/go/rfc7231)
+
+use std::io::{self, Read, Write};
+use super::headers::{AcceptEncodingHeader, ContentCoding};
+
+/// The body uses a coding that has no codec in the [`CodecRegistry`].
+///
+/// [`CodecRegistry`]: struct.CodecRegistry.html
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnsupportedCoding {
+    coding: ContentCoding,
+}
+
+impl UnsupportedCoding {
+    pub fn coding(&self) -> &ContentCoding {
+        &self.coding
+    }
+}
+
+/// A writer that encodes everything written to it.
+pub trait Encoder: Write {
+    /// Writes the remaining data including any trailer required by the coding.
+    /// Dropping an encoder without calling `finish` may produce a truncated body.
+    fn finish(self: Box<Self>) -> io::Result<()>;
+}
+
+/// Wraps body streams to decode and encode a single content coding.
+pub trait Codec {
+    fn coding(&self) -> ContentCoding;
+
+    fn decoder<'a>(&self, inner: Box<dyn Read + 'a>) -> Box<dyn Read + 'a>;
+
+    fn encoder<'a>(&self, inner: Box<dyn Write + 'a>) -> Box<dyn Encoder + 'a>;
+}
+
+struct IdentityEncoder<W> {
+    inner: W,
+}
+
+impl<W: Write> Write for IdentityEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Encoder for IdentityEncoder<W> {
+    fn finish(mut self: Box<Self>) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The available codecs. `identity` is always supported.
+///
+/// The default registry contains all codecs enabled by features.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use teapot::http::encoding::CodecRegistry;
+/// use teapot::http::headers::ContentCoding;
+///
+/// let registry = CodecRegistry::new();
+/// let mut body = String::new();
+///
+/// registry.decode(&[ContentCoding::Identity], Box::new("Hello".as_bytes()))
+///     .unwrap()
+///     .read_to_string(&mut body)
+///     .unwrap();
+///
+/// assert_eq!("Hello", body);
+/// assert!(registry.decode(&[ContentCoding::Compress], Box::new("".as_bytes())).is_err());
+/// ```
+pub struct CodecRegistry {
+    codecs: Vec<Box<dyn Codec>>,
+}
+
+impl Default for CodecRegistry {
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = CodecRegistry::new();
+
+        #[cfg(feature = "gzip")]
+        registry.register(Box::new(flate::Gzip));
+
+        #[cfg(feature = "deflate")]
+        registry.register(Box::new(flate::Deflate));
+
+        #[cfg(feature = "brotli")]
+        registry.register(Box::new(brotli::Brotli));
+
+        registry
+    }
+}
+
+impl CodecRegistry {
+    /// Creates a registry that only supports `identity`.
+    pub fn new() -> Self {
+        CodecRegistry { codecs: Vec::new() }
+    }
+
+    /// Registers `codec`, replacing any codec for the same coding.
+    pub fn register(&mut self, codec: Box<dyn Codec>) {
+        let coding = codec.coding();
+
+        self.codecs.retain(|other| other.coding() != coding);
+        self.codecs.push(codec);
+    }
+
+    pub fn get(&self, coding: &ContentCoding) -> Option<&dyn Codec> {
+        self.codecs.iter().find(|codec| codec.coding() == *coding).map(|codec| &**codec)
+    }
+
+    /// The supported codings other than `identity`, in order of registration.
+    pub fn codings(&self) -> Vec<ContentCoding> {
+        self.codecs.iter().map(|codec| codec.coding()).collect()
+    }
+
+    /// Wraps `body` to undo `codings`, which are in the order they were applied
+    /// (as listed by `Content-Encoding`).
+    pub fn decode<'a>(&self,
+                      codings: &[ContentCoding],
+                      body: Box<dyn Read + 'a>)
+                      -> Result<Box<dyn Read + 'a>, UnsupportedCoding> {
+        codings.iter().rev().try_fold(body, |body, coding| {
+            if *coding == ContentCoding::Identity {
+                return Ok(body);
+            }
+
+            match self.get(coding) {
+                Some(codec) => Ok(codec.decoder(body)),
+                None => Err(UnsupportedCoding { coding: coding.clone() }),
+            }
+        })
+    }
+
+    pub fn encode<'a>(&self,
+                      coding: &ContentCoding,
+                      body: Box<dyn Write + 'a>)
+                      -> Result<Box<dyn Encoder + 'a>, UnsupportedCoding> {
+        if *coding == ContentCoding::Identity {
+            return Ok(Box::new(IdentityEncoder { inner: body }));
+        }
+
+        match self.get(coding) {
+            Some(codec) => Ok(codec.encoder(body)),
+            None => Err(UnsupportedCoding { coding: coding.clone() }),
+        }
+    }
+
+    /// Selects the coding for a response, using the q-values of `accept`.
+    /// Responses to requests without `Accept-Encoding` are not encoded.
+    /// Returns `None` if no supported coding is acceptable.
+    pub fn negotiate(&self, accept: Option<&AcceptEncodingHeader>) -> Option<ContentCoding> {
+        match accept {
+            Some(accept) => accept.negotiate(&self.codings()),
+            None => Some(ContentCoding::Identity),
+        }
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "deflate"))]
+mod flate {
+    use std::io::{self, Read, Write};
+    use flate2::Compression;
+    #[cfg(feature = "gzip")]
+    use flate2::read::GzDecoder;
+    #[cfg(feature = "deflate")]
+    use flate2::read::ZlibDecoder;
+    #[cfg(feature = "gzip")]
+    use flate2::write::GzEncoder;
+    #[cfg(feature = "deflate")]
+    use flate2::write::ZlibEncoder;
+    use super::{Codec, Encoder};
+    use super::super::headers::ContentCoding;
+
+    #[cfg(feature = "gzip")]
+    pub struct Gzip;
+
+    #[cfg(feature = "gzip")]
+    impl Codec for Gzip {
+        fn coding(&self) -> ContentCoding {
+            ContentCoding::Gzip
+        }
+
+        fn decoder<'a>(&self, inner: Box<dyn Read + 'a>) -> Box<dyn Read + 'a> {
+            Box::new(GzDecoder::new(inner))
+        }
+
+        fn encoder<'a>(&self, inner: Box<dyn Write + 'a>) -> Box<dyn Encoder + 'a> {
+            Box::new(GzEncoder::new(inner, Compression::default()))
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    impl<W: Write> Encoder for GzEncoder<W> {
+        fn finish(self: Box<Self>) -> io::Result<()> {
+            GzEncoder::finish(*self).map(|_| ())
+        }
+    }
+
+    /// `deflate` is the zlib format of
+    /// [RFC1950](http://www.iana.org/go/rfc1950), not raw deflate.
+    #[cfg(feature = "deflate")]
+    pub struct Deflate;
+
+    #[cfg(feature = "deflate")]
+    impl Codec for Deflate {
+        fn coding(&self) -> ContentCoding {
+            ContentCoding::Deflate
+        }
+
+        fn decoder<'a>(&self, inner: Box<dyn Read + 'a>) -> Box<dyn Read + 'a> {
+            Box::new(ZlibDecoder::new(inner))
+        }
+
+        fn encoder<'a>(&self, inner: Box<dyn Write + 'a>) -> Box<dyn Encoder + 'a> {
+            Box::new(ZlibEncoder::new(inner, Compression::default()))
+        }
+    }
+
+    #[cfg(feature = "deflate")]
+    impl<W: Write> Encoder for ZlibEncoder<W> {
+        fn finish(self: Box<Self>) -> io::Result<()> {
+            ZlibEncoder::finish(*self).map(|_| ())
+        }
+    }
+}
+
+#[cfg(feature = "brotli")]
+mod brotli {
+    use std::io::{self, Read, Write};
+    use brotli_crate::{CompressorWriter, Decompressor};
+    use super::{Codec, Encoder};
+    use super::super::headers::ContentCoding;
+
+    const BUFFER_SIZE: usize = 4096;
+    const QUALITY: u32 = 5;
+    const WINDOW_SIZE: u32 = 22;
+
+    pub struct Brotli;
+
+    impl Codec for Brotli {
+        fn coding(&self) -> ContentCoding {
+            ContentCoding::Br
+        }
+
+        fn decoder<'a>(&self, inner: Box<dyn Read + 'a>) -> Box<dyn Read + 'a> {
+            Box::new(Decompressor::new(inner, BUFFER_SIZE))
+        }
+
+        fn encoder<'a>(&self, inner: Box<dyn Write + 'a>) -> Box<dyn Encoder + 'a> {
+            let inner = CheckedWriter {
+                inner,
+                error: None,
+            };
+
+            Box::new(CompressorWriter::new(inner, BUFFER_SIZE, QUALITY, WINDOW_SIZE))
+        }
+    }
+
+    /// Keeps the first write error, since the compressor discards
+    /// errors while writing the end of the stream.
+    struct CheckedWriter<W> {
+        inner: W,
+        error: Option<io::Error>,
+    }
+
+    impl<W: Write> Write for CheckedWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.inner.write(buf).map_err(|err| {
+                let kind = err.kind();
+
+                self.error.get_or_insert(err);
+                io::Error::from(kind)
+            })
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl<W: Write> Encoder for CompressorWriter<CheckedWriter<W>> {
+        fn finish(mut self: Box<Self>) -> io::Result<()> {
+            self.flush()?;
+
+            // Consuming the writer completes the stream
+            let mut inner = self.into_inner();
+
+            match inner.error.take() {
+                Some(err) => Err(err),
+                None => inner.flush(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::slice;
+    use super::*;
+
+    fn round_trip(registry: &CodecRegistry, coding: &ContentCoding) -> Vec<u8> {
+        let input = "teapot ".repeat(100);
+        let mut encoded = vec![];
+
+        {
+            let mut encoder = registry.encode(coding, Box::new(&mut encoded)).unwrap();
+
+            encoder.write_all(input.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        }
+
+        let mut decoded = vec![];
+
+        registry.decode(slice::from_ref(coding), Box::new(encoded.as_slice()))
+            .unwrap()
+            .read_to_end(&mut decoded)
+            .unwrap();
+
+        assert_eq!(input.as_bytes(), decoded.as_slice());
+
+        encoded
+    }
+
+    #[test]
+    fn test_identity() {
+        let registry = CodecRegistry::new();
+
+        assert_eq!("teapot ".repeat(100).into_bytes(),
+                   round_trip(&registry, &ContentCoding::Identity));
+        assert_eq!(Err(UnsupportedCoding { coding: ContentCoding::Gzip }),
+                   registry.encode(&ContentCoding::Gzip, Box::new(vec![])).map(|_| ()));
+    }
+
+    #[test]
+    fn test_negotiate() {
+        use super::super::headers::{RawHeader, TypedHeader};
+
+        let registry = CodecRegistry::default();
+        let accept = AcceptEncodingHeader::parse(&[&RawHeader::new("Accept-Encoding",
+                                                                   "br, gzip, deflate")])
+            .unwrap();
+        let expected = registry.codings().first().cloned().unwrap_or(ContentCoding::Identity);
+
+        assert_eq!(Some(expected), registry.negotiate(Some(&accept)));
+        assert_eq!(Some(ContentCoding::Identity), registry.negotiate(None));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip() {
+        let encoded = round_trip(&CodecRegistry::default(), &ContentCoding::Gzip);
+
+        assert_eq!(&[0x1f, 0x8b], &encoded[..2]);
+    }
+
+    #[cfg(feature = "deflate")]
+    #[test]
+    fn test_deflate() {
+        round_trip(&CodecRegistry::default(), &ContentCoding::Deflate);
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_brotli() {
+        round_trip(&CodecRegistry::default(), &ContentCoding::Br);
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_brotli_write_error() {
+        struct Full;
+
+        impl Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::StorageFull, "full"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let registry = CodecRegistry::default();
+        let mut encoder = registry.encode(&ContentCoding::Br, Box::new(Full)).unwrap();
+
+        encoder.write_all(b"teapot").unwrap();
+
+        assert_eq!(io::ErrorKind::StorageFull, encoder.finish().unwrap_err().kind());
+    }
+
+    #[cfg(all(feature = "gzip", feature = "deflate"))]
+    #[test]
+    fn test_stacked_codings() {
+        let registry = CodecRegistry::default();
+        let mut encoded = b"stacked".to_vec();
+
+        for coding in &[ContentCoding::Deflate, ContentCoding::Gzip] {
+            let mut output = vec![];
+
+            {
+                let mut encoder = registry.encode(coding, Box::new(&mut output)).unwrap();
+
+                encoder.write_all(&encoded).unwrap();
+                encoder.finish().unwrap();
+            }
+
+            encoded = output;
+        }
+
+        let mut decoded = String::new();
+
+        registry.decode(&[ContentCoding::Deflate, ContentCoding::Gzip],
+                    Box::new(encoded.as_slice()))
+            .unwrap()
+            .read_to_string(&mut decoded)
+            .unwrap();
+
+        assert_eq!("stacked", decoded);
+    }
+}
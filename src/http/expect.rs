@@ -0,0 +1,428 @@
+//! The `Expect: 100-continue` handshake, which lets a client find out whether
+//! the server would accept a request before sending a large body.
+//! [RFC7231, Section 5.1.1](http://www.iana.org/go/rfc7231)
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+use super::headers::{ExpectHeader, Headers, TypedHeader};
+use super::interim::{self, InterimResponse};
+use super::parse;
+use super::status::StatusCode;
+
+/// The outcome of [`check_expectation`].
+///
+/// [`check_expectation`]: fn.check_expectation.html
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ExpectDecision {
+    /// There is no expectation, the body can be read right away.
+    Proceed,
+    /// The client waits for [`write_continue`] before sending the body,
+    /// unless the server responds with a final status code instead.
+    ///
+    /// [`write_continue`]: fn.write_continue.html
+    Continue,
+    /// The expectation can't be met, respond with [`write_expectation_failed`]
+    /// without reading the body.
+    ///
+    /// [`write_expectation_failed`]: fn.write_expectation_failed.html
+    Fail,
+}
+
+/// Checks the `Expect` header of a request before its body is read.
+/// `version` is the HTTP version of the request line, e.g. `HTTP/1.1`.
+/// `100-continue` is ignored for HTTP/1.0 and older requests, since those clients
+/// don't wait for `100 Continue`.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::expect::{check_expectation, write_continue, ExpectDecision};
+/// use teapot::http::headers::{Headers, RawHeader};
+///
+/// let mut headers = Headers::new();
+/// let mut output = Vec::new();
+///
+/// headers.append_raw(RawHeader::new("Expect", "100-continue"));
+///
+/// if check_expectation("HTTP/1.1", &headers) == ExpectDecision::Continue {
+///     write_continue(&mut output).unwrap();
+/// }
+///
+/// assert_eq!(b"HTTP/1.1 100 Continue\r\n\r\n".to_vec(), output);
+/// ```
+pub fn check_expectation(version: &str, headers: &Headers) -> ExpectDecision {
+    if !headers.contains(ExpectHeader::name()) {
+        return ExpectDecision::Proceed;
+    }
+
+    match headers.get::<ExpectHeader>() {
+        Some(ref expect) if expect.is_continue() && is_before_http_11(version) => {
+            ExpectDecision::Proceed
+        }
+        Some(ref expect) if expect.is_continue() => ExpectDecision::Continue,
+        _ => ExpectDecision::Fail,
+    }
+}
+
+/// Checks whether `version` is `HTTP/1.0` or older.
+fn is_before_http_11(version: &str) -> bool {
+    let number = match version.strip_prefix("HTTP/") {
+        Some(number) => number,
+        None => return false,
+    };
+    let mut parts = number.splitn(2, '.').map(|part| part.parse::<u32>());
+
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => (major, minor) < (1, 1),
+        _ => false,
+    }
+}
+
+/// Writes the interim `100 Continue` response.
+pub fn write_continue(writer: &mut dyn Write) -> io::Result<()> {
+    InterimResponse::hundred_continue().write_to(writer)
+}
+
+/// Writes a `417 Expectation Failed` response. The connection is closed
+/// afterwards, since the body of the request was not read.
+pub fn write_expectation_failed(writer: &mut dyn Write) -> io::Result<()> {
+    write!(writer,
+           "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+           StatusCode::ExpectationFailed)?;
+    writer.flush()
+}
+
+/// A stream whose reads can time out.
+pub trait ReadTimeout: Read {
+    fn read_timeout(&self) -> io::Result<Option<Duration>>;
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        TcpStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+/// The outcome of [`await_continue`].
+///
+/// [`await_continue`]: fn.await_continue.html
+#[derive(Debug)]
+pub enum ContinueOutcome {
+    /// The server sent `100 Continue`, the body should be sent now.
+    Continue,
+    /// The server didn't respond in time. The body should be sent anyway,
+    /// since the server might not support the handshake.
+    TimedOut,
+    /// The server responded with a final status code, e.g. `417` or `401`,
+    /// or switched to another protocol with `101 Switching Protocols`.
+    /// The body must not be sent, the stream is positioned after the response head.
+    Final {
+        status: StatusCode,
+        headers: Headers,
+    },
+}
+
+/// Waits up to `timeout` for the response to a request sent with
+/// `Expect: 100-continue`, after the headers have been written.
+/// Other interim responses, like `103 Early Hints`, are skipped.
+///
+/// If a response has started to arrive when the time is up, the rest of its
+/// head is read with the previous read timeout of `stream`, which is restored
+/// before returning.
+pub fn await_continue<S: ReadTimeout>(stream: &mut S,
+                                      timeout: Duration)
+                                      -> io::Result<ContinueOutcome> {
+    let previous_timeout = stream.read_timeout()?;
+    let outcome = read_until_continue(stream, Instant::now().checked_add(timeout),
+                                      previous_timeout);
+
+    stream.set_read_timeout(previous_timeout)?;
+    outcome
+}
+
+/// Waits without a deadline if it is `None`, which happens if the timeout
+/// is too large to be added to the current time.
+fn read_until_continue<S: ReadTimeout>(stream: &mut S,
+                                       deadline: Option<Instant>,
+                                       previous_timeout: Option<Duration>)
+                                       -> io::Result<ContinueOutcome> {
+    let mut head = vec![];
+
+    if deadline.is_none() {
+        stream.set_read_timeout(None)?;
+    }
+
+    loop {
+        if !read_head_until(stream, deadline, &mut head)? {
+            if head.is_empty() {
+                return Ok(ContinueOutcome::TimedOut);
+            }
+
+            stream.set_read_timeout(previous_timeout)?;
+
+            if !read_head_until(stream, None, &mut head)? {
+                return Err(io::Error::new(io::ErrorKind::TimedOut,
+                                          "timed out reading the response head"));
+            }
+        }
+
+        let (status_line, headers) = parse::read_head(&mut head.as_slice())?;
+        let status = interim::parse_status_line(&status_line)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid status line"))?;
+
+        head.clear();
+
+        match status {
+            StatusCode::Continue => return Ok(ContinueOutcome::Continue),
+            status if interim::is_interim(status) => {}
+            status => return Ok(ContinueOutcome::Final { status, headers }),
+        }
+    }
+}
+
+/// Reads a response head into `head` one byte at a time, so nothing after
+/// the head is consumed. The read timeout is set to the time remaining until
+/// `deadline` before each read, or left as it is without a deadline.
+///
+/// Returns `false` if the time ran out, with the bytes read so far in `head`.
+fn read_head_until<S: ReadTimeout>(stream: &mut S,
+                                   deadline: Option<Instant>,
+                                   head: &mut Vec<u8>)
+                                   -> io::Result<bool> {
+    while !head.ends_with(b"\r\n\r\n") {
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+
+            stream.set_read_timeout(Some(remaining))?;
+        }
+
+        let mut byte = [0; 1];
+
+        match stream.read(&mut byte) {
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+            Ok(_) => head.push(byte[0]),
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock ||
+                            err.kind() == io::ErrorKind::TimedOut => return Ok(false),
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::RawHeader;
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+
+    fn headers(raw: &[(&str, &str)]) -> Headers {
+        let mut headers = Headers::new();
+
+        for &(name, value) in raw {
+            headers.append_raw(RawHeader::new(name, value));
+        }
+
+        headers
+    }
+
+    /// A stream that returns each part of the response in turn.
+    /// An empty part is read as a timeout.
+    struct ScriptedStream {
+        parts: VecDeque<Vec<u8>>,
+        timeout: Cell<Option<Duration>>,
+        read_timeouts: Vec<Option<Duration>>,
+    }
+
+    impl ScriptedStream {
+        fn new(parts: &[&str], timeout: Option<Duration>) -> Self {
+            ScriptedStream {
+                parts: parts.iter().map(|part| part.as_bytes().to_vec()).collect(),
+                timeout: Cell::new(timeout),
+                read_timeouts: vec![],
+            }
+        }
+    }
+
+    impl Read for ScriptedStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.read_timeouts.push(self.timeout.get());
+
+            let part = match self.parts.front_mut() {
+                Some(part) => part,
+                None => return Ok(0),
+            };
+
+            if part.is_empty() {
+                self.parts.pop_front();
+
+                return Err(io::Error::from(io::ErrorKind::WouldBlock));
+            }
+
+            let length = buf.len().min(part.len());
+
+            buf[..length].copy_from_slice(&part[..length]);
+            part.drain(..length);
+
+            if part.is_empty() {
+                self.parts.pop_front();
+            }
+
+            Ok(length)
+        }
+    }
+
+    impl ReadTimeout for ScriptedStream {
+        fn read_timeout(&self) -> io::Result<Option<Duration>> {
+            Ok(self.timeout.get())
+        }
+
+        fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+            self.timeout.set(timeout);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_check_expectation() {
+        let check = |raw| check_expectation("HTTP/1.1", &headers(raw));
+
+        assert_eq!(ExpectDecision::Proceed, check(&[]));
+        assert_eq!(ExpectDecision::Continue, check(&[("Expect", "100-continue")]));
+        assert_eq!(ExpectDecision::Fail, check(&[("Expect", "200-ok")]));
+        assert_eq!(ExpectDecision::Fail, check(&[("Expect", "")]));
+    }
+
+    #[test]
+    fn test_check_expectation_http_10() {
+        let continue_headers = headers(&[("Expect", "100-continue")]);
+
+        assert_eq!(ExpectDecision::Proceed, check_expectation("HTTP/1.0", &continue_headers));
+        assert_eq!(ExpectDecision::Proceed, check_expectation("HTTP/0.9", &continue_headers));
+        assert_eq!(ExpectDecision::Continue, check_expectation("HTTP/2.0", &continue_headers));
+        assert_eq!(ExpectDecision::Fail,
+                   check_expectation("HTTP/1.0", &headers(&[("Expect", "200-ok")])));
+    }
+
+    #[test]
+    fn test_write_expectation_failed() {
+        let mut output = Vec::new();
+
+        write_expectation_failed(&mut output).unwrap();
+
+        assert_eq!("HTTP/1.1 417 Expectation Failed\r\nContent-Length: 0\r\n\
+                    Connection: close\r\n\r\n",
+                   String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_await_continue() {
+        let mut stream = ScriptedStream::new(&["HTTP/1.1 102 Processing\r\n\r\n\
+                                                HTTP/1.1 100 Continue\r\n\r\nbody"],
+                                             None);
+
+        assert!(matches!(await_continue(&mut stream, Duration::from_secs(5)).unwrap(),
+                         ContinueOutcome::Continue));
+        assert_eq!(None, stream.read_timeout().unwrap());
+
+        let mut rest = String::new();
+
+        stream.read_to_string(&mut rest).unwrap();
+
+        assert_eq!("body", rest);
+    }
+
+    #[test]
+    fn test_await_continue_final() {
+        let mut stream =
+            ScriptedStream::new(&["HTTP/1.1 413 Payload Too Large\r\nConnection: close\r\n\r\n"],
+                                None);
+
+        match await_continue(&mut stream, Duration::from_secs(5)).unwrap() {
+            ContinueOutcome::Final { status, headers } => {
+                assert_eq!(StatusCode::PayloadTooLarge, status);
+                assert!(headers.contains("connection"));
+            }
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn test_await_continue_switching_protocols() {
+        let mut stream =
+            ScriptedStream::new(&["HTTP/1.1 101 Switching Protocols\r\nUpgrade: h2c\r\n\r\n"],
+                                None);
+
+        match await_continue(&mut stream, Duration::from_secs(5)).unwrap() {
+            ContinueOutcome::Final { status, headers } => {
+                assert_eq!(StatusCode::SwitchingProtocols, status);
+                assert!(headers.contains("upgrade"));
+            }
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn test_await_continue_timeout() {
+        let previous = Some(Duration::from_secs(30));
+        let mut stream = ScriptedStream::new(&["", "HTTP/1.1 100 Continue\r\n\r\n"], previous);
+
+        assert!(matches!(await_continue(&mut stream, Duration::from_secs(1)).unwrap(),
+                         ContinueOutcome::TimedOut));
+        assert_eq!(previous, stream.read_timeout().unwrap());
+        assert!(stream.read_timeouts[0].is_some_and(|timeout| timeout <= Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_await_continue_partial_head() {
+        let previous = Some(Duration::from_secs(30));
+        let mut stream = ScriptedStream::new(&["HTTP/1.1 417 Expectation Failed\r\n",
+                                               "",
+                                               "Connection: close\r\n\r\n"],
+                                             previous);
+
+        match await_continue(&mut stream, Duration::from_secs(1)).unwrap() {
+            ContinueOutcome::Final { status, headers } => {
+                assert_eq!(StatusCode::ExpectationFailed, status);
+                assert!(headers.contains("connection"));
+            }
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        }
+
+        assert_eq!(Some(&previous), stream.read_timeouts.last());
+        assert_eq!(previous, stream.read_timeout().unwrap());
+    }
+
+    #[test]
+    fn test_await_continue_partial_head_timeout() {
+        let mut stream = ScriptedStream::new(&["HTTP/1.1 417 Expectation Failed\r\n", "", ""],
+                                             None);
+
+        assert_eq!(io::ErrorKind::TimedOut,
+                   await_continue(&mut stream, Duration::from_secs(1)).unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_await_continue_max_timeout() {
+        let mut stream = ScriptedStream::new(&["HTTP/1.1 100 Continue\r\n\r\n"],
+                                             Some(Duration::from_secs(30)));
+
+        assert!(matches!(await_continue(&mut stream, Duration::MAX).unwrap(),
+                         ContinueOutcome::Continue));
+        assert!(stream.read_timeouts.iter().all(Option::is_none));
+        assert_eq!(Some(Duration::from_secs(30)), stream.read_timeout().unwrap());
+    }
+}
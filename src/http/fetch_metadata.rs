@@ -0,0 +1,134 @@
+//! A resource isolation policy based on the Fetch Metadata request headers,
+//! which protects against CSRF, cross-site script inclusion and other
+//! cross-site leaks by rejecting requests other sites initiated.
+//! [Fetch Metadata, Section 5.1](https://www.w3.org/TR/fetch-metadata/)
+
+use super::headers::{Headers, SecFetchDest, SecFetchDestHeader, SecFetchMode,
+                     SecFetchModeHeader, SecFetchSite, SecFetchSiteHeader};
+use super::method::Method;
+
+/// Decides whether a request may be processed. Rejected requests should be
+/// answered with `403 Forbidden`.
+///
+/// Requests from browsers that don't send `Sec-Fetch-Site`, as well as
+/// requests from other clients, are always allowed. Same-origin requests and
+/// requests the user initiated directly are allowed, as are top-level
+/// `GET` navigations from other sites, so links to the site keep working.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::fetch_metadata::IsolationPolicy;
+/// use teapot::http::headers::{Headers, RawHeader};
+/// use teapot::http::method::Method;
+///
+/// let policy = IsolationPolicy::new();
+/// let mut headers = Headers::new();
+///
+/// headers.append_raw(RawHeader::new("Sec-Fetch-Site", "cross-site"));
+/// headers.append_raw(RawHeader::new("Sec-Fetch-Mode", "no-cors"));
+/// headers.append_raw(RawHeader::new("Sec-Fetch-Dest", "script"));
+///
+/// assert!(!policy.allows(&Method::Get, &headers));
+/// assert!(policy.allows(&Method::Get, &Headers::new()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct IsolationPolicy {
+    allow_same_site: bool,
+    allow_cors: bool,
+}
+
+impl Default for IsolationPolicy {
+    fn default() -> Self {
+        IsolationPolicy::new()
+    }
+}
+
+impl IsolationPolicy {
+    /// Allows same-site requests, but no cross-site CORS requests.
+    pub fn new() -> Self {
+        IsolationPolicy {
+            allow_same_site: true,
+            allow_cors: false,
+        }
+    }
+
+    /// Whether requests from other origins of the same site,
+    /// e.g. other subdomains, are allowed.
+    pub fn set_allow_same_site(&mut self, allow_same_site: bool) {
+        self.allow_same_site = allow_same_site;
+    }
+
+    /// Whether cross-site requests in `cors` mode are allowed, e.g. for a
+    /// public API. The CORS protocol still decides whether the response
+    /// can be read.
+    pub fn set_allow_cors(&mut self, allow_cors: bool) {
+        self.allow_cors = allow_cors;
+    }
+
+    pub fn allows(&self, method: &Method, headers: &Headers) -> bool {
+        let site = match headers.get::<SecFetchSiteHeader>() {
+            Some(site) => site.into_field(),
+            None => return true,
+        };
+
+        match site {
+            SecFetchSite::SameOrigin | SecFetchSite::None => return true,
+            SecFetchSite::SameSite if self.allow_same_site => return true,
+            SecFetchSite::SameSite | SecFetchSite::CrossSite => {}
+        }
+
+        let mode = headers.get::<SecFetchModeHeader>().map(SecFetchModeHeader::into_field);
+        let dest = headers.get::<SecFetchDestHeader>().map(SecFetchDestHeader::into_field);
+
+        match mode {
+            Some(SecFetchMode::Navigate) => {
+                (*method == Method::Get || *method == Method::Head) &&
+                dest != Some(SecFetchDest::Object) && dest != Some(SecFetchDest::Embed)
+            }
+            Some(SecFetchMode::Cors) => self.allow_cors,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::RawHeader;
+
+    fn headers(site: &str, mode: &str, dest: &str) -> Headers {
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Sec-Fetch-Site", site));
+        headers.append_raw(RawHeader::new("Sec-Fetch-Mode", mode));
+        headers.append_raw(RawHeader::new("Sec-Fetch-Dest", dest));
+        headers
+    }
+
+    #[test]
+    fn test_allows() {
+        let policy = IsolationPolicy::new();
+
+        assert!(policy.allows(&Method::Post, &headers("same-origin", "cors", "empty")));
+        assert!(policy.allows(&Method::Get, &headers("none", "navigate", "document")));
+        assert!(policy.allows(&Method::Post, &headers("same-site", "no-cors", "empty")));
+        assert!(policy.allows(&Method::Get, &headers("cross-site", "navigate", "document")));
+        assert!(!policy.allows(&Method::Post, &headers("cross-site", "navigate", "document")));
+        assert!(!policy.allows(&Method::Get, &headers("cross-site", "navigate", "object")));
+        assert!(!policy.allows(&Method::Get, &headers("cross-site", "cors", "empty")));
+        assert!(!policy.allows(&Method::Get, &headers("cross-site", "no-cors", "image")));
+        assert!(policy.allows(&Method::Get, &headers("unknown", "no-cors", "image")));
+    }
+
+    #[test]
+    fn test_configuration() {
+        let mut policy = IsolationPolicy::new();
+
+        policy.set_allow_same_site(false);
+        policy.set_allow_cors(true);
+
+        assert!(!policy.allows(&Method::Post, &headers("same-site", "no-cors", "empty")));
+        assert!(policy.allows(&Method::Put, &headers("cross-site", "cors", "empty")));
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use super::headers::{CommaDelimited, ForwardedElement, ForwardedHeader, Headers, Node, TypedHeader,
+                     XForwardedForHeader};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidCidr;
+
+/// An IP network in CIDR notation like `10.0.0.0/8` or `2001:db8::/32`.
+/// A single address without prefix length is a network of just that address.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Cidr {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Returns `None` if `prefix_len` exceeds the length of the address.
+    pub fn new(address: IpAddr, prefix_len: u8) -> Option<Self> {
+        if prefix_len > max_prefix_len(address) {
+            return None;
+        }
+
+        Some(Cidr { address, prefix_len })
+    }
+
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// IPv4-mapped IPv6 addresses are treated as IPv4 addresses.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (canonical(self.address), canonical(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) if self.address.is_ipv4() => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix_len)).unwrap_or(0);
+
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix_len)).unwrap_or(0);
+
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+fn max_prefix_len(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        ip => ip,
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = InvalidCidr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = match s.find('/') {
+            Some(index) => (&s[..index], Some(&s[index + 1..])),
+            None => (s, None),
+        };
+        let address: IpAddr = address.parse().map_err(|_| InvalidCidr)?;
+        let prefix_len = match prefix_len {
+            Some(prefix_len) if prefix_len.bytes().all(|b| b.is_ascii_digit()) => {
+                prefix_len.parse().map_err(|_| InvalidCidr)?
+            }
+            Some(_) => return Err(InvalidCidr),
+            None => max_prefix_len(address),
+        };
+
+        Cidr::new(address, prefix_len).ok_or(InvalidCidr)
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+/// Determines the address of the client behind a chain of trusted reverse proxies.
+///
+/// Forwarding headers can be set by anyone, so they are only honoured
+/// when the peer that sent them is a trusted proxy. The chain is walked
+/// from the closest hop backwards until an untrusted address is found.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::forwarded::TrustedProxies;
+/// use teapot::http::headers::{Headers, RawHeader};
+///
+/// let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+/// let mut headers = Headers::new();
+///
+/// headers.append_raw(RawHeader::new("X-Forwarded-For", "192.0.2.1, 203.0.113.7, 10.0.0.2"));
+///
+/// assert_eq!("203.0.113.7".parse::<std::net::IpAddr>().unwrap(),
+///            proxies.client_ip("10.1.2.3".parse().unwrap(), &headers));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TrustedProxies {
+    networks: Vec<Cidr>,
+}
+
+impl TrustedProxies {
+    pub fn new(networks: Vec<Cidr>) -> Self {
+        TrustedProxies { networks }
+    }
+
+    pub fn networks(&self) -> &[Cidr] {
+        &self.networks
+    }
+
+    pub fn push(&mut self, network: Cidr) {
+        self.networks.push(network);
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(ip))
+    }
+
+    /// Returns the address of the client given the address of the connected `peer`.
+    ///
+    /// If `Forwarded` is present, `X-Forwarded-For` is ignored. When a hop is
+    /// invalid, `unknown` or obfuscated, the last address that could be determined is returned.
+    pub fn client_ip(&self, peer: IpAddr, headers: &Headers) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let hops: Vec<Option<IpAddr>> = if headers.contains(ForwardedHeader::name()) {
+            elements(headers, ForwardedHeader::name())
+                .iter()
+                .map(|element| {
+                    element.parse::<ForwardedElement>()
+                        .ok()
+                        .and_then(|element| element.forwarded_for().and_then(Node::ip))
+                })
+                .collect()
+        } else {
+            elements(headers, XForwardedForHeader::name())
+                .iter()
+                .map(|element| XForwardedForHeader::parse_node(element).as_ref().and_then(Node::ip))
+                .collect()
+        };
+
+        let mut client = peer;
+
+        for hop in hops.into_iter().rev() {
+            if !self.is_trusted(client) {
+                break;
+            }
+
+            match hop {
+                Some(ip) => client = ip,
+                None => break,
+            }
+        }
+
+        client
+    }
+}
+
+/// The elements of the header `name`, parsed one by one so that
+/// a single invalid hop doesn't discard the rest of the chain.
+fn elements(headers: &Headers, name: &str) -> Vec<String> {
+    CommaDelimited::from_raw(&headers.get_raw(name))
+        .map(CommaDelimited::into_items)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::RawHeader;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cidr() {
+        let v4: Cidr = "192.168.0.0/16".parse().unwrap();
+        let v6: Cidr = "2001:db8::/32".parse().unwrap();
+
+        assert!(v4.contains(ip("192.168.10.1")));
+        assert!(v4.contains(ip("::ffff:192.168.10.1")));
+        assert!(!v4.contains(ip("192.169.0.1")));
+        assert!(v6.contains(ip("2001:db8:1::1")));
+        assert!(!v6.contains(ip("2001:db9::1")));
+        assert!("0.0.0.0/0".parse::<Cidr>().unwrap().contains(ip("8.8.8.8")));
+        assert_eq!("10.0.0.1/32", "10.0.0.1".parse::<Cidr>().unwrap().to_string());
+        assert_eq!(Err(InvalidCidr), "10.0.0.0/33".parse::<Cidr>());
+        assert_eq!(Err(InvalidCidr), "10.0.0.0/+8".parse::<Cidr>());
+        assert_eq!(Err(InvalidCidr), "example.com/8".parse::<Cidr>());
+    }
+
+    #[test]
+    fn test_untrusted_peer() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("X-Forwarded-For", "192.0.2.1"));
+
+        assert_eq!(ip("203.0.113.9"), proxies.client_ip(ip("203.0.113.9"), &headers));
+    }
+
+    #[test]
+    fn test_forwarded_preferred() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap(),
+                                               "2001:db8::/32".parse().unwrap()]);
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Forwarded",
+                                          "for=192.0.2.60, for=\"[2001:db8::5]:4711\""));
+        headers.append_raw(RawHeader::new("X-Forwarded-For", "198.51.100.1"));
+
+        assert_eq!(ip("192.0.2.60"), proxies.client_ip(ip("10.0.0.1"), &headers));
+    }
+
+    #[test]
+    fn test_all_trusted_and_unknown() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("X-Forwarded-For", "10.0.0.3, 10.0.0.2"));
+
+        assert_eq!(ip("10.0.0.3"), proxies.client_ip(ip("10.0.0.1"), &headers));
+
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Forwarded", "for=192.0.2.1, for=unknown"));
+
+        assert_eq!(ip("10.0.0.1"), proxies.client_ip(ip("10.0.0.1"), &headers));
+        assert_eq!(ip("10.0.0.1"), proxies.client_ip(ip("10.0.0.1"), &Headers::new()));
+    }
+
+    #[test]
+    fn test_invalid_forwarded_ignores_x_forwarded_for() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Forwarded", "for=\"[2001:db8::1\""));
+        headers.append_raw(RawHeader::new("X-Forwarded-For", "1.2.3.4"));
+
+        assert_eq!(ip("10.0.0.1"), proxies.client_ip(ip("10.0.0.1"), &headers));
+
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Forwarded", "for=1.2.3.4, for=bad:ip:, for=10.0.0.3"));
+
+        assert_eq!(ip("10.0.0.3"), proxies.client_ip(ip("10.0.0.1"), &headers));
+    }
+
+    #[test]
+    fn test_invalid_hop() {
+        let proxies = TrustedProxies::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("X-Forwarded-For", "not-an-ip, 203.0.113.7"));
+
+        assert_eq!(ip("203.0.113.7"), proxies.client_ip(ip("10.0.0.2"), &headers));
+
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("X-Forwarded-For", "203.0.113.7, not-an-ip, 10.0.0.3"));
+
+        assert_eq!(ip("10.0.0.3"), proxies.client_ip(ip("10.0.0.2"), &headers));
+    }
+}
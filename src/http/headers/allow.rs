@@ -0,0 +1,88 @@
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::super::method::Method;
+
+/// The methods supported by the target resource. Must be sent with
+/// `405 Method Not Allowed`. An empty list means the resource
+/// doesn't allow any method.
+/// [RFC7231, Section 7.4.1](http://www.iana.org/go/rfc7231)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{AllowHeader, Headers};
+/// use teapot::http::method::Method;
+/// use teapot::http::status::StatusCode;
+///
+/// let allow = AllowHeader::new(vec![Method::Get, Method::Head]);
+/// let mut headers = Headers::new();
+///
+/// if !allow.contains(&Method::Post) {
+///     assert_eq!(405, StatusCode::MethodNotAllowed.to_u16());
+///     headers.append(allow);
+/// }
+///
+/// assert_eq!("GET, HEAD", headers.get_raw("allow")[0].value());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AllowHeader {
+    methods: CommaDelimited<Method>,
+}
+
+impl AllowHeader {
+    pub fn new(methods: Vec<Method>) -> Self {
+        AllowHeader { methods: CommaDelimited::new(methods) }
+    }
+
+    pub fn methods(&self) -> &[Method] {
+        self.methods.items()
+    }
+
+    /// Method names are case-sensitive.
+    pub fn contains(&self, method: &Method) -> bool {
+        self.methods.iter().any(|other| other == method)
+    }
+}
+
+impl TypedHeader for AllowHeader {
+    fn name() -> &'static str {
+        "allow"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Allow"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let methods = CommaDelimited::from_raw(raw).ok()?;
+
+        Some(AllowHeader { methods })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.methods.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let raw = [RawHeader::new("Allow", "GET, HEAD"), RawHeader::new("Allow", "PROPFIND")];
+        let allow = AllowHeader::parse(&[&raw[0], &raw[1]]).unwrap();
+
+        assert_eq!(&[Method::Get, Method::Head, Method::Extension("PROPFIND".to_string())],
+                   allow.methods());
+        assert!(allow.contains(&Method::Head));
+        assert!(!allow.contains(&Method::Post));
+        assert_eq!(Some(AllowHeader::new(vec![])),
+                   AllowHeader::parse(&[&RawHeader::new("Allow", "")]));
+        assert_eq!(None, AllowHeader::parse(&[&RawHeader::new("Allow", "GET /")]));
+        assert_eq!(None, AllowHeader::parse(&[]));
+    }
+}
@@ -0,0 +1,269 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidAltService;
+
+/// The freshness lifetime of an alternative service without `ma` parameter.
+const DEFAULT_MAX_AGE: u64 = 86_400;
+
+/// An alternative service, reachable with another protocol
+/// or at another network location.
+/// [RFC7838, Section 3](http://www.iana.org/go/rfc7838)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AltService {
+    protocol_id: String,
+    authority: String,
+    max_age: Option<u64>,
+    persist: bool,
+}
+
+impl AltService {
+    /// `protocol_id` is the percent-encoded ALPN protocol id, e.g. `h3`, and `authority`
+    /// is `host:port`. The host may be omitted to keep the origin's host, e.g. `:443`.
+    pub fn new<P: Into<String>, A: Into<String>>(protocol_id: P, authority: A) -> Self {
+        AltService {
+            protocol_id: protocol_id.into(),
+            authority: authority.into(),
+            max_age: None,
+            persist: false,
+        }
+    }
+
+    pub fn protocol_id(&self) -> &str {
+        &self.protocol_id
+    }
+
+    pub fn authority(&self) -> &str {
+        &self.authority
+    }
+
+    /// The host of the alternative, empty if it is the same as the origin's.
+    pub fn host(&self) -> &str {
+        match self.authority.rfind(':') {
+            Some(index) => &self.authority[..index],
+            None => &self.authority,
+        }
+    }
+
+    pub fn port(&self) -> Option<u16> {
+        self.authority.rfind(':').and_then(|index| self.authority[index + 1..].parse().ok())
+    }
+
+    /// The number of seconds the alternative may be used, 24 hours by default.
+    pub fn max_age(&self) -> u64 {
+        self.max_age.unwrap_or(DEFAULT_MAX_AGE)
+    }
+
+    pub fn set_max_age(&mut self, seconds: Option<u64>) {
+        self.max_age = seconds;
+    }
+
+    /// Whether the alternative should be kept when the client's network changes.
+    pub fn persist(&self) -> bool {
+        self.persist
+    }
+
+    pub fn set_persist(&mut self, persist: bool) {
+        self.persist = persist;
+    }
+}
+
+impl FromStr for AltService {
+    type Err = InvalidAltService;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = syntax::split_quoted(s, ';').into_iter();
+        let (protocol_id, authority) = syntax::split_pair(parts.next().ok_or(InvalidAltService)?);
+        let authority = authority.ok_or(InvalidAltService)?;
+
+        if !syntax::is_token(protocol_id) || !authority.starts_with('"') {
+            return Err(InvalidAltService);
+        }
+
+        let mut service = AltService::new(protocol_id, syntax::unquote(authority));
+
+        service.port().ok_or(InvalidAltService)?;
+
+        for param in parts {
+            match syntax::split_pair(param) {
+                (name, Some(value)) if name.eq_ignore_ascii_case("ma") => {
+                    service.max_age = syntax::parse_delta_seconds(&syntax::unquote(value));
+                }
+                (name, Some(value)) if name.eq_ignore_ascii_case("persist") => {
+                    service.persist = syntax::unquote(value) == "1";
+                }
+                _ => {}
+            }
+        }
+
+        Ok(service)
+    }
+}
+
+impl fmt::Display for AltService {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.protocol_id, syntax::quote(&self.authority))?;
+
+        if let Some(seconds) = self.max_age {
+            write!(f, "; ma={}", seconds)?;
+        }
+
+        if self.persist {
+            write!(f, "; persist=1")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The value of [`AltSvcHeader`].
+///
+/// [`AltSvcHeader`]: struct.AltSvcHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AltSvc {
+    /// `clear`, all alternatives of the origin must be removed
+    Clear,
+    Services(Vec<AltService>),
+}
+
+impl fmt::Display for AltSvc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AltSvc::Clear => write!(f, "clear"),
+            AltSvc::Services(ref services) => {
+                let services: Vec<String> = services.iter().map(AltService::to_string).collect();
+
+                write!(f, "{}", services.join(", "))
+            }
+        }
+    }
+}
+
+/// Advertises alternative services for the origin of the response.
+/// Invalid alternatives are skipped.
+/// [RFC7838, Section 3](http://www.iana.org/go/rfc7838)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{AltService, AltSvcHeader, RawHeader, TypedHeader};
+///
+/// let mut h3 = AltService::new("h3", ":443");
+///
+/// h3.set_max_age(Some(3600));
+///
+/// assert_eq!(vec!["h3=\":443\"; ma=3600".to_string()],
+///            AltSvcHeader::services(vec![h3]).raw_values());
+///
+/// let raw = RawHeader::new("Alt-Svc", "h2=\"alt.example.com:8000\"; persist=1");
+/// let header = AltSvcHeader::parse(&[&raw]).unwrap();
+/// let service = &header.alternatives()[0];
+///
+/// assert_eq!("alt.example.com", service.host());
+/// assert_eq!(Some(8000), service.port());
+/// assert_eq!(86400, service.max_age());
+/// assert!(service.persist());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AltSvcHeader {
+    value: AltSvc,
+}
+
+impl AltSvcHeader {
+    pub fn new(value: AltSvc) -> Self {
+        AltSvcHeader { value }
+    }
+
+    pub fn clear() -> Self {
+        AltSvcHeader::new(AltSvc::Clear)
+    }
+
+    pub fn services(services: Vec<AltService>) -> Self {
+        AltSvcHeader::new(AltSvc::Services(services))
+    }
+
+    pub fn value(&self) -> &AltSvc {
+        &self.value
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.value == AltSvc::Clear
+    }
+
+    /// The advertised alternatives, empty for `clear`.
+    pub fn alternatives(&self) -> &[AltService] {
+        match self.value {
+            AltSvc::Clear => &[],
+            AltSvc::Services(ref services) => services,
+        }
+    }
+}
+
+impl TypedHeader for AltSvcHeader {
+    fn name() -> &'static str {
+        "alt-svc"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Alt-Svc"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let elements: CommaDelimited<String> = CommaDelimited::from_raw(raw).unwrap_or_default();
+
+        if elements.items() == ["clear"] {
+            return Some(AltSvcHeader::clear());
+        }
+
+        let services = CommaDelimited::<AltService>::from_raw_lossy(raw).into_items();
+
+        if services.is_empty() {
+            None
+        } else {
+            Some(AltSvcHeader::services(services))
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(value: &str) -> Option<AltSvcHeader> {
+        AltSvcHeader::parse(&[&RawHeader::new("Alt-Svc", value)])
+    }
+
+    #[test]
+    fn test_parse() {
+        let header = parse("h3=\":443\"; ma=2592000; foo=bar, h2=\"[::1]:8443\"; ma=x, \
+                            h2=alt:80, quic=\"no-port\"")
+            .unwrap();
+        let alternatives = header.alternatives();
+
+        assert_eq!(2, alternatives.len());
+        assert_eq!("h3", alternatives[0].protocol_id());
+        assert_eq!("", alternatives[0].host());
+        assert_eq!(2_592_000, alternatives[0].max_age());
+        assert_eq!("[::1]", alternatives[1].host());
+        assert_eq!(Some(8443), alternatives[1].port());
+        assert_eq!(86400, alternatives[1].max_age());
+        assert_eq!(vec!["h3=\":443\"; ma=2592000, h2=\"[::1]:8443\"".to_string()],
+                   header.raw_values());
+    }
+
+    #[test]
+    fn test_clear() {
+        assert_eq!(Some(AltSvcHeader::clear()), parse("clear"));
+        assert_eq!(vec!["clear".to_string()], AltSvcHeader::clear().raw_values());
+        assert_eq!(&[] as &[AltService], AltSvcHeader::clear().alternatives());
+        assert_eq!(None, parse("Clear"));
+        assert_eq!(None, parse(""));
+    }
+}
@@ -0,0 +1,507 @@
+use std::fmt;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+use super::super::super::base64;
+
+/// The data following the auth-scheme of a challenge or credentials.
+/// [RFC7235, Section 2.1](http://www.iana.org/go/rfc7235)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AuthParams {
+    None,
+    Token68(String),
+    /// Parameter names are compared case-insensitively, values are unquoted.
+    Params(Vec<(String, String)>),
+}
+
+impl AuthParams {
+    fn get(&self, name: &str) -> Option<&str> {
+        match *self {
+            AuthParams::Params(ref params) => {
+                params.iter()
+                    .find(|param| param.0.eq_ignore_ascii_case(name))
+                    .map(|param| param.1.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    fn token68(&self) -> Option<&str> {
+        match *self {
+            AuthParams::Token68(ref token) => Some(token),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters that are written as tokens in challenges,
+/// all other values are written as quoted strings.
+const CHALLENGE_TOKEN_PARAMS: [&str; 2] = ["algorithm", "stale"];
+
+/// Parameters that are written as tokens in credentials,
+/// all other values are written as quoted strings.
+/// [RFC7616, Section 3.4](http://www.iana.org/go/rfc7616)
+const CREDENTIALS_TOKEN_PARAMS: [&str; 3] = ["algorithm", "qop", "nc"];
+
+/// Writes `scheme` followed by its token68 or parameters.
+fn write_auth(f: &mut fmt::Formatter,
+              scheme: &str,
+              params: &AuthParams,
+              token_params: &[&str])
+              -> fmt::Result {
+    write!(f, "{}", scheme)?;
+
+    match *params {
+        AuthParams::None => Ok(()),
+        AuthParams::Token68(ref token) => write!(f, " {}", token),
+        AuthParams::Params(ref params) => {
+            let params: Vec<String> = params.iter()
+                .map(|(name, value)| {
+                    let is_token_param = token_params.iter()
+                        .any(|param| param.eq_ignore_ascii_case(name));
+
+                    if is_token_param && syntax::is_token(value) {
+                        format!("{}={}", name, value)
+                    } else {
+                        format!("{}={}", name, syntax::quote(value))
+                    }
+                })
+                .collect();
+
+            write!(f, " {}", params.join(", "))
+        }
+    }
+}
+
+fn is_token68(value: &str) -> bool {
+    let data = value.trim_end_matches('=');
+
+    !data.is_empty() &&
+    data.chars().all(|c| c.is_ascii_alphanumeric() || "-._~+/".contains(c))
+}
+
+/// Parses the list of challenges or credentials in the raw values.
+/// A list element starts a new item if it begins with an auth-scheme,
+/// otherwise it is an auth-param of the previous item, so the elements
+/// can't be parsed on their own and are split as strings first.
+fn parse_auth_list(raw: &[&RawHeader]) -> Option<Vec<(String, AuthParams)>> {
+    let elements: CommaDelimited<String> = CommaDelimited::from_raw(raw).unwrap_or_default();
+    let mut items: Vec<(String, AuthParams)> = vec![];
+
+    for element in &elements {
+        let element = element.as_str();
+        let (first, rest) = match element.find(' ') {
+            Some(index) => (&element[..index], element[index + 1..].trim_start()),
+            None => (element, ""),
+        };
+
+        let is_param = first.contains('=') || rest.starts_with('=');
+
+        if is_param {
+            let (name, value) = syntax::split_pair(element);
+
+            match items.last_mut() {
+                Some(&mut (_, AuthParams::Params(ref mut params))) if syntax::is_token(name) => {
+                    params.push((name.to_string(), syntax::unquote(value.unwrap_or(""))));
+                }
+                _ => return None,
+            }
+
+            continue;
+        }
+
+        if !syntax::is_token(first) {
+            return None;
+        }
+
+        let params = if rest.is_empty() {
+            AuthParams::None
+        } else if is_token68(rest) && split_param(rest).is_none() {
+            AuthParams::Token68(rest.to_string())
+        } else {
+            let (name, value) = split_param(rest)?;
+
+            AuthParams::Params(vec![(name.to_string(), syntax::unquote(value))])
+        };
+
+        items.push((first.to_string(), params));
+    }
+
+    Some(items)
+}
+
+/// Splits `name = value` where the value is a token or a quoted string.
+fn split_param(value: &str) -> Option<(&str, &str)> {
+    let index = value.find('=')?;
+    let name = value[..index].trim();
+    let value = value[index + 1..].trim();
+
+    if !syntax::is_token(name) ||
+       !(syntax::is_token(value) || (value.len() >= 2 && value.starts_with('"'))) {
+        return None;
+    }
+
+    Some((name, value))
+}
+
+/// An authentication challenge, sent by servers in `WWW-Authenticate`
+/// and `Proxy-Authenticate` headers.
+/// [RFC7235, Section 2.1](http://www.iana.org/go/rfc7235)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Challenge {
+    scheme: String,
+    params: AuthParams,
+}
+
+impl Challenge {
+    pub fn new<S: Into<String>>(scheme: S, params: AuthParams) -> Self {
+        Challenge {
+            scheme: scheme.into(),
+            params,
+        }
+    }
+
+    /// [RFC7617, Section 2](http://www.iana.org/go/rfc7617)
+    pub fn basic<S: Into<String>>(realm: S) -> Self {
+        Challenge::new("Basic",
+                       AuthParams::Params(vec![("realm".to_string(), realm.into()),
+                                               ("charset".to_string(), "UTF-8".to_string())]))
+    }
+
+    /// [RFC6750, Section 3](http://www.iana.org/go/rfc6750)
+    pub fn bearer<S: Into<String>>(realm: S) -> Self {
+        Challenge::new("Bearer",
+                       AuthParams::Params(vec![("realm".to_string(), realm.into())]))
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn params(&self) -> &AuthParams {
+        &self.params
+    }
+
+    /// Returns the value of an auth-param, ignoring the case of `name`.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name)
+    }
+
+    pub fn realm(&self) -> Option<&str> {
+        self.param("realm")
+    }
+
+    /// Compares the auth-scheme, ignoring case.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_auth(f, &self.scheme, &self.params, &CHALLENGE_TOKEN_PARAMS)
+    }
+}
+
+/// Credentials, sent by clients in `Authorization` and `Proxy-Authorization` headers.
+/// [RFC7235, Section 2.1](http://www.iana.org/go/rfc7235)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::Credentials;
+///
+/// let credentials = Credentials::basic("Aladdin", "open sesame");
+///
+/// assert_eq!("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==", credentials.to_string());
+/// assert_eq!(Some(("Aladdin".to_string(), "open sesame".to_string())),
+///            credentials.to_basic());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Credentials {
+    scheme: String,
+    params: AuthParams,
+}
+
+impl Credentials {
+    pub fn new<S: Into<String>>(scheme: S, params: AuthParams) -> Self {
+        Credentials {
+            scheme: scheme.into(),
+            params,
+        }
+    }
+
+    /// Encodes the user-id and password as UTF-8.
+    /// [RFC7617, Section 2](http://www.iana.org/go/rfc7617)
+    pub fn basic(user_id: &str, password: &str) -> Self {
+        let token = base64::encode(format!("{}:{}", user_id, password).as_bytes());
+
+        Credentials::new("Basic", AuthParams::Token68(token))
+    }
+
+    /// [RFC6750, Section 2.1](http://www.iana.org/go/rfc6750)
+    pub fn bearer<S: Into<String>>(token: S) -> Self {
+        Credentials::new("Bearer", AuthParams::Token68(token.into()))
+    }
+
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn params(&self) -> &AuthParams {
+        &self.params
+    }
+
+    /// Returns the value of an auth-param, ignoring the case of `name`.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name)
+    }
+
+    /// Compares the auth-scheme, ignoring case.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+
+    /// Decodes `Basic` credentials into user-id and password.
+    pub fn to_basic(&self) -> Option<(String, String)> {
+        if !self.is_scheme("Basic") {
+            return None;
+        }
+
+        let decoded = String::from_utf8(base64::decode(self.params.token68()?)?).ok()?;
+        let index = decoded.find(':')?;
+
+        Some((decoded[..index].to_string(), decoded[index + 1..].to_string()))
+    }
+
+    /// Returns the token of `Bearer` credentials.
+    pub fn to_bearer(&self) -> Option<&str> {
+        if !self.is_scheme("Bearer") {
+            return None;
+        }
+
+        self.params.token68()
+    }
+}
+
+impl fmt::Display for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_auth(f, &self.scheme, &self.params, &CREDENTIALS_TOKEN_PARAMS)
+    }
+}
+
+macro_rules! credentials_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            credentials: Credentials,
+        }
+
+        impl $header {
+            pub fn new(credentials: Credentials) -> Self {
+                $header { credentials }
+            }
+
+            pub fn credentials(&self) -> &Credentials {
+                &self.credentials
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                let mut items = parse_auth_list(&raw[..raw.len().min(1)])?;
+
+                if items.len() != 1 {
+                    return None;
+                }
+
+                let (scheme, params) = items.remove(0);
+
+                Some($header { credentials: Credentials::new(scheme, params) })
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                vec![self.credentials.to_string()]
+            }
+        }
+    }
+}
+
+macro_rules! challenge_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            challenges: Vec<Challenge>,
+        }
+
+        impl $header {
+            pub fn new(challenges: Vec<Challenge>) -> Self {
+                $header { challenges }
+            }
+
+            pub fn challenges(&self) -> &[Challenge] {
+                &self.challenges
+            }
+
+            /// Finds the first challenge for `scheme`, ignoring case.
+            pub fn challenge(&self, scheme: &str) -> Option<&Challenge> {
+                self.challenges.iter().find(|challenge| challenge.is_scheme(scheme))
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                let challenges: Vec<Challenge> = parse_auth_list(raw)?
+                    .into_iter()
+                    .map(|(scheme, params)| Challenge::new(scheme, params))
+                    .collect();
+
+                if challenges.is_empty() {
+                    return None;
+                }
+
+                Some($header { challenges })
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                self.challenges.iter().map(|challenge| challenge.to_string()).collect()
+            }
+        }
+    }
+}
+
+credentials_header!(
+    /// [RFC7235, Section 4.2](http://www.iana.org/go/rfc7235)
+    AuthorizationHeader, "authorization", "Authorization");
+
+credentials_header!(
+    /// [RFC7235, Section 4.4](http://www.iana.org/go/rfc7235)
+    ProxyAuthorizationHeader, "proxy-authorization", "Proxy-Authorization");
+
+challenge_header!(
+    /// Sent with `401 Unauthorized` responses.
+    /// [RFC7235, Section 4.1](http://www.iana.org/go/rfc7235)
+    WwwAuthenticateHeader, "www-authenticate", "WWW-Authenticate");
+
+challenge_header!(
+    /// Sent with `407 Proxy Authentication Required` responses.
+    /// [RFC7235, Section 4.3](http://www.iana.org/go/rfc7235)
+    ProxyAuthenticateHeader, "proxy-authenticate", "Proxy-Authenticate");
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn params(params: &[(&str, &str)]) -> AuthParams {
+        AuthParams::Params(params.iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect())
+    }
+
+    #[test]
+    fn test_parse_challenges() {
+        let header = WwwAuthenticateHeader::parse(&[&RawHeader::new("WWW-Authenticate",
+                                                                    "Newauth realm=\"apps\", \
+                                                                     type=1, title=\"Login \
+                                                                     to \\\"apps\\\"\", Basic \
+                                                                     realm=\"simple\"")])
+            .unwrap();
+
+        assert_eq!(&[Challenge::new("Newauth",
+                                    params(&[("realm", "apps"),
+                                             ("type", "1"),
+                                             ("title", "Login to \"apps\"")])),
+                     Challenge::new("Basic", params(&[("realm", "simple")]))],
+                   header.challenges());
+        assert_eq!(Some("simple"), header.challenge("basic").unwrap().realm());
+    }
+
+    #[test]
+    fn test_parse_token68_and_bare_schemes() {
+        let header = WwwAuthenticateHeader::parse(&[&RawHeader::new("WWW-Authenticate",
+                                                                    "Negotiate, Custom \
+                                                                     abc+/==, Bearer realm \
+                                                                     = \"api\"")])
+            .unwrap();
+
+        assert_eq!(&[Challenge::new("Negotiate", AuthParams::None),
+                     Challenge::new("Custom", AuthParams::Token68("abc+/==".to_string())),
+                     Challenge::new("Bearer", params(&[("realm", "api")]))],
+                   header.challenges());
+    }
+
+    #[test]
+    fn test_invalid_challenges() {
+        let parse = |value| WwwAuthenticateHeader::parse(&[&RawHeader::new("WWW-Authenticate", value)]);
+
+        assert!(parse("realm=\"foo\"").is_none());
+        assert!(parse("Custom abc==, foo=bar").is_none());
+        assert!(parse("").is_none());
+    }
+
+    #[test]
+    fn test_challenge_roundtrip() {
+        let header = WwwAuthenticateHeader::new(vec![Challenge::basic("Our \"site\""),
+                                                     Challenge::bearer("api")]);
+
+        assert_eq!(vec!["Basic realm=\"Our \\\"site\\\"\", charset=\"UTF-8\"".to_string(),
+                        "Bearer realm=\"api\"".to_string()],
+                   header.raw_values());
+
+        let raw = header.to_raw();
+        let parsed = WwwAuthenticateHeader::parse(&raw.iter().collect::<Vec<_>>()).unwrap();
+
+        assert_eq!(header, parsed);
+    }
+
+    #[test]
+    fn test_basic_credentials() {
+        let header = AuthorizationHeader::parse(&[&RawHeader::new("Authorization",
+                                                                  "basic dGVzdDoxMjPCow==")])
+            .unwrap();
+
+        assert_eq!(Some(("test".to_string(), "123£".to_string())),
+                   header.credentials().to_basic());
+        assert_eq!(None, header.credentials().to_bearer());
+        assert_eq!(None, Credentials::bearer("x").to_basic());
+    }
+
+    #[test]
+    fn test_bearer_credentials() {
+        let header = ProxyAuthorizationHeader::parse(&[&RawHeader::new("Proxy-Authorization",
+                                                                       "Bearer mF_9.B5f-4.1JqM")])
+            .unwrap();
+
+        assert_eq!(Some("mF_9.B5f-4.1JqM"), header.credentials().to_bearer());
+        assert_eq!(vec!["Bearer mF_9.B5f-4.1JqM".to_string()], header.raw_values());
+    }
+
+    #[test]
+    fn test_credentials_params() {
+        let header = AuthorizationHeader::parse(&[&RawHeader::new("Authorization",
+                                                                  "Digest username=\"Mufasa\", \
+                                                                   nc=00000001")])
+            .unwrap();
+
+        assert!(header.credentials().is_scheme("digest"));
+        assert_eq!(Some("Mufasa"), header.credentials().param("USERNAME"));
+        assert_eq!(Some("00000001"), header.credentials().param("nc"));
+    }
+}
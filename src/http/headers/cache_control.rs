@@ -0,0 +1,374 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+/// A single `Cache-Control` directive.
+/// [RFC7234, Section 5.2](http://www.iana.org/go/rfc7234)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CacheDirective {
+    MaxAge(u64),
+    /// `max-stale`, optionally limited to a number of seconds
+    MaxStale(Option<u64>),
+    MinFresh(u64),
+    SMaxAge(u64),
+    /// `no-cache`, optionally limited to a list of field names
+    NoCache(Vec<String>),
+    NoStore,
+    NoTransform,
+    OnlyIfCached,
+    MustRevalidate,
+    ProxyRevalidate,
+    Public,
+    /// `private`, optionally limited to a list of field names
+    Private(Vec<String>),
+    /// [RFC8246](http://www.iana.org/go/rfc8246)
+    Immutable,
+    /// [RFC5861, Section 3](http://www.iana.org/go/rfc5861)
+    StaleWhileRevalidate(u64),
+    /// [RFC5861, Section 4](http://www.iana.org/go/rfc5861)
+    StaleIfError(u64),
+    /// Any other directive, including known directives with an invalid argument
+    Extension(String, Option<String>),
+}
+
+impl FromStr for CacheDirective {
+    type Err = Infallible;
+
+    /// Unknown directives and known directives with an invalid argument
+    /// are parsed as `Extension`, so parsing never fails.
+    /// An argument of a directive that takes none is ignored,
+    /// so that e.g. `no-store=1` still forbids storing the response.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = syntax::split_pair(s);
+        let lower_name = name.to_ascii_lowercase();
+        let value = value.map(syntax::unquote);
+
+        let seconds = value.as_ref().and_then(|value| syntax::parse_delta_seconds(value));
+        let fields = || match value {
+            Some(ref value) => {
+                value.parse::<CommaDelimited<String>>().unwrap_or_default().into_items()
+            }
+            None => vec![],
+        };
+
+        let directive = match (lower_name.as_str(), value.is_some()) {
+            ("max-age", true) => seconds.map(CacheDirective::MaxAge),
+            ("max-stale", false) => Some(CacheDirective::MaxStale(None)),
+            ("max-stale", true) => seconds.map(|seconds| CacheDirective::MaxStale(Some(seconds))),
+            ("min-fresh", true) => seconds.map(CacheDirective::MinFresh),
+            ("s-maxage", true) => seconds.map(CacheDirective::SMaxAge),
+            ("no-cache", _) => Some(CacheDirective::NoCache(fields())),
+            ("no-store", _) => Some(CacheDirective::NoStore),
+            ("no-transform", _) => Some(CacheDirective::NoTransform),
+            ("only-if-cached", _) => Some(CacheDirective::OnlyIfCached),
+            ("must-revalidate", _) => Some(CacheDirective::MustRevalidate),
+            ("proxy-revalidate", _) => Some(CacheDirective::ProxyRevalidate),
+            ("public", _) => Some(CacheDirective::Public),
+            ("private", _) => Some(CacheDirective::Private(fields())),
+            ("immutable", _) => Some(CacheDirective::Immutable),
+            ("stale-while-revalidate", true) => seconds.map(CacheDirective::StaleWhileRevalidate),
+            ("stale-if-error", true) => seconds.map(CacheDirective::StaleIfError),
+            _ => None,
+        };
+
+        Ok(directive.unwrap_or(CacheDirective::Extension(lower_name, value)))
+    }
+}
+
+impl fmt::Display for CacheDirective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CacheDirective::MaxAge(seconds) => write!(f, "max-age={}", seconds),
+            CacheDirective::MaxStale(None) => write!(f, "max-stale"),
+            CacheDirective::MaxStale(Some(seconds)) => write!(f, "max-stale={}", seconds),
+            CacheDirective::MinFresh(seconds) => write!(f, "min-fresh={}", seconds),
+            CacheDirective::SMaxAge(seconds) => write!(f, "s-maxage={}", seconds),
+            CacheDirective::NoCache(ref fields) => write_fields(f, "no-cache", fields),
+            CacheDirective::NoStore => write!(f, "no-store"),
+            CacheDirective::NoTransform => write!(f, "no-transform"),
+            CacheDirective::OnlyIfCached => write!(f, "only-if-cached"),
+            CacheDirective::MustRevalidate => write!(f, "must-revalidate"),
+            CacheDirective::ProxyRevalidate => write!(f, "proxy-revalidate"),
+            CacheDirective::Public => write!(f, "public"),
+            CacheDirective::Private(ref fields) => write_fields(f, "private", fields),
+            CacheDirective::Immutable => write!(f, "immutable"),
+            CacheDirective::StaleWhileRevalidate(seconds) => {
+                write!(f, "stale-while-revalidate={}", seconds)
+            }
+            CacheDirective::StaleIfError(seconds) => write!(f, "stale-if-error={}", seconds),
+            CacheDirective::Extension(ref name, None) => write!(f, "{}", name),
+            CacheDirective::Extension(ref name, Some(ref value)) => {
+                write!(f, "{}={}", name, syntax::quote_if_needed(value))
+            }
+        }
+    }
+}
+
+fn write_fields(f: &mut fmt::Formatter, name: &str, fields: &[String]) -> fmt::Result {
+    if fields.is_empty() {
+        write!(f, "{}", name)
+    } else {
+        write!(f, "{}={}", name, syntax::quote(&fields.join(", ")))
+    }
+}
+
+/// The `Cache-Control` header.
+/// Directives from multiple header lines are merged in order.
+/// [RFC7234, Section 5.2](http://www.iana.org/go/rfc7234)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{CacheControlHeader, CacheDirective, TypedHeader};
+///
+/// let mut header = CacheControlHeader::new(vec![CacheDirective::Public]);
+///
+/// header.push(CacheDirective::MaxAge(60));
+///
+/// assert_eq!(vec!["public, max-age=60".to_string()], header.raw_values());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct CacheControlHeader {
+    directives: Vec<CacheDirective>,
+}
+
+impl CacheControlHeader {
+    pub fn new(directives: Vec<CacheDirective>) -> Self {
+        CacheControlHeader { directives }
+    }
+
+    pub fn directives(&self) -> &[CacheDirective] {
+        &self.directives
+    }
+
+    pub fn push(&mut self, directive: CacheDirective) {
+        self.directives.push(directive);
+    }
+
+    /// Keeps only the directives for which `predicate` returns `true`.
+    pub fn retain<F: FnMut(&CacheDirective) -> bool>(&mut self, predicate: F) {
+        self.directives.retain(predicate);
+    }
+
+    pub fn max_age(&self) -> Option<u64> {
+        self.find(|directive| match *directive {
+            CacheDirective::MaxAge(seconds) => Some(seconds),
+            _ => None,
+        })
+    }
+
+    pub fn s_maxage(&self) -> Option<u64> {
+        self.find(|directive| match *directive {
+            CacheDirective::SMaxAge(seconds) => Some(seconds),
+            _ => None,
+        })
+    }
+
+    /// Returns the field names of the `no-cache` directive.
+    /// An empty list means that the whole response must not be reused without revalidation.
+    pub fn no_cache(&self) -> Option<&[String]> {
+        self.find(|directive| match *directive {
+            CacheDirective::NoCache(ref fields) => Some(fields.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Returns the field names of the `private` directive.
+    /// An empty list means that the whole response is private.
+    pub fn private(&self) -> Option<&[String]> {
+        self.find(|directive| match *directive {
+            CacheDirective::Private(ref fields) => Some(fields.as_slice()),
+            _ => None,
+        })
+    }
+
+    pub fn no_store(&self) -> bool {
+        self.contains(&CacheDirective::NoStore)
+    }
+
+    pub fn public(&self) -> bool {
+        self.contains(&CacheDirective::Public)
+    }
+
+    pub fn must_revalidate(&self) -> bool {
+        self.contains(&CacheDirective::MustRevalidate)
+    }
+
+    pub fn immutable(&self) -> bool {
+        self.contains(&CacheDirective::Immutable)
+    }
+
+    pub fn stale_while_revalidate(&self) -> Option<u64> {
+        self.find(|directive| match *directive {
+            CacheDirective::StaleWhileRevalidate(seconds) => Some(seconds),
+            _ => None,
+        })
+    }
+
+    pub fn stale_if_error(&self) -> Option<u64> {
+        self.find(|directive| match *directive {
+            CacheDirective::StaleIfError(seconds) => Some(seconds),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of an extension directive, ignoring case.
+    pub fn extension(&self, name: &str) -> Option<Option<&str>> {
+        self.find(|directive| match *directive {
+            CacheDirective::Extension(ref extension, ref value) if extension
+                .eq_ignore_ascii_case(name) => Some(value.as_deref()),
+            _ => None,
+        })
+    }
+
+    fn contains(&self, directive: &CacheDirective) -> bool {
+        self.directives.contains(directive)
+    }
+
+    fn find<'a, T, F>(&'a self, f: F) -> Option<T>
+        where F: Fn(&'a CacheDirective) -> Option<T>
+    {
+        self.directives.iter().filter_map(f).next()
+    }
+}
+
+impl TypedHeader for CacheControlHeader {
+    fn name() -> &'static str {
+        "cache-control"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Cache-Control"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let directives = CommaDelimited::from_raw(raw).unwrap_or_default().into_items();
+
+        Some(CacheControlHeader { directives })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        if self.directives.is_empty() {
+            return vec![];
+        }
+
+        let directives: Vec<String> = self.directives
+            .iter()
+            .map(|directive| directive.to_string())
+            .collect();
+
+        vec![directives.join(", ")]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::Headers;
+
+    #[test]
+    fn test_parse_response_directives() {
+        let header = CacheControlHeader::parse(&[&RawHeader::new("Cache-Control",
+                                                                 "public, MAX-AGE=\"60\", \
+                                                                  s-maxage=120, immutable")])
+            .unwrap();
+
+        assert!(header.public());
+        assert!(header.immutable());
+        assert!(!header.no_store());
+        assert_eq!(Some(60), header.max_age());
+        assert_eq!(Some(120), header.s_maxage());
+        assert_eq!(None, header.no_cache());
+    }
+
+    #[test]
+    fn test_field_lists() {
+        let header = CacheControlHeader::parse(&[&RawHeader::new("Cache-Control",
+                                                                 "no-cache=\"Set-Cookie, \
+                                                                  X-Foo\", private")])
+            .unwrap();
+
+        assert_eq!(Some(&["Set-Cookie".to_string(), "X-Foo".to_string()][..]),
+                   header.no_cache());
+        assert_eq!(Some(&[][..]), header.private());
+        assert_eq!(vec!["no-cache=\"Set-Cookie, X-Foo\", private".to_string()],
+                   header.raw_values());
+    }
+
+    #[test]
+    fn test_merge_lines() {
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Cache-Control", "max-age=10, stale-if-error=300"));
+        headers.append_raw(RawHeader::new("cache-control", "stale-while-revalidate=30, foo"));
+
+        let header: CacheControlHeader = headers.get().unwrap();
+
+        assert_eq!(Some(10), header.max_age());
+        assert_eq!(Some(300), header.stale_if_error());
+        assert_eq!(Some(30), header.stale_while_revalidate());
+        assert_eq!(Some(None), header.extension("FOO"));
+    }
+
+    #[test]
+    fn test_request_directives() {
+        let header = CacheControlHeader::parse(&[&RawHeader::new("Cache-Control",
+                                                                 "max-stale, min-fresh=5, \
+                                                                  only-if-cached, no-transform")])
+            .unwrap();
+
+        assert_eq!(&[CacheDirective::MaxStale(None),
+                     CacheDirective::MinFresh(5),
+                     CacheDirective::OnlyIfCached,
+                     CacheDirective::NoTransform],
+                   header.directives());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut header = CacheControlHeader::parse(&[&RawHeader::new("Cache-Control",
+                                                                     "max-age=soon, no-store, \
+                                                                      community=\"UCI\"")])
+            .unwrap();
+
+        assert_eq!(None, header.max_age());
+        assert_eq!(Some(Some("soon")), header.extension("max-age"));
+
+        header.retain(|directive| *directive != CacheDirective::NoStore);
+        header.push(CacheDirective::MaxAge(0));
+
+        assert_eq!(vec!["max-age=soon, community=UCI, max-age=0".to_string()],
+                   header.raw_values());
+    }
+
+    #[test]
+    fn test_ignored_arguments() {
+        let header = CacheControlHeader::parse(&[&RawHeader::new("Cache-Control",
+                                                                 "no-store=1, public=\"yes\", \
+                                                                  must-revalidate=0")])
+            .unwrap();
+
+        assert!(header.no_store());
+        assert!(header.public());
+        assert!(header.must_revalidate());
+        assert_eq!(vec!["no-store, public, must-revalidate".to_string()], header.raw_values());
+    }
+
+    #[test]
+    fn test_empty() {
+        let header = CacheControlHeader::default();
+        let mut headers = Headers::new();
+
+        assert!(header.raw_values().is_empty());
+
+        headers.append(header);
+
+        assert!(!headers.contains("cache-control"));
+    }
+}
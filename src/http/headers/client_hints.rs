@@ -0,0 +1,365 @@
+use super::structured::{BareItem, Item, List, Member, StructuredField, StructuredHeader};
+
+/// A brand and its significant version, e.g. `"Chromium";v="124"`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Brand {
+    name: String,
+    version: String,
+}
+
+impl Brand {
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        Brand {
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+fn parse_brands(value: &List) -> Vec<Brand> {
+    value.members()
+        .iter()
+        .filter_map(|member| match *member {
+            Member::Item(ref item) => {
+                let name = item.bare_item().as_string()?;
+                let version = item.params().get("v").and_then(BareItem::as_string)?;
+
+                Some(Brand::new(name, version))
+            }
+            Member::InnerList(_) => None,
+        })
+        .collect()
+}
+
+fn brands_value(brands: &[Brand]) -> List {
+    let members = brands.iter()
+        .map(|brand| {
+            let mut item = Item::new(BareItem::String(brand.name.clone()));
+
+            item.params_mut().insert("v", BareItem::String(brand.version.clone()));
+            Member::Item(item)
+        })
+        .collect();
+
+    List::new(members)
+}
+
+macro_rules! brand_list_field {
+    ($(#[$attr:meta])* $field:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $field {
+            brands: Vec<Brand>,
+        }
+
+        impl $field {
+            pub fn new(brands: Vec<Brand>) -> Self {
+                $field { brands }
+            }
+
+            /// The brands in the order they were sent. Browsers add
+            /// arbitrary brands to prevent sniffing for a specific list.
+            pub fn brands(&self) -> &[Brand] {
+                &self.brands
+            }
+
+            pub fn brand(&self, name: &str) -> Option<&Brand> {
+                self.brands.iter().find(|brand| brand.name == name)
+            }
+        }
+
+        impl StructuredField for $field {
+            type Value = List;
+
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            /// Members that aren't strings with a `v` parameter are skipped.
+            fn from_value(value: List) -> Option<Self> {
+                Some($field::new(parse_brands(&value)))
+            }
+
+            fn to_value(&self) -> List {
+                brands_value(&self.brands)
+            }
+        }
+    }
+}
+
+macro_rules! string_field {
+    ($(#[$attr:meta])* $field:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $field {
+            value: String,
+        }
+
+        impl $field {
+            pub fn new<S: Into<String>>(value: S) -> Self {
+                $field { value: value.into() }
+            }
+
+            pub fn value(&self) -> &str {
+                &self.value
+            }
+        }
+
+        impl StructuredField for $field {
+            type Value = Item;
+
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn from_value(value: Item) -> Option<Self> {
+                value.bare_item().as_string().map($field::new)
+            }
+
+            fn to_value(&self) -> Item {
+                Item::new(BareItem::String(self.value.clone()))
+            }
+        }
+    }
+}
+
+macro_rules! boolean_field {
+    ($(#[$attr:meta])* $field:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub struct $field {
+            value: bool,
+        }
+
+        impl $field {
+            pub fn new(value: bool) -> Self {
+                $field { value }
+            }
+
+            pub fn value(&self) -> bool {
+                self.value
+            }
+        }
+
+        impl StructuredField for $field {
+            type Value = Item;
+
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn from_value(value: Item) -> Option<Self> {
+                value.bare_item().as_boolean().map($field::new)
+            }
+
+            fn to_value(&self) -> Item {
+                Item::new(BareItem::Boolean(self.value))
+            }
+        }
+    }
+}
+
+brand_list_field!(
+    /// The brands of the browser with their significant versions, sent by default.
+    /// [User-Agent Client Hints, Section 3.1](https://wicg.github.io/ua-client-hints/)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use teapot::http::headers::{RawHeader, SecChUaHeader, TypedHeader};
+    ///
+    /// let raw = RawHeader::new("Sec-CH-UA",
+    ///                          "\"Chromium\";v=\"124\", \"Not-A.Brand\";v=\"99\"");
+    /// let header = SecChUaHeader::parse(&[&raw]).unwrap();
+    ///
+    /// assert_eq!("124", header.field().brand("Chromium").unwrap().version());
+    /// ```
+    SecChUa, "sec-ch-ua", "Sec-CH-UA");
+
+brand_list_field!(
+    /// The brands of the browser with their full versions.
+    /// [User-Agent Client Hints, Section 3.6](https://wicg.github.io/ua-client-hints/)
+    SecChUaFullVersionList, "sec-ch-ua-full-version-list", "Sec-CH-UA-Full-Version-List");
+
+boolean_field!(
+    /// Whether the browser prefers a mobile experience, sent by default.
+    /// [User-Agent Client Hints, Section 3.8](https://wicg.github.io/ua-client-hints/)
+    SecChUaMobile, "sec-ch-ua-mobile", "Sec-CH-UA-Mobile");
+
+boolean_field!(
+    /// Whether a 32-bit browser runs on 64-bit Windows.
+    /// [User-Agent Client Hints, Section 3.12](https://wicg.github.io/ua-client-hints/)
+    SecChUaWow64, "sec-ch-ua-wow64", "Sec-CH-UA-WoW64");
+
+string_field!(
+    /// The operating system, e.g. `Windows` or `Android`, sent by default.
+    /// [User-Agent Client Hints, Section 3.10](https://wicg.github.io/ua-client-hints/)
+    SecChUaPlatform, "sec-ch-ua-platform", "Sec-CH-UA-Platform");
+
+string_field!(
+    /// [User-Agent Client Hints, Section 3.11](https://wicg.github.io/ua-client-hints/)
+    SecChUaPlatformVersion, "sec-ch-ua-platform-version", "Sec-CH-UA-Platform-Version");
+
+string_field!(
+    /// The CPU architecture, e.g. `x86` or `arm`.
+    /// [User-Agent Client Hints, Section 3.2](https://wicg.github.io/ua-client-hints/)
+    SecChUaArch, "sec-ch-ua-arch", "Sec-CH-UA-Arch");
+
+string_field!(
+    /// The bitness of the CPU architecture, e.g. `64`.
+    /// [User-Agent Client Hints, Section 3.3](https://wicg.github.io/ua-client-hints/)
+    SecChUaBitness, "sec-ch-ua-bitness", "Sec-CH-UA-Bitness");
+
+string_field!(
+    /// The device model, empty on most desktop systems.
+    /// [User-Agent Client Hints, Section 3.9](https://wicg.github.io/ua-client-hints/)
+    SecChUaModel, "sec-ch-ua-model", "Sec-CH-UA-Model");
+
+pub type SecChUaHeader = StructuredHeader<SecChUa>;
+pub type SecChUaFullVersionListHeader = StructuredHeader<SecChUaFullVersionList>;
+pub type SecChUaMobileHeader = StructuredHeader<SecChUaMobile>;
+pub type SecChUaWow64Header = StructuredHeader<SecChUaWow64>;
+pub type SecChUaPlatformHeader = StructuredHeader<SecChUaPlatform>;
+pub type SecChUaPlatformVersionHeader = StructuredHeader<SecChUaPlatformVersion>;
+pub type SecChUaArchHeader = StructuredHeader<SecChUaArch>;
+pub type SecChUaBitnessHeader = StructuredHeader<SecChUaBitness>;
+pub type SecChUaModelHeader = StructuredHeader<SecChUaModel>;
+
+/// The client hints a server wants to receive in subsequent requests.
+/// [RFC8942, Section 3.1](http://www.iana.org/go/rfc8942)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{AcceptCh, AcceptChHeader, TypedHeader};
+///
+/// let header = AcceptChHeader::new(AcceptCh::new(vec!["Sec-CH-UA-Model".to_string(),
+///                                                     "Sec-CH-UA-Platform-Version".to_string()]));
+///
+/// assert_eq!(vec!["Sec-CH-UA-Model, Sec-CH-UA-Platform-Version".to_string()],
+///            header.raw_values());
+/// assert!(header.field().contains("sec-ch-ua-model"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct AcceptCh {
+    hints: Vec<String>,
+}
+
+impl AcceptCh {
+    pub fn new(hints: Vec<String>) -> Self {
+        AcceptCh { hints }
+    }
+
+    /// The names of the requested hint headers.
+    pub fn hints(&self) -> &[String] {
+        &self.hints
+    }
+
+    /// Checks whether the hint `name` is requested, ignoring case.
+    pub fn contains(&self, name: &str) -> bool {
+        self.hints.iter().any(|hint| hint.eq_ignore_ascii_case(name))
+    }
+}
+
+impl StructuredField for AcceptCh {
+    type Value = List;
+
+    fn name() -> &'static str {
+        "accept-ch"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Accept-CH"
+    }
+
+    /// Members that aren't tokens are skipped.
+    fn from_value(value: List) -> Option<Self> {
+        let hints = value.members()
+            .iter()
+            .filter_map(|member| match *member {
+                Member::Item(ref item) => item.bare_item().as_token().map(str::to_string),
+                Member::InnerList(_) => None,
+            })
+            .collect();
+
+        Some(AcceptCh::new(hints))
+    }
+
+    fn to_value(&self) -> List {
+        let members = self.hints
+            .iter()
+            .map(|hint| Member::Item(Item::new(BareItem::Token(hint.clone()))))
+            .collect();
+
+        List::new(members)
+    }
+}
+
+pub type AcceptChHeader = StructuredHeader<AcceptCh>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{RawHeader, TypedHeader};
+
+    #[test]
+    fn test_brands() {
+        let raw = RawHeader::new("Sec-CH-UA-Full-Version-List",
+                                 "\"Google Chrome\";v=\"124.0.6367.91\", \"Chromium\", x, \
+                                  \"Not-A.Brand\";v=\"99.0.0.0\"");
+        let header = SecChUaFullVersionListHeader::parse(&[&raw]).unwrap();
+
+        assert_eq!(&[Brand::new("Google Chrome", "124.0.6367.91"),
+                     Brand::new("Not-A.Brand", "99.0.0.0")],
+                   header.field().brands());
+        assert_eq!(vec!["\"Google Chrome\";v=\"124.0.6367.91\", \
+                         \"Not-A.Brand\";v=\"99.0.0.0\""
+                            .to_string()],
+                   header.raw_values());
+    }
+
+    #[test]
+    fn test_items() {
+        let mobile = SecChUaMobileHeader::parse(&[&RawHeader::new("Sec-CH-UA-Mobile", "?0")]);
+        let platform = SecChUaPlatformHeader::parse(&[&RawHeader::new("Sec-CH-UA-Platform",
+                                                                      "\"Android\"")]);
+
+        assert_eq!(Some(false), mobile.map(|header| header.field().value()));
+        assert_eq!(Some("Android".to_string()),
+                   platform.map(|header| header.field().value().to_string()));
+        assert_eq!(None,
+                   SecChUaPlatformHeader::parse(&[&RawHeader::new("Sec-CH-UA-Platform",
+                                                                  "Android")]));
+    }
+
+    #[test]
+    fn test_accept_ch() {
+        let raw = [RawHeader::new("Accept-CH", "Sec-CH-UA-Model"),
+                   RawHeader::new("Accept-CH", "Sec-CH-UA-Arch, \"invalid\"")];
+        let header = AcceptChHeader::parse(&[&raw[0], &raw[1]]).unwrap();
+
+        assert_eq!(&["Sec-CH-UA-Model".to_string(), "Sec-CH-UA-Arch".to_string()],
+                   header.field().hints());
+        assert!(header.field().contains("SEC-CH-UA-ARCH"));
+    }
+}
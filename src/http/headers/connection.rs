@@ -0,0 +1,503 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+/// Connection options, which also name the hop-by-hop headers of a message.
+/// [RFC7230, Section 6.1](http://www.iana.org/go/rfc7230)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConnectionHeader {
+    options: Vec<String>,
+}
+
+impl ConnectionHeader {
+    pub fn new(options: Vec<String>) -> Self {
+        ConnectionHeader { options }
+    }
+
+    pub fn close() -> Self {
+        ConnectionHeader::new(vec!["close".to_string()])
+    }
+
+    pub fn keep_alive() -> Self {
+        ConnectionHeader::new(vec!["keep-alive".to_string()])
+    }
+
+    pub fn upgrade() -> Self {
+        ConnectionHeader::new(vec!["upgrade".to_string()])
+    }
+
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    pub fn push<S: Into<String>>(&mut self, option: S) {
+        self.options.push(option.into());
+    }
+
+    /// Connection options are case-insensitive.
+    pub fn contains(&self, option: &str) -> bool {
+        self.options.iter().any(|other| other.eq_ignore_ascii_case(option))
+    }
+
+    pub fn is_close(&self) -> bool {
+        self.contains("close")
+    }
+
+    pub fn is_keep_alive(&self) -> bool {
+        self.contains("keep-alive")
+    }
+
+    pub fn is_upgrade(&self) -> bool {
+        self.contains("upgrade")
+    }
+}
+
+impl TypedHeader for ConnectionHeader {
+    fn name() -> &'static str {
+        "connection"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Connection"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let options: Vec<String> = CommaDelimited::<String>::from_raw(raw)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|option| syntax::is_token(option))
+            .collect();
+
+        Some(ConnectionHeader { options })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.options.join(", ")]
+    }
+}
+
+/// Parameters of a persistent connection, sent along with `Connection: keep-alive`.
+/// [RFC2068, Section 19.7.1.1](https://tools.ietf.org/html/rfc2068#section-19.7.1.1)
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct KeepAliveHeader {
+    timeout: Option<u64>,
+    max: Option<u64>,
+}
+
+impl KeepAliveHeader {
+    pub fn new(timeout: Option<u64>, max: Option<u64>) -> Self {
+        KeepAliveHeader { timeout, max }
+    }
+
+    /// The number of seconds an idle connection is kept open.
+    pub fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+
+    /// The number of requests that may still be sent on the connection.
+    pub fn max(&self) -> Option<u64> {
+        self.max
+    }
+}
+
+impl TypedHeader for KeepAliveHeader {
+    fn name() -> &'static str {
+        "keep-alive"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Keep-Alive"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let mut header = KeepAliveHeader::default();
+        let params: CommaDelimited<String> = CommaDelimited::from_raw(raw).unwrap_or_default();
+
+        for param in &params {
+            let (name, value) = syntax::split_pair(param);
+            let value = value.map(syntax::unquote).and_then(|value| value.parse().ok());
+
+            if name.eq_ignore_ascii_case("timeout") {
+                header.timeout = value;
+            } else if name.eq_ignore_ascii_case("max") {
+                header.max = value;
+            }
+        }
+
+        Some(header)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let mut params = vec![];
+
+        if let Some(timeout) = self.timeout {
+            params.push(format!("timeout={}", timeout));
+        }
+
+        if let Some(max) = self.max {
+            params.push(format!("max={}", max));
+        }
+
+        vec![params.join(", ")]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidProtocol;
+
+/// A protocol with an optional version like `websocket` or `HTTP/2.0`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Protocol {
+    name: String,
+    version: Option<String>,
+}
+
+impl Protocol {
+    pub fn new<S: Into<String>>(name: S, version: Option<S>) -> Self {
+        Protocol {
+            name: name.into(),
+            version: version.map(Into::into),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = InvalidProtocol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, version) = match s.find('/') {
+            Some(index) => (&s[..index], Some(&s[index + 1..])),
+            None => (s, None),
+        };
+
+        if !syntax::is_token(name) || !version.map_or(true, syntax::is_token) {
+            return Err(InvalidProtocol);
+        }
+
+        Ok(Protocol::new(name, version))
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.version {
+            Some(ref version) => write!(f, "{}/{}", self.name, version),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// The protocols a client would like to switch to, in order of preference.
+/// [RFC7230, Section 6.7](http://www.iana.org/go/rfc7230)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UpgradeHeader {
+    protocols: CommaDelimited<Protocol>,
+}
+
+impl UpgradeHeader {
+    pub fn new(protocols: Vec<Protocol>) -> Self {
+        UpgradeHeader { protocols: CommaDelimited::new(protocols) }
+    }
+
+    pub fn protocols(&self) -> &[Protocol] {
+        self.protocols.items()
+    }
+
+    /// Checks whether a protocol named `name` is listed, ignoring case and version.
+    pub fn contains(&self, name: &str) -> bool {
+        self.protocols.iter().any(|protocol| protocol.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl TypedHeader for UpgradeHeader {
+    fn name() -> &'static str {
+        "upgrade"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Upgrade"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let protocols = CommaDelimited::from_raw(raw).ok()?;
+
+        if protocols.is_empty() {
+            return None;
+        }
+
+        Some(UpgradeHeader { protocols })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.protocols.to_string()]
+    }
+}
+
+/// A single intermediary in a [`ViaHeader`].
+///
+/// [`ViaHeader`]: struct.ViaHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Via {
+    protocol_name: Option<String>,
+    protocol_version: String,
+    received_by: String,
+    comment: Option<String>,
+}
+
+impl Via {
+    /// `received_by` is either a host with optional port or a pseudonym.
+    pub fn new<S: Into<String>>(protocol_version: S, received_by: S) -> Self {
+        Via {
+            protocol_name: None,
+            protocol_version: protocol_version.into(),
+            received_by: received_by.into(),
+            comment: None,
+        }
+    }
+
+    /// The protocol name is omitted for HTTP.
+    pub fn protocol_name(&self) -> Option<&str> {
+        self.protocol_name.as_deref()
+    }
+
+    pub fn set_protocol_name(&mut self, protocol_name: Option<String>) {
+        self.protocol_name = protocol_name;
+    }
+
+    pub fn protocol_version(&self) -> &str {
+        &self.protocol_version
+    }
+
+    pub fn received_by(&self) -> &str {
+        &self.received_by
+    }
+
+    /// The comment without the surrounding parentheses.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        self.comment = comment;
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        let (protocol, rest) = split_whitespace_once(value)?;
+        let protocol: Protocol = protocol.parse().ok()?;
+        let (received_by, comment) = split_whitespace_once(rest).unwrap_or((rest, ""));
+        let comment = match comment {
+            "" => None,
+            comment if comment.starts_with('(') && comment.ends_with(')') => {
+                Some(comment[1..comment.len() - 1].to_string())
+            }
+            _ => return None,
+        };
+
+        if received_by.is_empty() {
+            return None;
+        }
+
+        let (protocol_name, protocol_version) = match protocol.version {
+            Some(version) => (Some(protocol.name), version),
+            None => (None, protocol.name),
+        };
+
+        Some(Via {
+            protocol_name,
+            protocol_version,
+            received_by: received_by.to_string(),
+            comment,
+        })
+    }
+}
+
+impl fmt::Display for Via {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref name) = self.protocol_name {
+            write!(f, "{}/", name)?;
+        }
+
+        write!(f, "{} {}", self.protocol_version, self.received_by)?;
+
+        if let Some(ref comment) = self.comment {
+            write!(f, " ({})", comment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The intermediaries a message passed through, in the order they were traversed.
+/// [RFC7230, Section 5.7.1](http://www.iana.org/go/rfc7230)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ViaHeader {
+    entries: Vec<Via>,
+}
+
+impl ViaHeader {
+    pub fn new(entries: Vec<Via>) -> Self {
+        ViaHeader { entries }
+    }
+
+    pub fn entries(&self) -> &[Via] {
+        &self.entries
+    }
+
+    /// Appends an intermediary, as a proxy does when forwarding the message.
+    pub fn push(&mut self, entry: Via) {
+        self.entries.push(entry);
+    }
+}
+
+impl TypedHeader for ViaHeader {
+    fn name() -> &'static str {
+        "via"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Via"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let entries: Option<Vec<Via>> = raw.iter()
+            .flat_map(|raw| split_comment_list(raw.value()))
+            .map(Via::parse)
+            .collect();
+        let entries = entries?;
+
+        if entries.is_empty() {
+            return None;
+        }
+
+        Some(ViaHeader { entries })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let entries: Vec<String> = self.entries.iter().map(Via::to_string).collect();
+
+        vec![entries.join(", ")]
+    }
+}
+
+/// Splits at the first run of whitespace and trims both parts.
+fn split_whitespace_once(value: &str) -> Option<(&str, &str)> {
+    let value = value.trim();
+    let index = value.find([' ', '\t'])?;
+
+    Some((&value[..index], value[index..].trim()))
+}
+
+/// Like `syntax::split_list`, but commas inside of comments don't split.
+/// `CommaDelimited` can't be used for `Via`, since comments may contain commas.
+fn split_comment_list(value: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if depth > 0 && c == '\\' {
+            escaped = true;
+        } else if c == '(' {
+            depth += 1;
+        } else if c == ')' {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 && c == ',' {
+            parts.push(&value[start..index]);
+            start = index + 1;
+        }
+    }
+
+    parts.push(&value[start..]);
+
+    parts.into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_connection() {
+        let connection = ConnectionHeader::parse(&[&RawHeader::new("Connection",
+                                                                   "Keep-Alive, X-Foo"),
+                                                   &RawHeader::new("Connection", "Upgrade")])
+            .unwrap();
+
+        assert!(connection.is_keep_alive());
+        assert!(connection.is_upgrade());
+        assert!(connection.contains("x-foo"));
+        assert!(!connection.is_close());
+        assert_eq!(vec!["close".to_string()], ConnectionHeader::close().raw_values());
+    }
+
+    #[test]
+    fn test_keep_alive() {
+        let keep_alive = KeepAliveHeader::parse(&[&RawHeader::new("Keep-Alive",
+                                                                  "timeout=5, max=1000")])
+            .unwrap();
+
+        assert_eq!(Some(5), keep_alive.timeout());
+        assert_eq!(Some(1000), keep_alive.max());
+        assert_eq!(vec!["timeout=5".to_string()],
+                   KeepAliveHeader::new(Some(5), None).raw_values());
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let upgrade = UpgradeHeader::parse(&[&RawHeader::new("Upgrade",
+                                                             "HTTP/2.0, SHTTP/1.3, websocket")])
+            .unwrap();
+
+        assert_eq!(&[Protocol::new("HTTP", Some("2.0")),
+                     Protocol::new("SHTTP", Some("1.3")),
+                     Protocol::new("websocket", None)],
+                   upgrade.protocols());
+        assert!(upgrade.contains("WebSocket"));
+        assert_eq!(vec!["HTTP/2.0, SHTTP/1.3, websocket".to_string()],
+                   upgrade.raw_values());
+        assert!(UpgradeHeader::parse(&[&RawHeader::new("Upgrade", "a b")]).is_none());
+    }
+
+    #[test]
+    fn test_via() {
+        let via = ViaHeader::parse(&[&RawHeader::new("Via",
+                                                     "1.0 fred, 1.1 p.example.net (Apache, 1.1)"),
+                                     &RawHeader::new("Via", "HTTP/2 edge:8080")])
+            .unwrap();
+        let entries = via.entries();
+
+        assert_eq!(3, entries.len());
+        assert_eq!(None, entries[0].protocol_name());
+        assert_eq!("1.0", entries[0].protocol_version());
+        assert_eq!("fred", entries[0].received_by());
+        assert_eq!(Some("Apache, 1.1"), entries[1].comment());
+        assert_eq!(Some("HTTP"), entries[2].protocol_name());
+        assert_eq!("edge:8080", entries[2].received_by());
+        assert_eq!(vec!["1.0 fred, 1.1 p.example.net (Apache, 1.1), HTTP/2 edge:8080".to_string()],
+                   via.raw_values());
+        assert!(ViaHeader::parse(&[&RawHeader::new("Via", "1.1")]).is_none());
+    }
+}
@@ -0,0 +1,324 @@
+use std::fmt;
+use super::{TypedHeader, RawHeader};
+use super::syntax;
+
+/// [RFC6266, Section 4.2](http://www.iana.org/go/rfc6266)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DispositionType {
+    Inline,
+    Attachment,
+    /// [RFC7578, Section 4.2](http://www.iana.org/go/rfc7578)
+    FormData,
+    /// Catch-All for all other types, in lower case
+    Extension(String),
+}
+
+impl DispositionType {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            DispositionType::Inline => "inline",
+            DispositionType::Attachment => "attachment",
+            DispositionType::FormData => "form-data",
+            DispositionType::Extension(ref value) => value,
+        }
+    }
+}
+
+impl fmt::Display for DispositionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Indicates whether a representation should be displayed or downloaded,
+/// or names a part of a `multipart/form-data` body.
+/// [RFC6266](http://www.iana.org/go/rfc6266)
+///
+/// Non-ASCII file names are written using the extended `filename*` parameter
+/// of [RFC8187](http://www.iana.org/go/rfc8187), along with an ASCII fallback
+/// in `filename` for older recipients.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{ContentDispositionHeader, RawHeader, TypedHeader};
+///
+/// let raw = RawHeader::new("Content-Disposition",
+///                          "attachment; filename=\"EURO rates\"; filename*=utf-8''%e2%82%ac%20rates");
+/// let header = ContentDispositionHeader::parse(&[&raw]).unwrap();
+///
+/// assert_eq!(Some("€ rates"), header.filename());
+///
+/// let header = ContentDispositionHeader::attachment("Übersicht.pdf");
+///
+/// assert_eq!(vec!["attachment; filename=\"_bersicht.pdf\"; filename*=UTF-8''%C3%9Cbersicht.pdf"],
+///            header.raw_values());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ContentDispositionHeader {
+    disposition: DispositionType,
+    name: Option<String>,
+    filename: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl ContentDispositionHeader {
+    pub fn new(disposition: DispositionType) -> Self {
+        ContentDispositionHeader {
+            disposition,
+            name: None,
+            filename: None,
+            params: vec![],
+        }
+    }
+
+    pub fn inline() -> Self {
+        ContentDispositionHeader::new(DispositionType::Inline)
+    }
+
+    pub fn attachment<S: Into<String>>(filename: S) -> Self {
+        let mut header = ContentDispositionHeader::new(DispositionType::Attachment);
+
+        header.set_filename(Some(filename.into()));
+        header
+    }
+
+    /// Returns `None` if `name` contains control characters other than tab.
+    pub fn form_data<S: Into<String>>(name: S) -> Option<Self> {
+        let mut header = ContentDispositionHeader::new(DispositionType::FormData);
+
+        if !header.set_name(Some(name.into())) {
+            return None;
+        }
+
+        Some(header)
+    }
+
+    pub fn disposition(&self) -> &DispositionType {
+        &self.disposition
+    }
+
+    /// The name of the form field for `form-data`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns `false` and leaves the name unchanged if `name` contains
+    /// control characters other than tab.
+    pub fn set_name(&mut self, name: Option<String>) -> bool {
+        match name {
+            Some(ref name) if !syntax::is_quotable(name) => return false,
+            _ => {}
+        }
+
+        self.name = name;
+        true
+    }
+
+    /// The suggested file name, taken from `filename*` if present.
+    /// It is not sanitized and may contain path separators.
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    pub fn set_filename(&mut self, filename: Option<String>) {
+        self.filename = filename;
+    }
+
+    /// Other parameters with unquoted values and lower case names.
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|&(other, _)| other.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `false` and ignores the parameter if `name` is not a token
+    /// or `value` contains control characters other than tab.
+    pub fn push_param<S: Into<String>>(&mut self, name: S, value: S) -> bool {
+        let name = name.into();
+        let value = value.into();
+
+        if !syntax::is_token(&name) || !syntax::is_quotable(&value) {
+            return false;
+        }
+
+        self.params.push((name.to_lowercase(), value));
+        true
+    }
+}
+
+fn ascii_fallback(filename: &str) -> String {
+    filename.chars()
+        .map(|c| if c.is_ascii() && !c.is_ascii_control() { c } else { '_' })
+        .collect()
+}
+
+impl fmt::Display for ContentDispositionHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.disposition)?;
+
+        if let Some(ref name) = self.name {
+            write!(f, "; name={}", syntax::quote(name))?;
+        }
+
+        if let Some(ref filename) = self.filename {
+            let fallback = ascii_fallback(filename);
+
+            write!(f, "; filename={}", syntax::quote(&fallback))?;
+
+            if fallback != *filename {
+                write!(f, "; filename*={}", syntax::encode_ext_value(filename))?;
+            }
+        }
+
+        for (name, value) in &self.params {
+            write!(f, "; {}={}", name, syntax::quote_if_needed(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TypedHeader for ContentDispositionHeader {
+    fn name() -> &'static str {
+        "content-disposition"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Content-Disposition"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let mut parts = syntax::split_quoted(raw.first()?.value(), ';').into_iter();
+        let disposition = parts.next()?;
+
+        if !syntax::is_token(disposition) {
+            return None;
+        }
+
+        let disposition = match disposition.to_lowercase().as_str() {
+            "inline" => DispositionType::Inline,
+            "attachment" => DispositionType::Attachment,
+            "form-data" => DispositionType::FormData,
+            other => DispositionType::Extension(other.to_string()),
+        };
+
+        let mut header = ContentDispositionHeader::new(disposition);
+        let mut ext_filename = None;
+
+        for part in parts {
+            let (name, value) = syntax::split_pair(part);
+            let value = value?;
+
+            match name.to_lowercase().as_str() {
+                "name" => header.name = Some(syntax::unquote(value)),
+                "filename" => header.filename = Some(syntax::unquote(value)),
+                // An invalid extended value is ignored in favour of `filename`
+                "filename*" => ext_filename = syntax::decode_ext_value(value),
+                _ => {
+                    header.push_param(name.to_string(), syntax::unquote(value));
+                }
+            }
+        }
+
+        if ext_filename.is_some() {
+            header.filename = ext_filename;
+        }
+
+        Some(header)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(value: &str) -> Option<ContentDispositionHeader> {
+        ContentDispositionHeader::parse(&[&RawHeader::new("Content-Disposition", value)])
+    }
+
+    #[test]
+    fn test_parse() {
+        let header = parse("Attachment; filename=example.html").unwrap();
+
+        assert_eq!(&DispositionType::Attachment, header.disposition());
+        assert_eq!(Some("example.html"), header.filename());
+
+        let header = parse("form-data; name=\"field\\\"1\"; filename=\"a;b.txt\"").unwrap();
+
+        assert_eq!(&DispositionType::FormData, header.disposition());
+        assert_eq!(Some("field\"1"), header.name());
+        assert_eq!(Some("a;b.txt"), header.filename());
+
+        assert!(parse("").is_none());
+        assert!(parse("attachment; filename").is_none());
+    }
+
+    #[test]
+    fn test_parse_ext_filename() {
+        // filename* takes precedence regardless of order
+        let header = parse("attachment; filename*=UTF-8''%e2%82%ac%20rates; filename=\"rates\"")
+            .unwrap();
+
+        assert_eq!(Some("€ rates"), header.filename());
+
+        let header = parse("attachment; filename=\"rates\"; filename*=UTF-8''%zz").unwrap();
+
+        assert_eq!(Some("rates"), header.filename());
+
+        let header = parse("inline; FILENAME*=iso-8859-1'de'%DCbersicht.txt").unwrap();
+
+        assert_eq!(Some("Übersicht.txt"), header.filename());
+    }
+
+    #[test]
+    fn test_raw_values() {
+        assert_eq!(vec!["inline".to_string()],
+                   ContentDispositionHeader::inline().raw_values());
+        assert_eq!(vec!["attachment; filename=\"report \\\"2024\\\".pdf\"".to_string()],
+                   ContentDispositionHeader::attachment("report \"2024\".pdf").raw_values());
+
+        let mut header = ContentDispositionHeader::form_data("upload").unwrap();
+
+        header.set_filename(Some("日本.txt".to_string()));
+        header.push_param("Creation-Date", "today");
+
+        let raw = header.raw_values();
+
+        assert_eq!(vec!["form-data; name=\"upload\"; filename=\"__.txt\"; \
+                         filename*=UTF-8''%E6%97%A5%E6%9C%AC.txt; creation-date=today"
+                            .to_string()],
+                   raw);
+        assert_eq!(Some(header), parse(&raw[0]));
+    }
+
+    #[test]
+    fn test_header_injection() {
+        assert_eq!(None, ContentDispositionHeader::form_data("a\r\nSet-Cookie: x=1"));
+
+        let mut header = ContentDispositionHeader::form_data("a\tb").unwrap();
+
+        assert!(!header.set_name(Some("c\x7f".to_string())));
+        assert_eq!(Some("a\tb"), header.name());
+
+        header.set_filename(Some("b\r\nX: y.txt".to_string()));
+
+        assert!(!header.push_param("c\r\nX", "1"));
+        assert!(!header.push_param("d", "2\r\n"));
+        assert!(header.push_param("e", "3 \"4\""));
+        assert_eq!(vec!["form-data; name=\"a\tb\"; filename=\"b__X: y.txt\"; \
+                         filename*=UTF-8''b%0D%0AX%3A%20y.txt; e=\"3 \\\"4\\\"\""
+                            .to_string()],
+                   header.raw_values());
+        assert_eq!(Some(header.clone()), parse(&header.raw_values()[0]));
+    }
+}
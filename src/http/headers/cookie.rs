@@ -0,0 +1,882 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::{syntax, TypedHeader, RawHeader};
+use super::super::date::{self, Civil, HttpDate};
+
+/// The `SameSite` attribute of a cookie.
+/// [draft-ietf-httpbis-rfc6265bis, Section 4.1.2.7](https://tools.ietf.org/html/draft-ietf-httpbis-rfc6265bis)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match *self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+/// The `Cookie` request header.
+/// [RFC6265, Section 5.4](http://www.iana.org/go/rfc6265)
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct CookieHeader {
+    cookies: Vec<(String, String)>,
+}
+
+impl CookieHeader {
+    pub fn new() -> Self {
+        CookieHeader { cookies: vec![] }
+    }
+
+    pub fn push<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        self.cookies.push((name.into(), value.into()));
+    }
+
+    /// Returns the value of the first cookie called `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|cookie| cookie.0 == name)
+            .map(|cookie| cookie.1.as_str())
+    }
+
+    pub fn cookies(&self) -> &[(String, String)] {
+        &self.cookies
+    }
+}
+
+impl TypedHeader for CookieHeader {
+    fn name() -> &'static str {
+        "cookie"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Cookie"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let mut header = CookieHeader::new();
+
+        for pair in raw.iter().flat_map(|raw| raw.value().split(';')) {
+            if let Some(index) = pair.find('=') {
+                let name = pair[..index].trim();
+
+                if !name.is_empty() {
+                    header.push(name, pair[index + 1..].trim());
+                }
+            }
+        }
+
+        Some(header)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let pairs: Vec<String> = self.cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        vec![pairs.join("; ")]
+    }
+}
+
+/// A single cookie as sent by a server in a `Set-Cookie` header.
+/// [RFC6265, Section 4.1](http://www.iana.org/go/rfc6265)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SetCookie {
+    name: String,
+    value: String,
+    expires: Option<HttpDate>,
+    max_age: Option<i64>,
+    domain: Option<String>,
+    path: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl SetCookie {
+    /// Returns `None` if `name` is not a token or `value` is not a valid `cookie-value`.
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> Option<Self> {
+        let name = name.into();
+        let value = value.into();
+
+        if !syntax::is_token(&name) || !is_cookie_value(&value) {
+            return None;
+        }
+
+        Some(SetCookie::new_unchecked(name, value))
+    }
+
+    fn new_unchecked(name: String, value: String) -> Self {
+        SetCookie {
+            name,
+            value,
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    /// Parses a single `Set-Cookie` value following the user agent
+    /// algorithm in [RFC6265, Section 5.2](http://www.iana.org/go/rfc6265).
+    /// Unknown or malformed attributes are ignored.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split(';');
+        let pair = parts.next().unwrap_or("");
+        let index = pair.find('=')?;
+        let name = pair[..index].trim();
+
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = SetCookie::new_unchecked(name.to_string(),
+                                                  pair[index + 1..].trim().to_string());
+
+        for attribute in parts {
+            let (name, value) = match attribute.find('=') {
+                Some(index) => (attribute[..index].trim(), attribute[index + 1..].trim()),
+                None => (attribute.trim(), ""),
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "expires" => {
+                    if let Some(expires) = parse_cookie_date(value) {
+                        cookie.expires = Some(expires);
+                    }
+                }
+                "max-age" => {
+                    if let Some(max_age) = parse_max_age(value) {
+                        cookie.max_age = Some(max_age);
+                    }
+                }
+                "domain" if !value.is_empty() => {
+                    cookie.domain = Some(value.trim_start_matches('.').to_ascii_lowercase());
+                }
+                "path" => {
+                    cookie.path = if value.starts_with('/') {
+                        Some(value.to_string())
+                    } else {
+                        None
+                    };
+                }
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                "samesite" => {
+                    cookie.same_site = match value.to_ascii_lowercase().as_str() {
+                        "strict" => Some(SameSite::Strict),
+                        "lax" => Some(SameSite::Lax),
+                        "none" => Some(SameSite::None),
+                        _ => cookie.same_site,
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        Some(cookie)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn expires(&self) -> Option<HttpDate> {
+        self.expires
+    }
+
+    pub fn set_expires(&mut self, expires: Option<HttpDate>) {
+        self.expires = expires;
+    }
+
+    /// The lifetime of the cookie in seconds.
+    /// Zero or a negative value expires the cookie immediately.
+    pub fn max_age(&self) -> Option<i64> {
+        self.max_age
+    }
+
+    pub fn set_max_age(&mut self, max_age: Option<i64>) {
+        self.max_age = max_age;
+    }
+
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// Returns `false` and leaves the domain unchanged if `domain` is not a valid domain name.
+    pub fn set_domain<S: Into<String>>(&mut self, domain: Option<S>) -> bool {
+        let domain = domain.map(|domain| domain.into());
+
+        match domain {
+            Some(ref domain) if !is_domain_value(domain) => return false,
+            _ => {}
+        }
+
+        self.domain = domain;
+        true
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns `false` and leaves the path unchanged if `path` contains
+    /// control characters or a `;`.
+    pub fn set_path<S: Into<String>>(&mut self, path: Option<S>) -> bool {
+        let path = path.map(|path| path.into());
+
+        match path {
+            Some(ref path) if !is_path_value(path) => return false,
+            _ => {}
+        }
+
+        self.path = path;
+        true
+    }
+
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+
+    pub fn set_secure(&mut self, secure: bool) {
+        self.secure = secure;
+    }
+
+    pub fn http_only(&self) -> bool {
+        self.http_only
+    }
+
+    pub fn set_http_only(&mut self, http_only: bool) {
+        self.http_only = http_only;
+    }
+
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+
+    pub fn set_same_site(&mut self, same_site: Option<SameSite>) {
+        self.same_site = same_site;
+    }
+}
+
+impl fmt::Display for SetCookie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", expires)?;
+        }
+
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age)?;
+        }
+
+        if let Some(ref domain) = self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+
+        if let Some(ref path) = self.path {
+            write!(f, "; Path={}", path)?;
+        }
+
+        if self.secure {
+            write!(f, "; Secure")?;
+        }
+
+        if self.http_only {
+            write!(f, "; HttpOnly")?;
+        }
+
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// [RFC6265, Section 4.1.1](http://www.iana.org/go/rfc6265)
+fn is_cookie_octet(c: char) -> bool {
+    matches!(c, '\x21' | '\x23'..='\x2b' | '\x2d'..='\x3a' | '\x3c'..='\x5b' | '\x5d'..='\x7e')
+}
+
+fn is_cookie_value(value: &str) -> bool {
+    let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    };
+
+    value.chars().all(is_cookie_octet)
+}
+
+/// Letters, digits and hyphens in non-empty labels separated by dots.
+fn is_domain_value(value: &str) -> bool {
+    value.split('.').all(|label| {
+        !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+fn is_path_value(value: &str) -> bool {
+    value.chars().all(|c| c.is_ascii() && !c.is_ascii_control() && c != ';')
+}
+
+/// The `Set-Cookie` response header.
+/// Unlike most other headers, each cookie is sent in a header of its own.
+/// [RFC6265, Section 4.1](http://www.iana.org/go/rfc6265)
+#[derive(PartialEq, Eq, Debug, Default)]
+pub struct SetCookieHeader {
+    cookies: Vec<SetCookie>,
+}
+
+impl SetCookieHeader {
+    pub fn new(cookies: Vec<SetCookie>) -> Self {
+        SetCookieHeader { cookies }
+    }
+
+    pub fn push(&mut self, cookie: SetCookie) {
+        self.cookies.push(cookie);
+    }
+
+    pub fn cookies(&self) -> &[SetCookie] {
+        &self.cookies
+    }
+}
+
+impl TypedHeader for SetCookieHeader {
+    fn name() -> &'static str {
+        "set-cookie"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Set-Cookie"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let cookies = raw.iter()
+            .filter_map(|raw| SetCookie::parse(raw.value()))
+            .collect();
+
+        Some(SetCookieHeader { cookies })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        self.cookies
+            .iter()
+            .map(|cookie| cookie.to_string())
+            .collect()
+    }
+}
+
+/// The expiry of cookies whose `Max-Age` exceeds the range of `SystemTime`,
+/// 9999-12-31 23:59:59 UTC in seconds since the epoch.
+const LATEST_EXPIRY: u64 = 253_402_300_799;
+
+#[derive(Clone, Debug)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    host_only: bool,
+    expiry: Option<SystemTime>,
+    secure: bool,
+    creation: u64,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: SystemTime) -> bool {
+        match self.expiry {
+            Some(expiry) => expiry <= now,
+            None => false,
+        }
+    }
+
+    fn matches(&self, host: &str, path: &str, secure: bool) -> bool {
+        let domain_matches = if self.host_only {
+            self.domain == host
+        } else {
+            domain_match(host, &self.domain)
+        };
+
+        domain_matches && path_match(path, &self.path) && (secure || !self.secure)
+    }
+}
+
+/// A client side cookie store, implementing the storage model
+/// from [RFC6265, Section 5.3](http://www.iana.org/go/rfc6265).
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{CookieJar, SetCookie, SetCookieHeader};
+///
+/// let mut jar = CookieJar::new();
+/// let mut cookie = SetCookie::new("session", "abc").unwrap();
+///
+/// cookie.set_path(Some("/app"));
+/// jar.store("example.com", "/app/login", &SetCookieHeader::new(vec![cookie]));
+///
+/// assert!(jar.cookies("example.com", "/", false).is_none());
+///
+/// let header = jar.cookies("example.com", "/app/home", false).unwrap();
+///
+/// assert_eq!(Some("abc"), header.get("session"));
+/// ```
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+    next_creation: u64,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        CookieJar {
+            cookies: vec![],
+            next_creation: 0,
+        }
+    }
+
+    /// Stores the cookies of a response to a request for `host` and `path`.
+    /// Cookies with a `Domain` attribute that does not match `host` are rejected.
+    pub fn store(&mut self, host: &str, path: &str, header: &SetCookieHeader) {
+        let now = SystemTime::now();
+
+        for cookie in header.cookies() {
+            self.store_at(host, path, cookie, now);
+        }
+    }
+
+    /// Builds the `Cookie` header for a request to `host` and `path`.
+    /// Secure cookies are only included when `secure` is `true`.
+    pub fn cookies(&self, host: &str, path: &str, secure: bool) -> Option<CookieHeader> {
+        self.cookies_at(host, path, secure, SystemTime::now())
+    }
+
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    fn store_at(&mut self, host: &str, path: &str, cookie: &SetCookie, now: SystemTime) {
+        let host = host.to_ascii_lowercase();
+
+        let expiry = match (cookie.max_age(), cookie.expires()) {
+            (Some(max_age), _) if max_age <= 0 => Some(UNIX_EPOCH),
+            (Some(max_age), _) => {
+                let expiry = now.checked_add(Duration::from_secs(max_age as u64));
+
+                Some(expiry.unwrap_or_else(|| UNIX_EPOCH + Duration::from_secs(LATEST_EXPIRY)))
+            }
+            (None, expires) => expires.map(SystemTime::from),
+        };
+
+        let (domain, host_only) = match cookie.domain() {
+            Some(domain) if domain_match(&host, domain) => (domain.to_string(), false),
+            Some(_) => return,
+            None => (host.clone(), true),
+        };
+
+        let path = match cookie.path() {
+            Some(path) => path.to_string(),
+            None => default_path(path),
+        };
+
+        let existing = self.cookies
+            .iter()
+            .position(|stored| {
+                stored.name == cookie.name() && stored.domain == domain && stored.path == path
+            });
+
+        let creation = match existing {
+            Some(index) => self.cookies.remove(index).creation,
+            None => {
+                self.next_creation += 1;
+                self.next_creation
+            }
+        };
+
+        let stored = StoredCookie {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            path,
+            host_only,
+            expiry,
+            secure: cookie.secure(),
+            creation,
+        };
+
+        if !stored.is_expired(now) {
+            self.cookies.push(stored);
+        }
+    }
+
+    fn cookies_at(&self,
+                  host: &str,
+                  path: &str,
+                  secure: bool,
+                  now: SystemTime)
+                  -> Option<CookieHeader> {
+        let host = host.to_ascii_lowercase();
+
+        let mut matching: Vec<&StoredCookie> = self.cookies
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now) && cookie.matches(&host, path, secure))
+            .collect();
+
+        if matching.is_empty() {
+            return None;
+        }
+
+        // Longer paths first, then older cookies first
+        matching.sort_by(|a, b| {
+            b.path.len().cmp(&a.path.len()).then(a.creation.cmp(&b.creation))
+        });
+
+        let mut header = CookieHeader::new();
+
+        for cookie in matching {
+            header.push(cookie.name.as_str(), cookie.value.as_str());
+        }
+
+        Some(header)
+    }
+}
+
+/// [RFC6265, Section 5.1.3](http://www.iana.org/go/rfc6265)
+fn domain_match(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+
+    host.len() > domain.len() && host.ends_with(domain) &&
+    host.as_bytes()[host.len() - domain.len() - 1] == b'.' &&
+    host.parse::<IpAddr>().is_err()
+}
+
+/// [RFC6265, Section 5.1.4](http://www.iana.org/go/rfc6265)
+fn default_path(path: &str) -> String {
+    if !path.starts_with('/') {
+        return "/".to_string();
+    }
+
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => path[..index].to_string(),
+    }
+}
+
+/// [RFC6265, Section 5.1.4](http://www.iana.org/go/rfc6265)
+fn path_match(path: &str, cookie_path: &str) -> bool {
+    path == cookie_path ||
+    (path.starts_with(cookie_path) &&
+     (cookie_path.ends_with('/') || path.as_bytes()[cookie_path.len()] == b'/'))
+}
+
+fn parse_max_age(value: &str) -> Option<i64> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    // Values too large to represent are clamped
+    Some(value.parse().unwrap_or(if value.starts_with('-') {
+        i64::MIN
+    } else {
+        i64::MAX
+    }))
+}
+
+/// Parses the lenient date format used by the `Expires` attribute.
+/// [RFC6265, Section 5.1.1](http://www.iana.org/go/rfc6265)
+fn parse_cookie_date(value: &str) -> Option<HttpDate> {
+    let is_delimiter = |c: char| {
+        c == '\t' || (' '..='/').contains(&c) || (';'..='@').contains(&c) ||
+        ('['..='`').contains(&c) || ('{'..='~').contains(&c)
+    };
+
+    let mut time = None;
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+
+    for token in value.split(is_delimiter).filter(|token| !token.is_empty()) {
+        if time.is_none() {
+            if let Some(parsed) = parse_time(token) {
+                time = Some(parsed);
+                continue;
+            }
+        }
+
+        if day.is_none() {
+            if let Some(parsed) = leading_digits(token, 1, 2) {
+                day = Some(parsed);
+                continue;
+            }
+        }
+
+        if month.is_none() && token.len() >= 3 && token.is_char_boundary(3) {
+            if let Some(parsed) = date::parse_month(&token[..3]) {
+                month = Some(parsed);
+                continue;
+            }
+        }
+
+        if year.is_none() {
+            if let Some(parsed) = leading_digits(token, 2, 4) {
+                year = Some(parsed);
+                continue;
+            }
+        }
+    }
+
+    let (hour, minute, second) = time?;
+    let year = match year? {
+        year @ 70..=99 => year + 1900,
+        year @ 0..=69 => year + 2000,
+        year => year,
+    };
+
+    if year < 1601 {
+        return None;
+    }
+
+    Civil::new(i64::from(year), month?, day?, hour, minute, second).map(HttpDate::from_civil)
+}
+
+fn parse_time(token: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = token.splitn(3, ':');
+    let hour = digits(parts.next()?, 1, 2)?;
+    let minute = digits(parts.next()?, 1, 2)?;
+    let second = leading_digits(parts.next()?, 1, 2)?;
+
+    Some((hour, minute, second))
+}
+
+/// Parses `min` to `max` digits at the start of `token`,
+/// which may be followed by anything but another digit.
+fn leading_digits(token: &str, min: usize, max: usize) -> Option<u32> {
+    let end = token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len());
+
+    digits(&token[..end], min, max)
+}
+
+fn digits(value: &str, min: usize, max: usize) -> Option<u32> {
+    if value.len() < min || value.len() > max || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::Headers;
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn test_cookie_header() {
+        let header = CookieHeader::parse(&[&RawHeader::new("Cookie", "a=1; b=2"),
+                                           &RawHeader::new("Cookie", "c=3")])
+            .unwrap();
+
+        assert_eq!(Some("2"), header.get("b"));
+        assert_eq!(Some("3"), header.get("c"));
+        assert_eq!(vec!["a=1; b=2; c=3".to_string()], header.raw_values());
+    }
+
+    #[test]
+    fn test_parse_set_cookie() {
+        let cookie = SetCookie::parse("SID=31d4d96e407aad42; Path=/; Domain=.Example.com; \
+                                       Secure; HttpOnly; SameSite=lax; Max-Age=3600; \
+                                       Expires=Wed, 09 Jun 2021 10:18:14 GMT; Foo=bar")
+            .unwrap();
+
+        assert_eq!("SID", cookie.name());
+        assert_eq!("31d4d96e407aad42", cookie.value());
+        assert_eq!(Some("/"), cookie.path());
+        assert_eq!(Some("example.com"), cookie.domain());
+        assert!(cookie.secure());
+        assert!(cookie.http_only());
+        assert_eq!(Some(SameSite::Lax), cookie.same_site());
+        assert_eq!(Some(3600), cookie.max_age());
+        assert_eq!(Some(HttpDate::from(at(1_623_233_894))), cookie.expires());
+    }
+
+    #[test]
+    fn test_parse_invalid_set_cookie() {
+        assert!(SetCookie::parse("foo").is_none());
+        assert!(SetCookie::parse("=bar").is_none());
+
+        let cookie = SetCookie::parse("a=b; Max-Age=1x; Path=foo; Expires=never").unwrap();
+
+        assert_eq!(None, cookie.max_age());
+        assert_eq!(None, cookie.path());
+        assert_eq!(None, cookie.expires());
+    }
+
+    #[test]
+    fn test_invalid_set_cookie() {
+        assert!(SetCookie::new("a", "x; Domain=evil.com; HttpOnly").is_none());
+        assert!(SetCookie::new("a b", "1").is_none());
+        assert!(SetCookie::new("a", "1 2").is_none());
+        assert!(SetCookie::new("a", "\"1\"").is_some());
+        assert!(SetCookie::new("a", "").is_some());
+
+        let mut cookie = SetCookie::new("a", "1").unwrap();
+
+        assert!(!cookie.set_domain(Some("example.com; Secure")));
+        assert!(!cookie.set_domain(Some("example..com")));
+        assert!(!cookie.set_path(Some("/; HttpOnly")));
+        assert!(!cookie.set_path(Some("/\r\n")));
+        assert!(cookie.set_domain(Some("sub-1.example.com")));
+        assert!(cookie.set_path(Some("/a b")));
+        assert_eq!("a=1; Domain=sub-1.example.com; Path=/a b", cookie.to_string());
+    }
+
+    #[test]
+    fn test_cookie_date() {
+        let expected = Some(HttpDate::from(at(784_111_777)));
+
+        assert_eq!(expected, parse_cookie_date("Sun, 06 Nov 1994 08:49:37 GMT"));
+        assert_eq!(expected, parse_cookie_date("Sunday, 06-Nov-94 08:49:37 GMT"));
+        assert_eq!(expected, parse_cookie_date("Sun Nov  6 08:49:37 1994"));
+        assert_eq!(None, parse_cookie_date("Sun, 31 Feb 1994 08:49:37 GMT"));
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 1994"));
+    }
+
+    #[test]
+    fn test_set_cookie_header() {
+        let mut first = SetCookie::new("a", "1").unwrap();
+        let mut second = SetCookie::new("b", "2").unwrap();
+
+        first.set_expires(Some(HttpDate::from(at(784_111_777))));
+        first.set_http_only(true);
+        second.set_path(Some("/docs"));
+        second.set_same_site(Some(SameSite::Strict));
+
+        let mut headers = Headers::new();
+
+        headers.append(SetCookieHeader::new(vec![first, second]));
+
+        let raw = headers.get_raw("set-cookie");
+
+        assert_eq!(2, raw.len());
+        assert_eq!("a=1; Expires=Sun, 06 Nov 1994 08:49:37 GMT; HttpOnly",
+                   raw[0].value());
+        assert_eq!("b=2; Path=/docs; SameSite=Strict", raw[1].value());
+
+        let parsed: SetCookieHeader = headers.get().unwrap();
+
+        assert_eq!(2, parsed.cookies().len());
+    }
+
+    #[test]
+    fn test_jar_domain_matching() {
+        let mut jar = CookieJar::new();
+        let now = at(1000);
+        let mut shared = SetCookie::new("shared", "1").unwrap();
+        let mut foreign = SetCookie::new("foreign", "1").unwrap();
+
+        shared.set_domain(Some("example.com"));
+        foreign.set_domain(Some("example.org"));
+
+        jar.store_at("www.example.com", "/", &shared, now);
+        jar.store_at("www.example.com", "/", &foreign, now);
+        jar.store_at("www.example.com", "/", &SetCookie::new("host", "1").unwrap(), now);
+
+        assert_eq!(2, jar.len());
+
+        let sub = jar.cookies_at("api.example.com", "/", false, now).unwrap();
+
+        assert_eq!(Some("1"), sub.get("shared"));
+        assert_eq!(None, sub.get("host"));
+
+        let host = jar.cookies_at("WWW.example.com", "/", false, now).unwrap();
+
+        assert_eq!(2, host.cookies().len());
+        assert!(jar.cookies_at("notexample.com", "/", false, now).is_none());
+    }
+
+    #[test]
+    fn test_jar_paths_and_order() {
+        let mut jar = CookieJar::new();
+        let now = at(1000);
+        let mut root = SetCookie::new("root", "1").unwrap();
+        let mut secure = SetCookie::new("secure", "1").unwrap();
+
+        root.set_path(Some("/"));
+        secure.set_secure(true);
+
+        jar.store_at("example.com", "/docs/index.html", &root, now);
+        jar.store_at("example.com", "/docs/index.html", &SetCookie::new("docs", "1").unwrap(), now);
+        jar.store_at("example.com", "/docs/index.html", &secure, now);
+
+        let header = jar.cookies_at("example.com", "/docs/api", false, now).unwrap();
+
+        assert_eq!(vec!["docs=1; root=1".to_string()], header.raw_values());
+        assert!(jar.cookies_at("example.com", "/docsx", false, now).unwrap().get("docs").is_none());
+
+        let header = jar.cookies_at("example.com", "/docs", true, now).unwrap();
+
+        assert_eq!(Some("1"), header.get("secure"));
+    }
+
+    #[test]
+    fn test_jar_expiry() {
+        let mut jar = CookieJar::new();
+        let mut cookie = SetCookie::new("a", "1").unwrap();
+
+        cookie.set_max_age(Some(10));
+        jar.store_at("example.com", "/", &cookie, at(1000));
+
+        assert!(jar.cookies_at("example.com", "/", false, at(1009)).is_some());
+        assert!(jar.cookies_at("example.com", "/", false, at(1010)).is_none());
+
+        cookie.set_max_age(Some(0));
+        jar.store_at("example.com", "/", &cookie, at(1000));
+
+        assert!(jar.is_empty());
+
+        cookie.set_max_age(Some(i64::MAX));
+        jar.store_at("example.com", "/", &cookie, at(1000));
+
+        assert!(jar.cookies_at("example.com", "/", false, at(LATEST_EXPIRY - 1)).is_some());
+        assert!(jar.cookies_at("example.com", "/", false, at(LATEST_EXPIRY)).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+use super::super::method::Method;
+
+/// The `Origin` request header, either a serialized origin like
+/// `https://example.com:8080` or `null`.
+/// [RFC6454, Section 7](http://www.iana.org/go/rfc6454)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OriginHeader {
+    value: String,
+}
+
+impl OriginHeader {
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        OriginHeader { value: value.into() }
+    }
+
+    pub fn null() -> Self {
+        OriginHeader::new("null")
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.value == "null"
+    }
+}
+
+impl TypedHeader for OriginHeader {
+    fn name() -> &'static str {
+        "origin"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Origin"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value().trim();
+
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(OriginHeader::new(value))
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.clone()]
+    }
+}
+
+/// The value of `Access-Control-Allow-Origin`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AllowOrigin {
+    /// `*`, which cannot be used for requests with credentials
+    Any,
+    Null,
+    Origin(String),
+}
+
+impl fmt::Display for AllowOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AllowOrigin::Any => write!(f, "*"),
+            AllowOrigin::Null => write!(f, "null"),
+            AllowOrigin::Origin(ref origin) => write!(f, "{}", origin),
+        }
+    }
+}
+
+/// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-allow-origin)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AccessControlAllowOriginHeader {
+    value: AllowOrigin,
+}
+
+impl AccessControlAllowOriginHeader {
+    pub fn new(value: AllowOrigin) -> Self {
+        AccessControlAllowOriginHeader { value }
+    }
+
+    pub fn value(&self) -> &AllowOrigin {
+        &self.value
+    }
+}
+
+impl TypedHeader for AccessControlAllowOriginHeader {
+    fn name() -> &'static str {
+        "access-control-allow-origin"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Access-Control-Allow-Origin"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = match raw.first()?.value().trim() {
+            "" => return None,
+            "*" => AllowOrigin::Any,
+            "null" => AllowOrigin::Null,
+            origin => AllowOrigin::Origin(origin.to_string()),
+        };
+
+        Some(AccessControlAllowOriginHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+/// Only `true` is a valid value, the header is omitted otherwise.
+/// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-allow-credentials)
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct AccessControlAllowCredentialsHeader;
+
+impl TypedHeader for AccessControlAllowCredentialsHeader {
+    fn name() -> &'static str {
+        "access-control-allow-credentials"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Access-Control-Allow-Credentials"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        match raw.first()?.value().trim() {
+            "true" => Some(AccessControlAllowCredentialsHeader),
+            _ => None,
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec!["true".to_string()]
+    }
+}
+
+/// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-request-method)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AccessControlRequestMethodHeader {
+    method: Method,
+}
+
+impl AccessControlRequestMethodHeader {
+    pub fn new(method: Method) -> Self {
+        AccessControlRequestMethodHeader { method }
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+}
+
+impl TypedHeader for AccessControlRequestMethodHeader {
+    fn name() -> &'static str {
+        "access-control-request-method"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Access-Control-Request-Method"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let method = raw.first()?.value().trim().parse().ok()?;
+
+        Some(AccessControlRequestMethodHeader { method })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.method.to_string()]
+    }
+}
+
+/// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-allow-methods)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AccessControlAllowMethodsHeader {
+    methods: CommaDelimited<Method>,
+}
+
+impl AccessControlAllowMethodsHeader {
+    pub fn new(methods: Vec<Method>) -> Self {
+        AccessControlAllowMethodsHeader { methods: CommaDelimited::new(methods) }
+    }
+
+    pub fn methods(&self) -> &[Method] {
+        self.methods.items()
+    }
+}
+
+impl TypedHeader for AccessControlAllowMethodsHeader {
+    fn name() -> &'static str {
+        "access-control-allow-methods"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Access-Control-Allow-Methods"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let methods = CommaDelimited::from_raw(raw).ok()?;
+
+        Some(AccessControlAllowMethodsHeader { methods })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.methods.to_string()]
+    }
+}
+
+macro_rules! field_names_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            names: CommaDelimited<String>,
+        }
+
+        impl $header {
+            pub fn new(names: Vec<String>) -> Self {
+                $header { names: CommaDelimited::new(names) }
+            }
+
+            pub fn names(&self) -> &[String] {
+                self.names.items()
+            }
+
+            /// Checks whether `name` is contained in the list, ignoring case.
+            pub fn contains(&self, name: &str) -> bool {
+                self.names.iter().any(|other| other.eq_ignore_ascii_case(name))
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                if raw.is_empty() {
+                    return None;
+                }
+
+                let names: CommaDelimited<String> = CommaDelimited::from_raw(raw).ok()?;
+
+                if names.iter().all(|name| name == "*" || syntax::is_token(name)) {
+                    Some($header { names })
+                } else {
+                    None
+                }
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                vec![self.names.to_string()]
+            }
+        }
+    }
+}
+
+field_names_header!(
+    /// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-request-headers)
+    AccessControlRequestHeadersHeader,
+    "access-control-request-headers",
+    "Access-Control-Request-Headers");
+
+field_names_header!(
+    /// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-allow-headers)
+    AccessControlAllowHeadersHeader,
+    "access-control-allow-headers",
+    "Access-Control-Allow-Headers");
+
+field_names_header!(
+    /// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-expose-headers)
+    AccessControlExposeHeadersHeader,
+    "access-control-expose-headers",
+    "Access-Control-Expose-Headers");
+
+/// The number of seconds a preflight result may be cached.
+/// [Fetch Standard, CORS protocol](https://fetch.spec.whatwg.org/#http-access-control-max-age)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AccessControlMaxAgeHeader {
+    seconds: u64,
+}
+
+impl AccessControlMaxAgeHeader {
+    pub fn new(seconds: u64) -> Self {
+        AccessControlMaxAgeHeader { seconds }
+    }
+
+    pub fn seconds(&self) -> u64 {
+        self.seconds
+    }
+}
+
+impl TypedHeader for AccessControlMaxAgeHeader {
+    fn name() -> &'static str {
+        "access-control-max-age"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Access-Control-Max-Age"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let seconds = syntax::parse_delta_seconds(raw.first()?.value().trim())?;
+
+        Some(AccessControlMaxAgeHeader { seconds })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.seconds.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_origin() {
+        let origin = OriginHeader::parse(&[&RawHeader::new("Origin", "https://example.com")])
+            .unwrap();
+
+        assert_eq!("https://example.com", origin.value());
+        assert!(!origin.is_null());
+        assert!(OriginHeader::null().is_null());
+        assert!(OriginHeader::parse(&[&RawHeader::new("Origin", "")]).is_none());
+    }
+
+    #[test]
+    fn test_allow_origin() {
+        let parse = |value| {
+            AccessControlAllowOriginHeader::parse(&[&RawHeader::new("Access-Control-Allow-Origin",
+                                                                    value)])
+                .map(|header| header.value().clone())
+        };
+
+        assert_eq!(Some(AllowOrigin::Any), parse("*"));
+        assert_eq!(Some(AllowOrigin::Null), parse("null"));
+        assert_eq!(Some(AllowOrigin::Origin("http://a.test".to_string())),
+                   parse("http://a.test"));
+    }
+
+    #[test]
+    fn test_methods() {
+        let request = AccessControlRequestMethodHeader::parse(&[&RawHeader::new("Access-Control-Request-Method", "PUT")])
+            .unwrap();
+        let allow = AccessControlAllowMethodsHeader::parse(&[&RawHeader::new("Access-Control-Allow-Methods",
+                                                                             "GET, PUT,PATCH")])
+            .unwrap();
+
+        assert_eq!(&Method::Put, request.method());
+        assert_eq!(&[Method::Get, Method::Put, Method::Patch], allow.methods());
+        assert_eq!(vec!["GET, PUT, PATCH".to_string()], allow.raw_values());
+    }
+
+    #[test]
+    fn test_field_names() {
+        let request = AccessControlRequestHeadersHeader::parse(&[&RawHeader::new("Access-Control-Request-Headers",
+                                                                                 "x-custom, content-type")])
+            .unwrap();
+
+        assert!(request.contains("Content-Type"));
+        assert!(!request.contains("accept"));
+        assert!(AccessControlExposeHeadersHeader::parse(&[&RawHeader::new("Access-Control-Expose-Headers",
+                                                                          "a b")])
+            .is_none());
+    }
+
+    #[test]
+    fn test_credentials_and_max_age() {
+        assert!(AccessControlAllowCredentialsHeader::parse(&[&RawHeader::new("Access-Control-Allow-Credentials",
+                                                                             "TRUE")])
+            .is_none());
+        assert_eq!(Some(AccessControlMaxAgeHeader::new(600)),
+                   AccessControlMaxAgeHeader::parse(&[&RawHeader::new("Access-Control-Max-Age",
+                                                                      "600")]));
+    }
+}
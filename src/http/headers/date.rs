@@ -0,0 +1,210 @@
+use std::fmt;
+use std::time::UNIX_EPOCH;
+use super::{TypedHeader, RawHeader};
+use super::super::date::HttpDate;
+
+macro_rules! date_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            value: HttpDate,
+        }
+
+        impl $header {
+            pub fn new(value: HttpDate) -> Self {
+                $header { value }
+            }
+
+            pub fn value(&self) -> HttpDate {
+                self.value
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                let value = raw.first()?.value().parse().ok()?;
+
+                Some($header { value })
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                vec![self.value.to_string()]
+            }
+        }
+    }
+}
+
+date_header!(
+    /// [RFC7231, Section 7.1.1.2](http://www.iana.org/go/rfc7231)
+    DateHeader, "date", "Date");
+
+date_header!(
+    /// [RFC7232, Section 2.2](http://www.iana.org/go/rfc7232)
+    LastModifiedHeader, "last-modified", "Last-Modified");
+
+date_header!(
+    /// [RFC7232, Section 3.3](http://www.iana.org/go/rfc7232)
+    IfModifiedSinceHeader, "if-modified-since", "If-Modified-Since");
+
+date_header!(
+    /// [RFC7232, Section 3.4](http://www.iana.org/go/rfc7232)
+    IfUnmodifiedSinceHeader, "if-unmodified-since", "If-Unmodified-Since");
+
+impl DateHeader {
+    /// Origin servers with a clock must send a `Date` header
+    /// in all but 1xx and 5xx responses.
+    pub fn now() -> Self {
+        DateHeader::new(HttpDate::now())
+    }
+}
+
+/// [RFC7234, Section 5.3](http://www.iana.org/go/rfc7234)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ExpiresHeader {
+    value: HttpDate,
+}
+
+impl ExpiresHeader {
+    pub fn new(value: HttpDate) -> Self {
+        ExpiresHeader { value }
+    }
+
+    pub fn value(&self) -> HttpDate {
+        self.value
+    }
+}
+
+impl TypedHeader for ExpiresHeader {
+    fn name() -> &'static str {
+        "expires"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Expires"
+    }
+
+    /// Invalid dates such as `0` are treated as a time in the past.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?
+            .value()
+            .parse()
+            .unwrap_or_else(|_| HttpDate::from(UNIX_EPOCH));
+
+        Some(ExpiresHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RetryAfter {
+    Date(HttpDate),
+    /// Delay in seconds
+    Delay(u64),
+}
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RetryAfter::Date(date) => write!(f, "{}", date),
+            RetryAfter::Delay(seconds) => write!(f, "{}", seconds),
+        }
+    }
+}
+
+/// [RFC7231, Section 7.1.3](http://www.iana.org/go/rfc7231)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct RetryAfterHeader {
+    value: RetryAfter,
+}
+
+impl RetryAfterHeader {
+    pub fn new(value: RetryAfter) -> Self {
+        RetryAfterHeader { value }
+    }
+
+    pub fn value(&self) -> RetryAfter {
+        self.value
+    }
+}
+
+impl TypedHeader for RetryAfterHeader {
+    fn name() -> &'static str {
+        "retry-after"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Retry-After"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value();
+
+        let value = if !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()) {
+            RetryAfter::Delay(value.parse().ok()?)
+        } else {
+            RetryAfter::Date(value.parse().ok()?)
+        };
+
+        Some(RetryAfterHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::Headers;
+
+    #[test]
+    fn test_date_headers() {
+        let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+        let mut headers = Headers::new();
+
+        headers.append(LastModifiedHeader::new(date));
+        headers.append_raw(RawHeader::new("If-Modified-Since", "Sunday, 06-Nov-94 08:49:37 GMT"));
+        headers.append_raw(RawHeader::new("If-Unmodified-Since", "yesterday"));
+
+        assert_eq!("Sun, 06 Nov 1994 08:49:37 GMT",
+                   headers.get_raw("last-modified")[0].value());
+        assert_eq!(Some(LastModifiedHeader::new(date)), headers.get());
+        assert_eq!(Some(IfModifiedSinceHeader::new(date)), headers.get());
+        assert_eq!(None, headers.get::<IfUnmodifiedSinceHeader>());
+        assert_eq!(None, headers.get::<DateHeader>());
+    }
+
+    #[test]
+    fn test_expires() {
+        let header = ExpiresHeader::parse(&[&RawHeader::new("Expires", "0")]).unwrap();
+
+        assert_eq!(HttpDate::from(UNIX_EPOCH), header.value());
+        assert!(ExpiresHeader::parse(&[]).is_none());
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let delay = RetryAfterHeader::parse(&[&RawHeader::new("Retry-After", "120")]).unwrap();
+        let date = RetryAfterHeader::parse(&[&RawHeader::new("Retry-After",
+                                                              "Fri, 31 Dec 1999 23:59:59 GMT")])
+            .unwrap();
+
+        assert_eq!(RetryAfter::Delay(120), delay.value());
+        assert_eq!(vec!["Fri, 31 Dec 1999 23:59:59 GMT".to_string()],
+                   date.raw_values());
+        assert!(RetryAfterHeader::parse(&[&RawHeader::new("Retry-After", "-1")]).is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::quality::{Quality, QualityItem};
+use super::syntax;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidContentCoding;
+
+/// A content coding, compared case-insensitively.
+/// [RFC7231, Section 3.1.2.1](http://www.iana.org/go/rfc7231)
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ContentCoding {
+    /// Also matches the legacy `x-gzip`
+    Gzip,
+    /// The zlib format, despite its name
+    Deflate,
+    /// [RFC7932](http://www.iana.org/go/rfc7932)
+    Br,
+    /// Also matches the legacy `x-compress`
+    Compress,
+    Identity,
+    /// Catch-All for all other codings, in lower case
+    Extension(String),
+}
+
+impl ContentCoding {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            ContentCoding::Gzip => "gzip",
+            ContentCoding::Deflate => "deflate",
+            ContentCoding::Br => "br",
+            ContentCoding::Compress => "compress",
+            ContentCoding::Identity => "identity",
+            ContentCoding::Extension(ref coding) => coding,
+        }
+    }
+}
+
+impl FromStr for ContentCoding {
+    type Err = InvalidContentCoding;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !syntax::is_token(s) {
+            return Err(InvalidContentCoding);
+        }
+
+        Ok(match s.to_lowercase().as_str() {
+            "gzip" | "x-gzip" => ContentCoding::Gzip,
+            "deflate" => ContentCoding::Deflate,
+            "br" => ContentCoding::Br,
+            "compress" | "x-compress" => ContentCoding::Compress,
+            "identity" => ContentCoding::Identity,
+            coding => ContentCoding::Extension(coding.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ContentCoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The codings applied to the representation, in the order they were applied.
+/// [RFC7231, Section 3.1.2.2](http://www.iana.org/go/rfc7231)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ContentEncodingHeader {
+    codings: CommaDelimited<ContentCoding>,
+}
+
+impl ContentEncodingHeader {
+    pub fn new(codings: Vec<ContentCoding>) -> Self {
+        ContentEncodingHeader { codings: CommaDelimited::new(codings) }
+    }
+
+    pub fn codings(&self) -> &[ContentCoding] {
+        self.codings.items()
+    }
+}
+
+impl TypedHeader for ContentEncodingHeader {
+    fn name() -> &'static str {
+        "content-encoding"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Content-Encoding"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let codings = CommaDelimited::from_raw(raw).ok()?;
+
+        if codings.is_empty() {
+            return None;
+        }
+
+        Some(ContentEncodingHeader { codings })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.codings.to_string()]
+    }
+}
+
+/// An element of [`AcceptEncodingHeader`].
+///
+/// [`AcceptEncodingHeader`]: struct.AcceptEncodingHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CodingRange {
+    /// `*`, matching any coding not explicitly listed
+    Any,
+    Coding(ContentCoding),
+}
+
+impl FromStr for CodingRange {
+    type Err = InvalidContentCoding;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "*" => Ok(CodingRange::Any),
+            coding => coding.parse().map(CodingRange::Coding),
+        }
+    }
+}
+
+impl fmt::Display for CodingRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodingRange::Any => write!(f, "*"),
+            CodingRange::Coding(ref coding) => write!(f, "{}", coding),
+        }
+    }
+}
+
+/// The content codings acceptable in a response.
+/// [RFC7231, Section 5.3.4](http://www.iana.org/go/rfc7231)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{AcceptEncodingHeader, ContentCoding, RawHeader, TypedHeader};
+///
+/// let raw = RawHeader::new("Accept-Encoding", "gzip;q=0.5, br, identity;q=0");
+/// let accept = AcceptEncodingHeader::parse(&[&raw]).unwrap();
+///
+/// assert_eq!(Some(ContentCoding::Br),
+///            accept.negotiate(&[ContentCoding::Gzip, ContentCoding::Br]));
+/// assert_eq!(None, accept.negotiate(&[ContentCoding::Deflate]));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AcceptEncodingHeader {
+    items: Vec<QualityItem<CodingRange>>,
+}
+
+impl AcceptEncodingHeader {
+    pub fn new(items: Vec<QualityItem<CodingRange>>) -> Self {
+        AcceptEncodingHeader { items }
+    }
+
+    pub fn items(&self) -> &[QualityItem<CodingRange>] {
+        &self.items
+    }
+
+    /// The weight of `coding`. `identity` is acceptable unless
+    /// it is excluded explicitly or by `*;q=0`.
+    pub fn quality(&self, coding: &ContentCoding) -> Quality {
+        let mut any = None;
+
+        for item in &self.items {
+            match *item.item() {
+                CodingRange::Coding(ref other) if other == coding => return item.quality(),
+                CodingRange::Any => any = Some(item.quality()),
+                _ => {}
+            }
+        }
+
+        match (any, coding) {
+            (Some(quality), _) => quality,
+            (None, &ContentCoding::Identity) => Quality::one(),
+            (None, _) => Quality::zero(),
+        }
+    }
+
+    /// Selects the acceptable coding with the highest weight from `available`,
+    /// preferring earlier codings on ties.
+    /// Falls back to `identity` if none are acceptable,
+    /// returns `None` if `identity` is not acceptable either.
+    pub fn negotiate(&self, available: &[ContentCoding]) -> Option<ContentCoding> {
+        let mut best: Option<(&ContentCoding, Quality)> = None;
+
+        for coding in available {
+            let quality = self.quality(coding);
+
+            if !quality.is_zero() && best.map_or(true, |(_, best)| quality > best) {
+                best = Some((coding, quality));
+            }
+        }
+
+        match best {
+            Some((coding, _)) => Some(coding.clone()),
+            None if !self.quality(&ContentCoding::Identity).is_zero() => {
+                Some(ContentCoding::Identity)
+            }
+            None => None,
+        }
+    }
+}
+
+impl TypedHeader for AcceptEncodingHeader {
+    fn name() -> &'static str {
+        "accept-encoding"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Accept-Encoding"
+    }
+
+    /// An empty value is valid and means that only `identity` is acceptable.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        CommaDelimited::from_raw(raw)
+            .ok()
+            .map(|items| AcceptEncodingHeader::new(items.into_items()))
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let items: Vec<String> = self.items.iter().map(QualityItem::to_string).collect();
+
+        vec![items.join(", ")]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn accept(value: &str) -> AcceptEncodingHeader {
+        AcceptEncodingHeader::parse(&[&RawHeader::new("Accept-Encoding", value)]).unwrap()
+    }
+
+    #[test]
+    fn test_content_coding() {
+        assert_eq!(Ok(ContentCoding::Gzip), "X-GZIP".parse());
+        assert_eq!(Ok(ContentCoding::Extension("zstd".to_string())), "zstd".parse());
+        assert_eq!(Err(InvalidContentCoding), "g zip".parse::<ContentCoding>());
+    }
+
+    #[test]
+    fn test_content_encoding() {
+        let header = ContentEncodingHeader::parse(&[&RawHeader::new("Content-Encoding",
+                                                                    "deflate, Gzip")])
+            .unwrap();
+
+        assert_eq!(&[ContentCoding::Deflate, ContentCoding::Gzip], header.codings());
+        assert_eq!(vec!["deflate, gzip".to_string()], header.raw_values());
+    }
+
+    #[test]
+    fn test_quality() {
+        let header = accept("gzip;q=0.8, *;q=0.1");
+
+        assert_eq!(Quality::from_millis(800).unwrap(),
+                   header.quality(&ContentCoding::Gzip));
+        assert_eq!(Quality::from_millis(100).unwrap(), header.quality(&ContentCoding::Br));
+        assert_eq!(Quality::one(), accept("gzip").quality(&ContentCoding::Identity));
+        assert_eq!(Quality::zero(), accept("gzip").quality(&ContentCoding::Br));
+        assert_eq!(Quality::zero(), accept("*;q=0").quality(&ContentCoding::Identity));
+        assert!(AcceptEncodingHeader::parse(&[&RawHeader::new("Accept-Encoding",
+                                                              "gzip;q=2")])
+            .is_none());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let available = [ContentCoding::Br, ContentCoding::Gzip];
+
+        assert_eq!(Some(ContentCoding::Gzip),
+                   accept("br;q=0.5, gzip").negotiate(&available));
+        assert_eq!(Some(ContentCoding::Br), accept("gzip, br").negotiate(&available));
+        assert_eq!(Some(ContentCoding::Identity), accept("").negotiate(&available));
+        assert_eq!(None,
+                   accept("deflate, identity;q=0").negotiate(&available));
+        assert_eq!(vec!["gzip;q=0.5, *".to_string()], accept("gzip;q=0.5,*").raw_values());
+    }
+}
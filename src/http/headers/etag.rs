@@ -0,0 +1,322 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidEntityTag;
+
+/// An opaque validator, optionally marked as weak.
+/// [RFC7232, Section 2.3](http://www.iana.org/go/rfc7232)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::EntityTag;
+///
+/// let weak: EntityTag = "W/\"xyzzy\"".parse().unwrap();
+/// let strong = EntityTag::strong("xyzzy").unwrap();
+///
+/// assert!(weak.weak_eq(&strong));
+/// assert!(!weak.strong_eq(&strong));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct EntityTag {
+    weak: bool,
+    tag: String,
+}
+
+impl EntityTag {
+    /// Returns `None` if `tag` contains a `"` or characters that are not allowed
+    /// in an entity tag.
+    pub fn new<S: Into<String>>(weak: bool, tag: S) -> Option<Self> {
+        let tag = tag.into();
+
+        if !tag.chars().all(is_etagc) {
+            return None;
+        }
+
+        Some(EntityTag { weak, tag })
+    }
+
+    pub fn strong<S: Into<String>>(tag: S) -> Option<Self> {
+        EntityTag::new(false, tag)
+    }
+
+    pub fn weak<S: Into<String>>(tag: S) -> Option<Self> {
+        EntityTag::new(true, tag)
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// The opaque tag without quotes.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// [RFC7232, Section 2.3.2](http://www.iana.org/go/rfc7232)
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// [RFC7232, Section 2.3.2](http://www.iana.org/go/rfc7232)
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl FromStr for EntityTag {
+    type Err = InvalidEntityTag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (weak, quoted) = match s.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, s),
+        };
+
+        if quoted.len() < 2 || !quoted.starts_with('"') || !quoted.ends_with('"') {
+            return Err(InvalidEntityTag);
+        }
+
+        EntityTag::new(weak, &quoted[1..quoted.len() - 1]).ok_or(InvalidEntityTag)
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/")?;
+        }
+
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+fn is_etagc(c: char) -> bool {
+    c == '!' || (c >= '#' && c != '\x7f')
+}
+
+/// [RFC7232, Section 2.3](http://www.iana.org/go/rfc7232)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ETagHeader {
+    value: EntityTag,
+}
+
+impl ETagHeader {
+    pub fn new(value: EntityTag) -> Self {
+        ETagHeader { value }
+    }
+
+    pub fn value(&self) -> &EntityTag {
+        &self.value
+    }
+}
+
+impl TypedHeader for ETagHeader {
+    fn name() -> &'static str {
+        "etag"
+    }
+
+    fn canonical_name() -> &'static str {
+        "ETag"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value().parse().ok()?;
+
+        Some(ETagHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+/// The value of `If-Match` and `If-None-Match`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum EntityTagMatch {
+    /// `*` matches any current representation
+    Any,
+    Tags(Vec<EntityTag>),
+}
+
+impl EntityTagMatch {
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        match CommaDelimited::from_raw(raw) {
+            Ok(tags) => Some(EntityTagMatch::Tags(tags.into_items())),
+            Err(InvalidEntityTag) if raw.len() == 1 && raw[0].value().trim() == "*" => {
+                Some(EntityTagMatch::Any)
+            }
+            Err(InvalidEntityTag) => None,
+        }
+    }
+
+    /// Returns `true` if any of the tags matches `etag` using `eq`.
+    /// `Any` matches if there is a current representation.
+    fn matches<F>(&self, etag: Option<&EntityTag>, eq: F) -> bool
+        where F: Fn(&EntityTag, &EntityTag) -> bool
+    {
+        match (self, etag) {
+            (EntityTagMatch::Any, etag) => etag.is_some(),
+            (EntityTagMatch::Tags(tags), Some(etag)) => tags.iter().any(|tag| eq(tag, etag)),
+            (EntityTagMatch::Tags(_), None) => false,
+        }
+    }
+}
+
+impl fmt::Display for EntityTagMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EntityTagMatch::Any => write!(f, "*"),
+            EntityTagMatch::Tags(ref tags) => {
+                let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+
+                write!(f, "{}", tags.join(", "))
+            }
+        }
+    }
+}
+
+/// [RFC7232, Section 3.1](http://www.iana.org/go/rfc7232)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IfMatchHeader {
+    value: EntityTagMatch,
+}
+
+impl IfMatchHeader {
+    pub fn new(value: EntityTagMatch) -> Self {
+        IfMatchHeader { value }
+    }
+
+    pub fn value(&self) -> &EntityTagMatch {
+        &self.value
+    }
+
+    /// Evaluates the condition using the strong comparison function.
+    /// `etag` is the current entity tag or `None` if there is no current representation.
+    pub fn matches(&self, etag: Option<&EntityTag>) -> bool {
+        self.value.matches(etag, EntityTag::strong_eq)
+    }
+}
+
+impl TypedHeader for IfMatchHeader {
+    fn name() -> &'static str {
+        "if-match"
+    }
+
+    fn canonical_name() -> &'static str {
+        "If-Match"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        EntityTagMatch::parse(raw).map(IfMatchHeader::new)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+/// [RFC7232, Section 3.2](http://www.iana.org/go/rfc7232)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IfNoneMatchHeader {
+    value: EntityTagMatch,
+}
+
+impl IfNoneMatchHeader {
+    pub fn new(value: EntityTagMatch) -> Self {
+        IfNoneMatchHeader { value }
+    }
+
+    pub fn value(&self) -> &EntityTagMatch {
+        &self.value
+    }
+
+    /// Returns `true` if any of the tags matches `etag` using the weak comparison function,
+    /// i.e. when the condition itself evaluates to false.
+    /// `etag` is the current entity tag or `None` if there is no current representation.
+    pub fn matches(&self, etag: Option<&EntityTag>) -> bool {
+        self.value.matches(etag, EntityTag::weak_eq)
+    }
+}
+
+impl TypedHeader for IfNoneMatchHeader {
+    fn name() -> &'static str {
+        "if-none-match"
+    }
+
+    fn canonical_name() -> &'static str {
+        "If-None-Match"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        EntityTagMatch::parse(raw).map(IfNoneMatchHeader::new)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_entity_tag() {
+        assert_eq!(Ok(EntityTag::weak("").unwrap()), "W/\"\"".parse());
+        assert_eq!(Ok(EntityTag::strong("a,b").unwrap()), "\"a,b\"".parse());
+        assert_eq!(Err(InvalidEntityTag), "xyzzy".parse::<EntityTag>());
+        assert_eq!(Err(InvalidEntityTag), "w/\"xyzzy\"".parse::<EntityTag>());
+        assert_eq!(Err(InvalidEntityTag), "\"a\"b\"".parse::<EntityTag>());
+        assert_eq!("W/\"1\"", EntityTag::weak("1").unwrap().to_string());
+    }
+
+    #[test]
+    fn test_comparison() {
+        let strong = EntityTag::strong("1").unwrap();
+        let weak = EntityTag::weak("1").unwrap();
+
+        assert!(strong.strong_eq(&strong));
+        assert!(!weak.strong_eq(&weak));
+        assert!(weak.weak_eq(&strong));
+        assert!(!weak.weak_eq(&EntityTag::weak("2").unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_tag() {
+        assert_eq!(None, EntityTag::strong("\""));
+        assert_eq!(None, EntityTag::weak("a\x7f"));
+        assert!(EntityTag::strong("\u{e9}").is_some());
+    }
+
+    #[test]
+    fn test_if_match() {
+        let header = IfMatchHeader::parse(&[&RawHeader::new("If-Match", "\"a\", W/\"b\""),
+                                            &RawHeader::new("If-Match", "\"c\"")])
+            .unwrap();
+
+        assert!(header.matches(Some(&EntityTag::strong("c").unwrap())));
+        assert!(!header.matches(Some(&EntityTag::strong("b").unwrap())));
+        assert!(!header.matches(None));
+        assert_eq!(vec!["\"a\", W/\"b\", \"c\"".to_string()], header.raw_values());
+        assert!(IfMatchHeader::parse(&[&RawHeader::new("If-Match", "a")]).is_none());
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let any = IfNoneMatchHeader::parse(&[&RawHeader::new("If-None-Match", "*")]).unwrap();
+        let tags = IfNoneMatchHeader::parse(&[&RawHeader::new("If-None-Match", "W/\"b\"")])
+            .unwrap();
+
+        assert_eq!(&EntityTagMatch::Any, any.value());
+        assert!(any.matches(Some(&EntityTag::weak("x").unwrap())));
+        assert!(!any.matches(None));
+        assert!(tags.matches(Some(&EntityTag::strong("b").unwrap())));
+    }
+}
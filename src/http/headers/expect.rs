@@ -0,0 +1,108 @@
+use std::fmt;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+
+/// The value of [`ExpectHeader`].
+///
+/// [`ExpectHeader`]: struct.ExpectHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Expectation {
+    /// `100-continue`, compared case-insensitively
+    Continue,
+    /// Any other expectation, which a server can't meet
+    Extension(String),
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expectation::Continue => write!(f, "100-continue"),
+            Expectation::Extension(ref value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// [RFC7231, Section 5.1.1](http://www.iana.org/go/rfc7231)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ExpectHeader {
+    value: Expectation,
+}
+
+impl ExpectHeader {
+    pub fn new(value: Expectation) -> Self {
+        ExpectHeader { value }
+    }
+
+    pub fn hundred_continue() -> Self {
+        ExpectHeader::new(Expectation::Continue)
+    }
+
+    pub fn value(&self) -> &Expectation {
+        &self.value
+    }
+
+    pub fn is_continue(&self) -> bool {
+        self.value == Expectation::Continue
+    }
+}
+
+impl TypedHeader for ExpectHeader {
+    fn name() -> &'static str {
+        "expect"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Expect"
+    }
+
+    /// The expectations of all lines are combined. Unless every one of them
+    /// is `100-continue`, the header is parsed as an `Extension` of the whole list.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let expectations: CommaDelimited<String> = CommaDelimited::from_raw(raw).ok()?;
+
+        if expectations.is_empty() {
+            return None;
+        }
+
+        let is_continue = |expectation: &String| expectation.eq_ignore_ascii_case("100-continue");
+        let value = if expectations.iter().all(is_continue) {
+            Expectation::Continue
+        } else {
+            Expectation::Extension(expectations.to_string())
+        };
+
+        Some(ExpectHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |value| ExpectHeader::parse(&[&RawHeader::new("Expect", value)]);
+
+        assert_eq!(Some(ExpectHeader::hundred_continue()), parse("100-Continue"));
+        assert_eq!(Some(ExpectHeader::new(Expectation::Extension("foo=bar".to_string()))),
+                   parse("foo=bar"));
+        assert_eq!(None, parse(" "));
+        assert_eq!(vec!["100-continue".to_string()],
+                   ExpectHeader::hundred_continue().raw_values());
+    }
+
+    #[test]
+    fn test_parse_multiple_lines() {
+        let first = RawHeader::new("Expect", "100-continue");
+        let second = RawHeader::new("Expect", "foo, 100-continue");
+
+        assert_eq!(Some(ExpectHeader::hundred_continue()),
+                   ExpectHeader::parse(&[&first, &first]));
+        let extension = Expectation::Extension("100-continue, foo, 100-continue".to_string());
+
+        assert_eq!(Some(ExpectHeader::new(extension)), ExpectHeader::parse(&[&first, &second]));
+    }
+}
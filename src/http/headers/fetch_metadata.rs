@@ -0,0 +1,178 @@
+use super::structured::{BareItem, Item, StructuredField, StructuredHeader};
+
+macro_rules! token_field {
+    ($(#[$attr:meta])* $field:ident, $name:expr, $canonical_name:expr,
+     { $($(#[$variant_attr:meta])* $variant:ident => $token:expr),+ $(,)* }) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum $field {
+            $($(#[$variant_attr])* $variant),+
+        }
+
+        impl $field {
+            pub fn as_str(&self) -> &'static str {
+                match *self {
+                    $($field::$variant => $token),+
+                }
+            }
+        }
+
+        impl StructuredField for $field {
+            type Value = Item;
+
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            /// Unknown values are ignored.
+            fn from_value(value: Item) -> Option<Self> {
+                match value.bare_item().as_token()? {
+                    $($token => Some($field::$variant),)+
+                    _ => None,
+                }
+            }
+
+            fn to_value(&self) -> Item {
+                Item::new(BareItem::Token(self.as_str().to_string()))
+            }
+        }
+    }
+}
+
+token_field!(
+    /// The relationship between the origin of the request initiator and the target.
+    /// [Fetch Metadata, Section 2.4](https://www.w3.org/TR/fetch-metadata/)
+    SecFetchSite, "sec-fetch-site", "Sec-Fetch-Site", {
+        CrossSite => "cross-site",
+        SameOrigin => "same-origin",
+        SameSite => "same-site",
+        /// The request was initiated by the user, e.g. by entering the URL.
+        None => "none",
+    });
+
+token_field!(
+    /// The mode of the request, e.g. `navigate` for top-level navigations.
+    /// [Fetch Metadata, Section 2.3](https://www.w3.org/TR/fetch-metadata/)
+    SecFetchMode, "sec-fetch-mode", "Sec-Fetch-Mode", {
+        Cors => "cors",
+        Navigate => "navigate",
+        NoCors => "no-cors",
+        SameOrigin => "same-origin",
+        WebSocket => "websocket",
+    });
+
+token_field!(
+    /// How the response will be used, e.g. `image` for an `<img>` element.
+    /// [Fetch Metadata, Section 2.1](https://www.w3.org/TR/fetch-metadata/)
+    SecFetchDest, "sec-fetch-dest", "Sec-Fetch-Dest", {
+        Audio => "audio",
+        AudioWorklet => "audioworklet",
+        Document => "document",
+        Embed => "embed",
+        Empty => "empty",
+        Font => "font",
+        Frame => "frame",
+        Iframe => "iframe",
+        Image => "image",
+        Json => "json",
+        Manifest => "manifest",
+        Object => "object",
+        PaintWorklet => "paintworklet",
+        Report => "report",
+        Script => "script",
+        ServiceWorker => "serviceworker",
+        SharedWorker => "sharedworker",
+        Style => "style",
+        Track => "track",
+        Video => "video",
+        WebIdentity => "webidentity",
+        Worker => "worker",
+        Xslt => "xslt",
+    });
+
+/// Whether a navigation was triggered by user activation. Only sent as `?1`.
+/// [Fetch Metadata, Section 2.5](https://www.w3.org/TR/fetch-metadata/)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SecFetchUser {
+    user_activated: bool,
+}
+
+impl SecFetchUser {
+    pub fn new(user_activated: bool) -> Self {
+        SecFetchUser { user_activated }
+    }
+
+    pub fn is_user_activated(&self) -> bool {
+        self.user_activated
+    }
+}
+
+impl StructuredField for SecFetchUser {
+    type Value = Item;
+
+    fn name() -> &'static str {
+        "sec-fetch-user"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Sec-Fetch-User"
+    }
+
+    fn from_value(value: Item) -> Option<Self> {
+        value.bare_item().as_boolean().map(SecFetchUser::new)
+    }
+
+    fn to_value(&self) -> Item {
+        Item::new(BareItem::Boolean(self.user_activated))
+    }
+}
+
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{RawHeader, SecFetchSite, SecFetchSiteHeader, TypedHeader};
+///
+/// let raw = RawHeader::new("Sec-Fetch-Site", "cross-site");
+///
+/// assert_eq!(&SecFetchSite::CrossSite, SecFetchSiteHeader::parse(&[&raw]).unwrap().field());
+/// ```
+pub type SecFetchSiteHeader = StructuredHeader<SecFetchSite>;
+pub type SecFetchModeHeader = StructuredHeader<SecFetchMode>;
+pub type SecFetchDestHeader = StructuredHeader<SecFetchDest>;
+pub type SecFetchUserHeader = StructuredHeader<SecFetchUser>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::{RawHeader, TypedHeader};
+
+    #[test]
+    fn test_parse() {
+        let parse = |name, value| RawHeader::new(name, value);
+
+        assert_eq!(Some(SecFetchMode::NoCors),
+                   SecFetchModeHeader::parse(&[&parse("Sec-Fetch-Mode", "no-cors")])
+                       .map(|header| *header.field()));
+        assert_eq!(Some(SecFetchDest::ServiceWorker),
+                   SecFetchDestHeader::parse(&[&parse("Sec-Fetch-Dest", "serviceworker")])
+                       .map(StructuredHeader::into_field));
+        assert_eq!(None, SecFetchDestHeader::parse(&[&parse("Sec-Fetch-Dest", "hologram")]));
+        assert_eq!(None, SecFetchSiteHeader::parse(&[&parse("Sec-Fetch-Site", "\"none\"")]));
+        assert_eq!(Some(true),
+                   SecFetchUserHeader::parse(&[&parse("Sec-Fetch-User", "?1")])
+                       .map(|header| header.field().is_user_activated()));
+        assert_eq!(None, SecFetchUserHeader::parse(&[&parse("Sec-Fetch-User", "1")]));
+    }
+
+    #[test]
+    fn test_raw_values() {
+        assert_eq!(vec!["same-origin".to_string()],
+                   SecFetchSiteHeader::new(SecFetchSite::SameOrigin).raw_values());
+        assert_eq!(vec!["?1".to_string()],
+                   SecFetchUserHeader::new(SecFetchUser::new(true)).raw_values());
+    }
+}
@@ -0,0 +1,502 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv6Addr};
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidNode;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidForwardedElement;
+
+/// The identifier part of a [`Node`].
+///
+/// [`Node`]: struct.Node.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NodeName {
+    Ip(IpAddr),
+    Unknown,
+    /// An obfuscated identifier starting with `_`
+    Obfuscated(String),
+}
+
+/// The port part of a [`Node`].
+///
+/// [`Node`]: struct.Node.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum NodePort {
+    Port(u16),
+    /// An obfuscated port starting with `_`
+    Obfuscated(String),
+}
+
+/// A node identifier as used by `for` and `by` in [`ForwardedHeader`].
+/// [RFC7239, Section 6](http://www.iana.org/go/rfc7239)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{Node, NodeName, NodePort};
+///
+/// let node: Node = "[2001:db8:cafe::17]:4711".parse().unwrap();
+///
+/// assert_eq!(Some("2001:db8:cafe::17".parse().unwrap()), node.ip());
+/// assert_eq!(Some(&NodePort::Port(4711)), node.port());
+/// assert_eq!(&NodeName::Unknown, "unknown".parse::<Node>().unwrap().name());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Node {
+    name: NodeName,
+    port: Option<NodePort>,
+}
+
+impl Node {
+    pub fn new(name: NodeName, port: Option<NodePort>) -> Self {
+        Node { name, port }
+    }
+
+    pub fn name(&self) -> &NodeName {
+        &self.name
+    }
+
+    pub fn port(&self) -> Option<&NodePort> {
+        self.port.as_ref()
+    }
+
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self.name {
+            NodeName::Ip(ip) => Some(ip),
+            _ => None,
+        }
+    }
+}
+
+fn is_obfuscated(value: &str) -> bool {
+    value.len() > 1 && value.starts_with('_') &&
+    value[1..].chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-')
+}
+
+impl FromStr for Node {
+    type Err = InvalidNode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, port) = if let Some(rest) = s.strip_prefix('[') {
+            let end = rest.find(']').ok_or(InvalidNode)?;
+            let ip: Ipv6Addr = rest[..end].parse().map_err(|_| InvalidNode)?;
+            let port = match &rest[end + 1..] {
+                "" => None,
+                port => Some(port.strip_prefix(':').ok_or(InvalidNode)?),
+            };
+
+            (NodeName::Ip(IpAddr::V6(ip)), port)
+        } else {
+            let (name, port) = match s.find(':') {
+                Some(index) => (&s[..index], Some(&s[index + 1..])),
+                None => (s, None),
+            };
+            let name = if name == "unknown" {
+                NodeName::Unknown
+            } else if is_obfuscated(name) {
+                NodeName::Obfuscated(name.to_string())
+            } else {
+                // IPv6 addresses must be enclosed in brackets
+                NodeName::Ip(IpAddr::V4(name.parse().map_err(|_| InvalidNode)?))
+            };
+
+            (name, port)
+        };
+
+        let port = match port {
+            None => None,
+            Some(port) if is_obfuscated(port) => Some(NodePort::Obfuscated(port.to_string())),
+            Some(port) if port.len() <= 5 && port.bytes().all(|b| b.is_ascii_digit()) => {
+                Some(NodePort::Port(port.parse().map_err(|_| InvalidNode)?))
+            }
+            Some(_) => return Err(InvalidNode),
+        };
+
+        Ok(Node { name, port })
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            NodeName::Ip(IpAddr::V4(ip)) => write!(f, "{}", ip)?,
+            NodeName::Ip(IpAddr::V6(ip)) => write!(f, "[{}]", ip)?,
+            NodeName::Unknown => write!(f, "unknown")?,
+            NodeName::Obfuscated(ref name) => write!(f, "{}", name)?,
+        }
+
+        match self.port {
+            Some(NodePort::Port(port)) => write!(f, ":{}", port),
+            Some(NodePort::Obfuscated(ref port)) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The information one proxy added to [`ForwardedHeader`].
+///
+/// [`ForwardedHeader`]: struct.ForwardedHeader.html
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ForwardedElement {
+    forwarded_for: Option<Node>,
+    by: Option<Node>,
+    host: Option<String>,
+    proto: Option<String>,
+}
+
+impl ForwardedElement {
+    pub fn new() -> Self {
+        ForwardedElement::default()
+    }
+
+    /// The node that made the request to the proxy.
+    pub fn forwarded_for(&self) -> Option<&Node> {
+        self.forwarded_for.as_ref()
+    }
+
+    pub fn set_forwarded_for(&mut self, node: Option<Node>) {
+        self.forwarded_for = node;
+    }
+
+    /// The interface on which the proxy received the request.
+    pub fn by(&self) -> Option<&Node> {
+        self.by.as_ref()
+    }
+
+    pub fn set_by(&mut self, node: Option<Node>) {
+        self.by = node;
+    }
+
+    /// The original `Host` header.
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    pub fn set_host(&mut self, host: Option<String>) {
+        self.host = host;
+    }
+
+    /// The original URI scheme, in lower case.
+    pub fn proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+
+    pub fn set_proto(&mut self, proto: Option<String>) {
+        self.proto = proto;
+    }
+}
+
+impl FromStr for ForwardedElement {
+    type Err = InvalidForwardedElement;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut element = ForwardedElement::new();
+
+        for pair in syntax::split_quoted(s, ';') {
+            let (name, value) = syntax::split_pair(pair);
+            let value = syntax::unquote(value.ok_or(InvalidForwardedElement)?);
+            let node = |value: &str| value.parse().map_err(|_| InvalidForwardedElement);
+
+            match name.to_lowercase().as_str() {
+                "for" => element.forwarded_for = Some(node(&value)?),
+                "by" => element.by = Some(node(&value)?),
+                "host" => element.host = Some(value),
+                "proto" => element.proto = Some(value.to_lowercase()),
+                _ => {}
+            }
+        }
+
+        Ok(element)
+    }
+}
+
+impl fmt::Display for ForwardedElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pairs = vec![];
+
+        if let Some(ref node) = self.forwarded_for {
+            pairs.push(format!("for={}", syntax::quote_if_needed(&node.to_string())));
+        }
+
+        if let Some(ref node) = self.by {
+            pairs.push(format!("by={}", syntax::quote_if_needed(&node.to_string())));
+        }
+
+        if let Some(ref host) = self.host {
+            pairs.push(format!("host={}", syntax::quote_if_needed(host)));
+        }
+
+        if let Some(ref proto) = self.proto {
+            pairs.push(format!("proto={}", syntax::quote_if_needed(proto)));
+        }
+
+        write!(f, "{}", pairs.join(";"))
+    }
+}
+
+/// Information about the proxies a request passed through,
+/// in the order they were traversed.
+/// [RFC7239](http://www.iana.org/go/rfc7239)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForwardedHeader {
+    elements: Vec<ForwardedElement>,
+}
+
+impl ForwardedHeader {
+    pub fn new(elements: Vec<ForwardedElement>) -> Self {
+        ForwardedHeader { elements }
+    }
+
+    pub fn elements(&self) -> &[ForwardedElement] {
+        &self.elements
+    }
+
+    pub fn push(&mut self, element: ForwardedElement) {
+        self.elements.push(element);
+    }
+}
+
+impl TypedHeader for ForwardedHeader {
+    fn name() -> &'static str {
+        "forwarded"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Forwarded"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let elements = CommaDelimited::from_raw(raw).ok()?.into_items();
+
+        if elements.is_empty() {
+            return None;
+        }
+
+        Some(ForwardedHeader { elements })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let elements: Vec<String> = self.elements.iter().map(ForwardedElement::to_string).collect();
+
+        vec![elements.join(", ")]
+    }
+}
+
+/// The de-facto standard predecessor of the `for` parameter of [`ForwardedHeader`].
+///
+/// Besides the node syntax of `Forwarded`, unbracketed IPv6 addresses are accepted.
+///
+/// [`ForwardedHeader`]: struct.ForwardedHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct XForwardedForHeader {
+    nodes: Vec<Node>,
+}
+
+impl XForwardedForHeader {
+    pub fn new(nodes: Vec<Node>) -> Self {
+        XForwardedForHeader { nodes }
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn push(&mut self, node: Node) {
+        self.nodes.push(node);
+    }
+
+    /// Parses a single element, which may be an unbracketed IPv6 address.
+    pub(crate) fn parse_node(value: &str) -> Option<Node> {
+        match value.parse::<Ipv6Addr>() {
+            Ok(ip) => Some(Node::new(NodeName::Ip(IpAddr::V6(ip)), None)),
+            Err(_) => value.parse().ok(),
+        }
+    }
+}
+
+impl TypedHeader for XForwardedForHeader {
+    fn name() -> &'static str {
+        "x-forwarded-for"
+    }
+
+    fn canonical_name() -> &'static str {
+        "X-Forwarded-For"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let elements: CommaDelimited<String> = CommaDelimited::from_raw(raw).ok()?;
+        let nodes: Option<Vec<Node>> = elements.iter()
+            .map(|element| XForwardedForHeader::parse_node(element))
+            .collect();
+        let nodes = nodes?;
+
+        if nodes.is_empty() {
+            return None;
+        }
+
+        Some(XForwardedForHeader { nodes })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let nodes: Vec<String> = self.nodes.iter().map(Node::to_string).collect();
+
+        vec![nodes.join(", ")]
+    }
+}
+
+macro_rules! forwarded_value_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            values: Vec<String>,
+        }
+
+        impl $header {
+            pub fn new<S: Into<String>>(value: S) -> Self {
+                $header { values: vec![value.into()] }
+            }
+
+            /// The value appended by the nearest proxy, i.e. the last one.
+            /// The others were appended by proxies further away and can only be trusted
+            /// as far as those proxies are.
+            pub fn value(&self) -> &str {
+                self.values.last().map_or("", String::as_str)
+            }
+
+            /// All values, starting with the one appended by the proxy furthest away.
+            pub fn values(&self) -> &[String] {
+                &self.values
+            }
+
+            pub fn push<S: Into<String>>(&mut self, value: S) {
+                self.values.push(value.into());
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                let values: CommaDelimited<String> = CommaDelimited::from_raw(raw).ok()?;
+
+                if values.is_empty() {
+                    return None;
+                }
+
+                Some($header { values: values.into_items() })
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                vec![self.values.join(", ")]
+            }
+        }
+    }
+}
+
+forwarded_value_header!(
+    /// The de-facto standard predecessor of the `proto` parameter of `Forwarded`.
+    XForwardedProtoHeader,
+    "x-forwarded-proto",
+    "X-Forwarded-Proto");
+
+forwarded_value_header!(
+    /// The de-facto standard predecessor of the `host` parameter of `Forwarded`.
+    XForwardedHostHeader,
+    "x-forwarded-host",
+    "X-Forwarded-Host");
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_node() {
+        let node: Node = "192.0.2.43:47011".parse().unwrap();
+
+        assert_eq!(Some("192.0.2.43".parse().unwrap()), node.ip());
+        assert_eq!(Some(&NodePort::Port(47011)), node.port());
+        assert_eq!(Node::new(NodeName::Obfuscated("_hidden".to_string()),
+                             Some(NodePort::Obfuscated("_SEVKISEK".to_string()))),
+                   "_hidden:_SEVKISEK".parse().unwrap());
+        assert_eq!("[2001:db8::1]:80",
+                   "[2001:db8::1]:80".parse::<Node>().unwrap().to_string());
+        assert_eq!(Err(InvalidNode), "2001:db8::1".parse::<Node>());
+        assert_eq!(Err(InvalidNode), "_".parse::<Node>());
+        assert_eq!(Err(InvalidNode), "10.0.0.1:123456".parse::<Node>());
+        assert_eq!(Err(InvalidNode), "[::1]80".parse::<Node>());
+    }
+
+    #[test]
+    fn test_forwarded() {
+        let forwarded = ForwardedHeader::parse(&[&RawHeader::new("Forwarded",
+                                                                 "for=192.0.2.60;proto=HTTP;\
+                                                                  by=203.0.113.43, \
+                                                                  For=\"[2001:db8:cafe::17]\""),
+                                                 &RawHeader::new("Forwarded", "for=unknown")])
+            .unwrap();
+        let elements = forwarded.elements();
+
+        assert_eq!(3, elements.len());
+        assert_eq!(Some("192.0.2.60".parse().unwrap()),
+                   elements[0].forwarded_for().and_then(Node::ip));
+        assert_eq!(Some("http"), elements[0].proto());
+        assert_eq!(Some("203.0.113.43".parse().unwrap()), elements[0].by().and_then(Node::ip));
+        assert_eq!(Some("2001:db8:cafe::17".parse().unwrap()),
+                   elements[1].forwarded_for().and_then(Node::ip));
+        assert_eq!(Some(&NodeName::Unknown), elements[2].forwarded_for().map(Node::name));
+        assert_eq!(vec!["for=192.0.2.60;by=203.0.113.43;proto=http, \
+                         for=\"[2001:db8:cafe::17]\", for=unknown"
+                            .to_string()],
+                   forwarded.raw_values());
+        assert!(ForwardedHeader::parse(&[&RawHeader::new("Forwarded", "for=2001:db8::1")])
+            .is_none());
+        assert_eq!(Err(InvalidForwardedElement), "for".parse::<ForwardedElement>());
+    }
+
+    #[test]
+    fn test_x_forwarded() {
+        let xff = XForwardedForHeader::parse(&[&RawHeader::new("X-Forwarded-For",
+                                                               "203.0.113.195, 2001:db8::1"),
+                                               &RawHeader::new("X-Forwarded-For",
+                                                               "10.0.0.1:8080")])
+            .unwrap();
+        let ips: Vec<Option<IpAddr>> = xff.nodes().iter().map(Node::ip).collect();
+
+        assert_eq!(vec![Some("203.0.113.195".parse().unwrap()),
+                        Some("2001:db8::1".parse().unwrap()),
+                        Some("10.0.0.1".parse().unwrap())],
+                   ips);
+
+        let proto = XForwardedProtoHeader::parse(&[&RawHeader::new("X-Forwarded-Proto",
+                                                                   "https, http"),
+                                                   &RawHeader::new("X-Forwarded-Proto",
+                                                                   "https")])
+            .unwrap();
+
+        assert_eq!("https", proto.value());
+        assert_eq!(&["https".to_string(), "http".to_string(), "https".to_string()],
+                   proto.values());
+        assert_eq!(vec!["https, http, https".to_string()], proto.raw_values());
+        assert_eq!("http",
+                   XForwardedProtoHeader::parse(&[&RawHeader::new("X-Forwarded-Proto",
+                                                                  "https, http")])
+                       .unwrap()
+                       .value());
+        assert!(XForwardedProtoHeader::parse(&[&RawHeader::new("X-Forwarded-Proto", "")])
+            .is_none());
+        assert_eq!(Some(XForwardedHostHeader::new("example.com")),
+                   XForwardedHostHeader::parse(&[&RawHeader::new("X-Forwarded-Host",
+                                                                 "example.com")]));
+    }
+}
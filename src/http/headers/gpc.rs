@@ -0,0 +1,58 @@
+use super::{TypedHeader, RawHeader};
+
+/// The Global Privacy Control signal, sent as `Sec-GPC: 1` when the user
+/// doesn't want their data to be sold or shared. It has replaced `DNT`
+/// in most browsers. Any other value is treated as if the header was absent.
+/// [Global Privacy Control, Section 3](https://privacycg.github.io/gpc-spec/)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{Headers, RawHeader, SecGpcHeader};
+///
+/// let mut headers = Headers::new();
+///
+/// assert_eq!(None, headers.get::<SecGpcHeader>());
+///
+/// headers.append_raw(RawHeader::new("Sec-GPC", "1"));
+///
+/// assert_eq!(Some(SecGpcHeader), headers.get());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SecGpcHeader;
+
+impl TypedHeader for SecGpcHeader {
+    fn name() -> &'static str {
+        "sec-gpc"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Sec-GPC"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        match raw.first()?.value().trim() {
+            "1" => Some(SecGpcHeader),
+            _ => None,
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec!["1".to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |value| SecGpcHeader::parse(&[&RawHeader::new("Sec-GPC", value)]);
+
+        assert_eq!(Some(SecGpcHeader), parse(" 1 "));
+        assert_eq!(None, parse("0"));
+        assert_eq!(None, parse("?1"));
+        assert_eq!(vec!["1".to_string()], SecGpcHeader.raw_values());
+    }
+}
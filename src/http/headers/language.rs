@@ -0,0 +1,563 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::quality::{Quality, QualityItem};
+use super::super::language;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidLanguageTag;
+
+/// Tags that don't follow the regular syntax, in canonical casing.
+/// [RFC5646, Section 2.2.8](http://www.iana.org/go/rfc5646)
+const GRANDFATHERED: [&str; 26] = ["en-GB-oed",
+                                   "i-ami",
+                                   "i-bnn",
+                                   "i-default",
+                                   "i-enochian",
+                                   "i-hak",
+                                   "i-klingon",
+                                   "i-lux",
+                                   "i-mingo",
+                                   "i-navajo",
+                                   "i-pwn",
+                                   "i-tao",
+                                   "i-tay",
+                                   "i-tsu",
+                                   "sgn-BE-FR",
+                                   "sgn-BE-NL",
+                                   "sgn-CH-DE",
+                                   "art-lojban",
+                                   "cel-gaulish",
+                                   "no-bok",
+                                   "no-nyn",
+                                   "zh-guoyu",
+                                   "zh-hakka",
+                                   "zh-min",
+                                   "zh-min-nan",
+                                   "zh-xiang"];
+
+/// A well-formed BCP 47 language tag like `en`, `de-CH` or `zh-Hant-TW`,
+/// stored in canonical casing: the script in title case, the region
+/// in upper case and all other subtags in lower case. Tags are compared
+/// case-insensitively as a result.
+/// Whether the subtags are registered is not checked.
+/// [RFC5646, Section 2.1](http://www.iana.org/go/rfc5646)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::LanguageTag;
+///
+/// let tag: LanguageTag = "ZH-hant-tw".parse().unwrap();
+///
+/// assert_eq!("zh-Hant-TW", tag.as_str());
+/// assert_eq!("zh", tag.primary_language());
+/// assert_eq!(Some("Hant"), tag.script());
+/// assert_eq!(Some("TW"), tag.region());
+/// assert!("en-".parse::<LanguageTag>().is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LanguageTag {
+    tag: String,
+    script: Option<usize>,
+    region: Option<usize>,
+}
+
+impl LanguageTag {
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// The language subtag, or the whole tag for private use and grandfathered tags.
+    pub fn primary_language(&self) -> &str {
+        if self.is_private_use() || self.is_grandfathered() {
+            return &self.tag;
+        }
+
+        self.subtags().next().unwrap_or_default()
+    }
+
+    pub fn script(&self) -> Option<&str> {
+        self.script.and_then(|index| self.subtags().nth(index))
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.and_then(|index| self.subtags().nth(index))
+    }
+
+    /// A tag like `x-whatever`, which only has a meaning by private agreement.
+    pub fn is_private_use(&self) -> bool {
+        self.tag.starts_with("x-")
+    }
+
+    pub fn is_grandfathered(&self) -> bool {
+        GRANDFATHERED.contains(&self.tag.as_str())
+    }
+
+    fn subtags(&self) -> impl Iterator<Item = &str> {
+        self.tag.split('-')
+    }
+}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.bytes().all(|byte| byte.is_ascii_alphabetic())
+}
+
+fn is_digit(subtag: &str) -> bool {
+    subtag.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn is_variant(subtag: &str) -> bool {
+    match subtag.len() {
+        5..=8 => true,
+        4 => subtag.starts_with(|c: char| c.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+impl FromStr for LanguageTag {
+    type Err = InvalidLanguageTag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(tag) = GRANDFATHERED.iter().find(|tag| tag.eq_ignore_ascii_case(s)) {
+            return Ok(LanguageTag {
+                tag: tag.to_string(),
+                script: None,
+                region: None,
+            });
+        }
+
+        let lower = s.to_ascii_lowercase();
+        let subtags: Vec<&str> = lower.split('-').collect();
+
+        if subtags.iter().any(|subtag| {
+            subtag.is_empty() || subtag.len() > 8 ||
+            !subtag.bytes().all(|byte| byte.is_ascii_alphanumeric())
+        }) {
+            return Err(InvalidLanguageTag);
+        }
+
+        let mut tag = LanguageTag {
+            tag: String::new(),
+            script: None,
+            region: None,
+        };
+        let mut canonical: Vec<String> = vec![];
+        let mut index = 0;
+        let next = |index: usize, predicate: &dyn Fn(&str) -> bool| {
+            subtags.get(index).filter(|subtag| predicate(subtag)).is_some()
+        };
+
+        if subtags[0] != "x" {
+            match subtags[0].len() {
+                2..=3 if is_alpha(subtags[0]) => {
+                    canonical.push(subtags[0].to_string());
+                    index += 1;
+
+                    let is_extlang = |subtag: &str| subtag.len() == 3 && is_alpha(subtag);
+
+                    while index < 4 && next(index, &is_extlang) {
+                        canonical.push(subtags[index].to_string());
+                        index += 1;
+                    }
+                }
+                4..=8 if is_alpha(subtags[0]) => {
+                    canonical.push(subtags[0].to_string());
+                    index += 1;
+                }
+                _ => return Err(InvalidLanguageTag),
+            }
+
+            if next(index, &|subtag| subtag.len() == 4 && is_alpha(subtag)) {
+                let script = subtags[index];
+
+                tag.script = Some(index);
+                canonical.push(script[..1].to_ascii_uppercase() + &script[1..]);
+                index += 1;
+            }
+
+            if next(index, &|subtag| {
+                (subtag.len() == 2 && is_alpha(subtag)) || (subtag.len() == 3 && is_digit(subtag))
+            }) {
+                tag.region = Some(index);
+                canonical.push(subtags[index].to_ascii_uppercase());
+                index += 1;
+            }
+
+            while next(index, &is_variant) {
+                canonical.push(subtags[index].to_string());
+                index += 1;
+            }
+
+            while next(index, &|subtag| subtag.len() == 1 && subtag != "x") {
+                canonical.push(subtags[index].to_string());
+                index += 1;
+
+                let start = index;
+
+                while next(index, &|subtag| subtag.len() >= 2) {
+                    canonical.push(subtags[index].to_string());
+                    index += 1;
+                }
+
+                if index == start {
+                    return Err(InvalidLanguageTag);
+                }
+            }
+        }
+
+        if next(index, &|subtag| subtag == "x") {
+            if index + 1 == subtags.len() {
+                return Err(InvalidLanguageTag);
+            }
+
+            canonical.extend(subtags[index..].iter().map(|subtag| subtag.to_string()));
+            index = subtags.len();
+        }
+
+        if index != subtags.len() {
+            return Err(InvalidLanguageTag);
+        }
+
+        tag.tag = canonical.join("-");
+
+        Ok(tag)
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.tag)
+    }
+}
+
+/// A basic language range of `Accept-Language`, either `*` or a prefix
+/// of language tags like `en` or `de-CH`. Ranges are compared case-insensitively.
+/// [RFC4647, Section 2.1](http://www.iana.org/go/rfc4647)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LanguageRange {
+    /// `*`, matching any language
+    Any,
+    Range(String),
+}
+
+impl LanguageRange {
+    /// Checks whether `tag` is matched by this range according to basic filtering,
+    /// i.e. the range equals the tag or a prefix of it ending at a `-`.
+    /// [RFC4647, Section 3.3.1](http://www.iana.org/go/rfc4647)
+    pub fn matches(&self, tag: &LanguageTag) -> bool {
+        let range = match *self {
+            LanguageRange::Any => return true,
+            LanguageRange::Range(ref range) => range,
+        };
+        let tag = tag.as_str();
+
+        tag.len() >= range.len() && tag[..range.len()].eq_ignore_ascii_case(range) &&
+        (tag.len() == range.len() || tag[range.len()..].starts_with('-'))
+    }
+}
+
+impl FromStr for LanguageRange {
+    type Err = InvalidLanguageTag;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "*" {
+            return Ok(LanguageRange::Any);
+        }
+
+        let is_subtag = |subtag: &str| {
+            !subtag.is_empty() && subtag.len() <= 8 &&
+            subtag.bytes().all(|byte| byte.is_ascii_alphanumeric())
+        };
+
+        if s.split('-').next().is_some_and(is_alpha) && s.split('-').all(is_subtag) {
+            Ok(LanguageRange::Range(s.to_string()))
+        } else {
+            Err(InvalidLanguageTag)
+        }
+    }
+}
+
+impl fmt::Display for LanguageRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LanguageRange::Any => write!(f, "*"),
+            LanguageRange::Range(ref range) => write!(f, "{}", range),
+        }
+    }
+}
+
+/// The natural languages of the intended audience of the representation.
+/// [RFC7231, Section 3.1.3.2](http://www.iana.org/go/rfc7231)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ContentLanguageHeader {
+    tags: CommaDelimited<LanguageTag>,
+}
+
+impl ContentLanguageHeader {
+    pub fn new(tags: Vec<LanguageTag>) -> Self {
+        ContentLanguageHeader { tags: CommaDelimited::new(tags) }
+    }
+
+    pub fn tags(&self) -> &[LanguageTag] {
+        self.tags.items()
+    }
+
+    pub fn contains(&self, tag: &LanguageTag) -> bool {
+        self.tags.iter().any(|other| other == tag)
+    }
+}
+
+impl TypedHeader for ContentLanguageHeader {
+    fn name() -> &'static str {
+        "content-language"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Content-Language"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let tags: CommaDelimited<LanguageTag> = CommaDelimited::from_raw(raw).ok()?;
+
+        if tags.is_empty() {
+            None
+        } else {
+            Some(ContentLanguageHeader { tags })
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.tags.to_string()]
+    }
+}
+
+/// The natural languages preferred in the response.
+/// [RFC7231, Section 5.3.5](http://www.iana.org/go/rfc7231)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{AcceptLanguageHeader, LanguageTag, RawHeader, TypedHeader};
+///
+/// let raw = RawHeader::new("Accept-Language", "de-CH, en;q=0.8, fr;q=0.5");
+/// let accept = AcceptLanguageHeader::parse(&[&raw]).unwrap();
+/// let available: Vec<LanguageTag> = vec!["en-US".parse().unwrap(),
+///                                        "fr".parse().unwrap(),
+///                                        "de".parse().unwrap()];
+///
+/// assert_eq!(Some(&available[2]), accept.lookup(&available));
+/// assert_eq!(vec![&available[0], &available[1]], accept.filter(&available));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AcceptLanguageHeader {
+    items: Vec<QualityItem<LanguageRange>>,
+}
+
+impl AcceptLanguageHeader {
+    pub fn new(items: Vec<QualityItem<LanguageRange>>) -> Self {
+        AcceptLanguageHeader { items }
+    }
+
+    pub fn items(&self) -> &[QualityItem<LanguageRange>] {
+        &self.items
+    }
+
+    /// The acceptable ranges, ordered by weight and then by position.
+    /// Ranges with a weight of `0` are left out.
+    pub fn ranges(&self) -> Vec<LanguageRange> {
+        let mut items: Vec<&QualityItem<LanguageRange>> = self.items
+            .iter()
+            .filter(|item| !item.quality().is_zero())
+            .collect();
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.quality()));
+        items.into_iter().map(|item| item.item().clone()).collect()
+    }
+
+    /// The tags of `available` matching the acceptable ranges, see [`basic_filter`].
+    /// A tag is left out if the most specific range matching it has a weight of `0`,
+    /// e.g. `de, *;q=0` excludes any language but German,
+    /// while `en-US, en;q=0` still accepts `en-US`.
+    ///
+    /// [`basic_filter`]: ../language/fn.basic_filter.html
+    pub fn filter<'a>(&self, available: &'a [LanguageTag]) -> Vec<&'a LanguageTag> {
+        language::basic_filter(&self.ranges(), available)
+            .into_iter()
+            .filter(|tag| self.quality_of(tag).is_some_and(|quality| !quality.is_zero()))
+            .collect()
+    }
+
+    /// The weight of the most specific range matching `tag`. Since a range only
+    /// matches tags it is a prefix of, longer ranges are more specific.
+    fn quality_of(&self, tag: &LanguageTag) -> Option<Quality> {
+        let specificity = |item: &&QualityItem<LanguageRange>| match *item.item() {
+            LanguageRange::Any => 0,
+            LanguageRange::Range(ref range) => range.len(),
+        };
+
+        self.items
+            .iter()
+            .rev()
+            .filter(|item| item.item().matches(tag))
+            .max_by_key(specificity)
+            .map(QualityItem::quality)
+    }
+
+    /// The best tag of `available` for the acceptable ranges, see [`lookup`].
+    /// Tags excluded like in [`filter`] are never returned, so `de-CH, de;q=0`
+    /// doesn't fall back to `de`.
+    ///
+    /// [`lookup`]: ../language/fn.lookup.html
+    /// [`filter`]: #method.filter
+    pub fn lookup<'a>(&self, available: &'a [LanguageTag]) -> Option<&'a LanguageTag> {
+        let candidates: Vec<LanguageTag> = available.iter()
+            .filter(|tag| !self.quality_of(tag).is_some_and(|quality| quality.is_zero()))
+            .cloned()
+            .collect();
+        let found = language::lookup(&self.ranges(), &candidates)?;
+
+        available.iter().find(|tag| *tag == found)
+    }
+}
+
+impl TypedHeader for AcceptLanguageHeader {
+    fn name() -> &'static str {
+        "accept-language"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Accept-Language"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        CommaDelimited::from_raw(raw)
+            .ok()
+            .map(|items| AcceptLanguageHeader::new(items.into_items()))
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let items: Vec<String> = self.items.iter().map(QualityItem::to_string).collect();
+
+        vec![items.join(", ")]
+    }
+}
+
+impl Default for AcceptLanguageHeader {
+    /// Without `Accept-Language`, any language is acceptable.
+    fn default() -> Self {
+        AcceptLanguageHeader::new(vec![QualityItem::new(LanguageRange::Any, Quality::one())])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tag(value: &str) -> LanguageTag {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        let valid = [("de", "de"),
+                     ("EN-us", "en-US"),
+                     ("zh-yue-HK", "zh-yue-HK"),
+                     ("sr-latn-rs", "sr-Latn-RS"),
+                     ("es-419", "es-419"),
+                     ("sl-rozaj-biske", "sl-rozaj-biske"),
+                     ("de-CH-1901", "de-CH-1901"),
+                     ("en-US-u-islamcal", "en-US-u-islamcal"),
+                     ("en-a-bbb-x-A-CCC", "en-a-bbb-x-a-ccc"),
+                     ("X-Whatever", "x-whatever"),
+                     ("I-KLINGON", "i-klingon"),
+                     ("en-gb-oed", "en-GB-oed")];
+
+        for &(value, canonical) in valid.iter() {
+            assert_eq!(canonical, tag(value).as_str());
+        }
+
+        for value in ["", "e", "en-", "-en", "en--US", "de-419-DE", "a-DE", "ar-a-aaa-b-bbb-a",
+                      "en-a", "en-x", "abcdefghi", "en-US-a-bbb-", "en_US", "ü"]
+            .iter() {
+            assert_eq!(Err(InvalidLanguageTag), value.parse::<LanguageTag>(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_subtags() {
+        let serbian = tag("sr-Latn-RS");
+
+        assert_eq!("sr", serbian.primary_language());
+        assert_eq!(Some("Latn"), serbian.script());
+        assert_eq!(Some("RS"), serbian.region());
+        assert_eq!(None, tag("de").region());
+        assert_eq!(Some("419"), tag("es-419").region());
+        assert!(tag("x-foo").is_private_use());
+        assert_eq!("i-klingon", tag("i-klingon").primary_language());
+        assert_eq!(serbian, "SR-LATN-rs".parse().unwrap());
+    }
+
+    #[test]
+    fn test_range_matches() {
+        let range: LanguageRange = "de-DE".parse().unwrap();
+
+        assert!(range.matches(&tag("de-de")));
+        assert!(range.matches(&tag("de-DE-1996")));
+        assert!(!range.matches(&tag("de-Deva")));
+        assert!(!range.matches(&tag("de")));
+        assert!(LanguageRange::Any.matches(&tag("de")));
+        assert!("en-".parse::<LanguageRange>().is_err());
+        assert!("1-en".parse::<LanguageRange>().is_err());
+    }
+
+    #[test]
+    fn test_headers() {
+        let raw = RawHeader::new("Content-Language", "mi, EN");
+        let content = ContentLanguageHeader::parse(&[&raw]).unwrap();
+
+        assert!(content.contains(&tag("en")));
+        assert_eq!(vec!["mi, en".to_string()], content.raw_values());
+        assert_eq!(None, ContentLanguageHeader::parse(&[&RawHeader::new("Content-Language", "")]));
+
+        let raw = RawHeader::new("Accept-Language", "fr;q=0.5, en-GB;q=0, *;q=0.1, de");
+        let accept = AcceptLanguageHeader::parse(&[&raw]).unwrap();
+
+        assert_eq!(vec![LanguageRange::Range("de".to_string()),
+                        LanguageRange::Range("fr".to_string()),
+                        LanguageRange::Any],
+                   accept.ranges());
+        assert_eq!(vec![&tag("fr"), &tag("en-US")],
+                   accept.filter(&[tag("en-GB"), tag("en-US"), tag("fr")]));
+        assert_eq!(None,
+                   AcceptLanguageHeader::parse(&[&RawHeader::new("Accept-Language", "en_US")]));
+    }
+
+    #[test]
+    fn test_filter_specificity() {
+        let accept = |value| {
+            AcceptLanguageHeader::parse(&[&RawHeader::new("Accept-Language", value)]).unwrap()
+        };
+
+        assert_eq!(vec![&tag("de")], accept("de, *;q=0").filter(&[tag("de"), tag("en")]));
+        assert_eq!(vec![&tag("en-US")],
+                   accept("en-US, en;q=0").filter(&[tag("en"), tag("en-GB"), tag("en-US")]));
+        assert_eq!(vec![&tag("en-GB")],
+                   accept("en, en-US;q=0").filter(&[tag("en-US"), tag("en-GB")]));
+        assert_eq!(vec![&tag("fr")], accept("*, en;q=0").filter(&[tag("en-US"), tag("fr")]));
+    }
+
+    #[test]
+    fn test_lookup_exclusion() {
+        let accept = |value| {
+            AcceptLanguageHeader::parse(&[&RawHeader::new("Accept-Language", value)]).unwrap()
+        };
+
+        assert_eq!(None, accept("de-CH, de;q=0").lookup(&[tag("de"), tag("en")]));
+        assert_eq!(Some(&tag("de")), accept("de-CH, en;q=0").lookup(&[tag("en"), tag("de")]));
+    }
+}
@@ -0,0 +1,431 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::CharIndices;
+use super::{TypedHeader, RawHeader};
+use super::syntax;
+
+/// A single link-value of a [`LinkHeader`].
+///
+/// Only the first occurrence of `rel`, `anchor`, `type` and `title` is used,
+/// all other parameters are kept in order.
+///
+/// [`LinkHeader`]: struct.LinkHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Link {
+    target: String,
+    rels: Vec<String>,
+    anchor: Option<String>,
+    hreflang: Vec<String>,
+    media_type: Option<String>,
+    title: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl Link {
+    /// `target` is a URI-reference, relative references are not resolved.
+    pub fn new<S: Into<String>>(target: S) -> Self {
+        Link {
+            target: target.into(),
+            rels: vec![],
+            anchor: None,
+            hreflang: vec![],
+            media_type: None,
+            title: None,
+            params: vec![],
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// The relation types, e.g. `next` or `preload`.
+    pub fn rels(&self) -> &[String] {
+        &self.rels
+    }
+
+    pub fn push_rel<S: Into<String>>(&mut self, rel: S) {
+        self.rels.push(rel.into());
+    }
+
+    /// Registered relation types are compared case-insensitively.
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rels.iter().any(|other| other.eq_ignore_ascii_case(rel))
+    }
+
+    /// The context of the link, if it is not the current resource.
+    pub fn anchor(&self) -> Option<&str> {
+        self.anchor.as_deref()
+    }
+
+    pub fn set_anchor(&mut self, anchor: Option<String>) {
+        self.anchor = anchor;
+    }
+
+    pub fn hreflang(&self) -> &[String] {
+        &self.hreflang
+    }
+
+    pub fn push_hreflang<S: Into<String>>(&mut self, hreflang: S) {
+        self.hreflang.push(hreflang.into());
+    }
+
+    /// The `type` parameter, a media type hint.
+    pub fn media_type(&self) -> Option<&str> {
+        self.media_type.as_deref()
+    }
+
+    pub fn set_media_type(&mut self, media_type: Option<String>) {
+        self.media_type = media_type;
+    }
+
+    /// The title, taken from `title*` if present.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+
+    /// Extension parameters with lower case names. Parameters without
+    /// a value have an empty value.
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|&(other, _)| other.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn push_param<S: Into<String>>(&mut self, name: S, value: S) {
+        self.params.push((name.into().to_lowercase(), value.into()));
+    }
+
+    fn set_param(&mut self, name: String, value: String) {
+        let mut ext_title = None;
+
+        match name.as_str() {
+            "rel" if self.rels.is_empty() => {
+                self.rels = value.split_ascii_whitespace().map(str::to_string).collect();
+            }
+            "anchor" if self.anchor.is_none() => self.anchor = Some(value),
+            "hreflang" => self.hreflang.push(value),
+            "type" if self.media_type.is_none() => self.media_type = Some(value),
+            "title" if self.title.is_none() => self.title = Some(value),
+            "title*" => ext_title = syntax::decode_ext_value(&value),
+            "rel" | "anchor" | "type" | "title" => {}
+            _ => self.params.push((name, value)),
+        }
+
+        // title* takes precedence over title
+        if ext_title.is_some() {
+            self.title = ext_title;
+        }
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}>", self.target)?;
+
+        if !self.rels.is_empty() {
+            write!(f, "; rel={}", syntax::quote_if_needed(&self.rels.join(" ")))?;
+        }
+
+        if let Some(ref anchor) = self.anchor {
+            write!(f, "; anchor={}", syntax::quote(anchor))?;
+        }
+
+        for hreflang in &self.hreflang {
+            write!(f, "; hreflang={}", syntax::quote_if_needed(hreflang))?;
+        }
+
+        if let Some(ref media_type) = self.media_type {
+            write!(f, "; type={}", syntax::quote_if_needed(media_type))?;
+        }
+
+        if let Some(ref title) = self.title {
+            if title.is_ascii() {
+                write!(f, "; title={}", syntax::quote(title))?;
+            } else {
+                write!(f, "; title*={}", syntax::encode_ext_value(title))?;
+            }
+        }
+
+        for (name, value) in &self.params {
+            if value.is_empty() {
+                write!(f, "; {}", name)?;
+            } else {
+                write!(f, "; {}={}", name, syntax::quote_if_needed(value))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct LinkParser<'a> {
+    value: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> LinkParser<'a> {
+    fn new(value: &'a str) -> Self {
+        LinkParser {
+            value,
+            chars: value.char_indices().peekable(),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn skip_while<F: Fn(char) -> bool>(&mut self, predicate: F) {
+        while self.peek().is_some_and(&predicate) {
+            self.chars.next();
+        }
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let start = self.chars.peek().map_or(self.value.len(), |&(index, _)| index);
+
+        self.skip_while(predicate);
+
+        let end = self.chars.peek().map_or(self.value.len(), |&(index, _)| index);
+
+        &self.value[start..end]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.skip_while(|c| c == ' ' || c == '\t');
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Some(()),
+            _ => None,
+        }
+    }
+
+    fn quoted_string(&mut self) -> Option<String> {
+        let mut result = String::new();
+
+        self.expect('"')?;
+
+        loop {
+            match self.chars.next()?.1 {
+                '"' => return Some(result),
+                '\\' => result.push(self.chars.next()?.1),
+                c => result.push(c),
+            }
+        }
+    }
+
+    fn links(mut self) -> Option<Vec<Link>> {
+        let mut links = vec![];
+
+        loop {
+            self.skip_while(|c| c == ' ' || c == '\t' || c == ',');
+
+            if self.peek().is_none() {
+                return Some(links);
+            }
+
+            links.push(self.link()?);
+        }
+    }
+
+    fn link(&mut self) -> Option<Link> {
+        self.expect('<')?;
+
+        let mut link = Link::new(self.take_while(|c| c != '>'));
+
+        self.expect('>')?;
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                None | Some(',') => return Some(link),
+                Some(';') => {
+                    self.chars.next();
+                }
+                Some(_) => return None,
+            }
+
+            self.skip_whitespace();
+
+            let name = self.take_while(syntax::is_tchar).to_lowercase();
+
+            if name.is_empty() {
+                return None;
+            }
+
+            self.skip_whitespace();
+
+            let value = if self.peek() == Some('=') {
+                self.chars.next();
+                self.skip_whitespace();
+
+                if self.peek() == Some('"') {
+                    self.quoted_string()?
+                } else {
+                    self.take_while(syntax::is_tchar).to_string()
+                }
+            } else {
+                String::new()
+            };
+
+            link.set_param(name, value);
+        }
+    }
+}
+
+/// Typed links to other resources, e.g. for pagination or preloading.
+/// [RFC8288, Section 3](http://www.iana.org/go/rfc8288)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{LinkHeader, RawHeader, TypedHeader};
+///
+/// let raw = RawHeader::new("Link",
+///                          "<https://api.example/items?page=2>; rel=\"next\", \
+///                           <https://api.example/items?page=9>; rel=last");
+/// let header = LinkHeader::parse(&[&raw]).unwrap();
+///
+/// assert_eq!(Some("https://api.example/items?page=2"),
+///            header.find("next").map(|link| link.target()));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct LinkHeader {
+    links: Vec<Link>,
+}
+
+impl LinkHeader {
+    pub fn new(links: Vec<Link>) -> Self {
+        LinkHeader { links }
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn push(&mut self, link: Link) {
+        self.links.push(link);
+    }
+
+    /// Returns the first link with the relation type `rel`.
+    pub fn find(&self, rel: &str) -> Option<&Link> {
+        self.links.iter().find(|link| link.has_rel(rel))
+    }
+}
+
+impl TypedHeader for LinkHeader {
+    fn name() -> &'static str {
+        "link"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Link"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let mut links = vec![];
+
+        for raw in raw {
+            links.extend(LinkParser::new(raw.value()).links()?);
+        }
+
+        if links.is_empty() {
+            return None;
+        }
+
+        Some(LinkHeader { links })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let links: Vec<String> = self.links.iter().map(Link::to_string).collect();
+
+        vec![links.join(", ")]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(value: &str) -> Option<LinkHeader> {
+        LinkHeader::parse(&[&RawHeader::new("Link", value)])
+    }
+
+    #[test]
+    fn test_parse() {
+        let header = parse("<http://example.com/TheBook/chapter2>; rel=\"previous\"; \
+                            title=\"previous chapter\", </a,b;c>;REL=\"start http://example.net/rel\"")
+            .unwrap();
+        let links = header.links();
+
+        assert_eq!(2, links.len());
+        assert_eq!("http://example.com/TheBook/chapter2", links[0].target());
+        assert_eq!(&["previous".to_string()], links[0].rels());
+        assert_eq!(Some("previous chapter"), links[0].title());
+        assert_eq!("/a,b;c", links[1].target());
+        assert!(links[1].has_rel("START"));
+        assert!(links[1].has_rel("http://example.net/rel"));
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let header = parse("</style.css>; rel=preload; as=style; type=\"text/css\"; nopush; \
+                            hreflang=en; hreflang=de; anchor=\"#foo\"; rel=ignored; \
+                            title*=UTF-8'de'n%c3%a4chstes%20Kapitel; title=\"next\"")
+            .unwrap();
+        let link = &header.links()[0];
+
+        assert_eq!(&["preload".to_string()], link.rels());
+        assert_eq!(Some("style"), link.param("as"));
+        assert_eq!(Some(""), link.param("nopush"));
+        assert_eq!(Some("text/css"), link.media_type());
+        assert_eq!(&["en".to_string(), "de".to_string()], link.hreflang());
+        assert_eq!(Some("#foo"), link.anchor());
+        assert_eq!(Some("nächstes Kapitel"), link.title());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("").is_none());
+        assert!(parse("http://example.com; rel=next").is_none());
+        assert!(parse("<http://example.com; rel=next").is_none());
+        assert!(parse("<http://example.com>; rel=\"next").is_none());
+        assert!(parse("<http://example.com> rel=next").is_none());
+    }
+
+    #[test]
+    fn test_raw_values() {
+        let mut preload = Link::new("/app.js");
+        let mut next = Link::new("/items?page=2");
+
+        preload.push_rel("preload");
+        preload.push_param("as", "script");
+        preload.push_param("crossorigin", "");
+        next.push_rel("next");
+        next.push_rel("http://example.net/rel");
+        next.set_title(Some("€ rates".to_string()));
+
+        let header = LinkHeader::new(vec![preload, next]);
+        let raw = header.raw_values();
+
+        assert_eq!(vec!["</app.js>; rel=preload; as=script; crossorigin, \
+                         </items?page=2>; rel=\"next http://example.net/rel\"; \
+                         title*=UTF-8''%E2%82%AC%20rates"
+                            .to_string()],
+                   raw);
+        assert_eq!(Some(header), parse(&raw[0]));
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::slice;
+use std::str::FromStr;
+use super::RawHeader;
+use super::syntax;
+
+/// A comma separated list of values (`#element`) that may be split across
+/// several header lines.
+/// [RFC7230, Section 7](http://www.iana.org/go/rfc7230)
+///
+/// Commas inside of quoted strings don't split elements and empty elements
+/// like in `a, , b` are skipped, as required for recipients.
+/// The elements are trimmed before they are parsed with `FromStr`.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{CommaDelimited, RawHeader};
+///
+/// let raw = [RawHeader::new("X-Numbers", "1, ,2"), RawHeader::new("X-Numbers", "3")];
+/// let list = CommaDelimited::<u32>::from_raw(&[&raw[0], &raw[1]]).unwrap();
+///
+/// assert_eq!(&[1, 2, 3], list.items());
+/// assert_eq!("1, 2, 3", list.to_string());
+/// assert!(CommaDelimited::<u32>::from_raw(&[&RawHeader::new("X-Numbers", "1, x")]).is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CommaDelimited<T> {
+    items: Vec<T>,
+}
+
+impl<T> CommaDelimited<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        CommaDelimited { items }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<T: FromStr> CommaDelimited<T> {
+    /// Parses the elements of all `raw` headers in order.
+    /// Fails with the error of the first element that could not be parsed.
+    pub fn from_raw(raw: &[&RawHeader]) -> Result<Self, T::Err> {
+        let items: Result<Vec<T>, T::Err> = raw.iter()
+            .flat_map(|raw| syntax::split_list(raw.value()))
+            .map(str::parse)
+            .collect();
+
+        items.map(CommaDelimited::new)
+    }
+
+    /// Parses the elements of all `raw` headers in order, skipping elements
+    /// that could not be parsed. For headers whose recipients must ignore
+    /// unknown or invalid elements.
+    pub fn from_raw_lossy(raw: &[&RawHeader]) -> Self {
+        let items = raw.iter()
+            .flat_map(|raw| syntax::split_list(raw.value()))
+            .filter_map(|element| element.parse().ok())
+            .collect();
+
+        CommaDelimited::new(items)
+    }
+}
+
+impl<T: FromStr> FromStr for CommaDelimited<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let items: Result<Vec<T>, T::Err> = syntax::split_list(s)
+            .into_iter()
+            .map(str::parse)
+            .collect();
+
+        items.map(CommaDelimited::new)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for CommaDelimited<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, item) in self.items.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Default for CommaDelimited<T> {
+    fn default() -> Self {
+        CommaDelimited::new(vec![])
+    }
+}
+
+impl<T> From<Vec<T>> for CommaDelimited<T> {
+    fn from(items: Vec<T>) -> Self {
+        CommaDelimited::new(items)
+    }
+}
+
+impl<T> IntoIterator for CommaDelimited<T> {
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CommaDelimited<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quoted_and_empty_elements() {
+        let list: CommaDelimited<String> = ", \"a, b\" ,,\tc ,".parse().unwrap();
+
+        assert_eq!(&["\"a, b\"".to_string(), "c".to_string()], list.items());
+        assert!("".parse::<CommaDelimited<String>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_raw() {
+        let first = RawHeader::new("X-List", "1,2");
+        let second = RawHeader::new("X-List", "");
+        let third = RawHeader::new("X-List", "3");
+        let list = CommaDelimited::<u8>::from_raw(&[&first, &second, &third]).unwrap();
+
+        assert_eq!(vec![1, 2, 3], list.into_items());
+        assert!(CommaDelimited::<u8>::from_raw(&[&RawHeader::new("X-List", "1, 256")]).is_err());
+    }
+
+    #[test]
+    fn test_from_raw_lossy() {
+        let first = RawHeader::new("X-List", "1, 256, x");
+        let second = RawHeader::new("X-List", "\"2\", 3");
+        let list = CommaDelimited::<u8>::from_raw_lossy(&[&first, &second]);
+
+        assert_eq!(&[1, 3], list.items());
+    }
+
+    #[test]
+    fn test_display() {
+        let list = CommaDelimited::from(vec!["gzip", "br"]);
+
+        assert_eq!("gzip, br", list.to_string());
+        assert_eq!("", CommaDelimited::<u8>::default().to_string());
+    }
+}
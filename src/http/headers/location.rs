@@ -0,0 +1,118 @@
+use super::{TypedHeader, RawHeader};
+use super::super::super::uri::UriReference;
+
+macro_rules! uri_reference_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            value: UriReference,
+        }
+
+        impl $header {
+            pub fn new(value: UriReference) -> Self {
+                $header { value }
+            }
+
+            pub fn value(&self) -> &UriReference {
+                &self.value
+            }
+
+            /// Resolves the value against `base`, usually the effective request URI.
+            pub fn resolve(&self, base: &UriReference) -> UriReference {
+                self.value.resolve(base)
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                let value = raw.first()?.value().trim().parse().ok()?;
+
+                Some($header { value })
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                vec![self.value.to_string()]
+            }
+        }
+    }
+}
+
+uri_reference_header!(
+    /// The target of a redirect or the resource created by a request.
+    /// [RFC7231, Section 7.1.2](http://www.iana.org/go/rfc7231)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use teapot::http::headers::{LocationHeader, RawHeader, TypedHeader};
+    /// use teapot::uri::UriReference;
+    ///
+    /// let base: UriReference = "https://example.com/blog/post?page=2#comments".parse().unwrap();
+    /// let location = LocationHeader::parse(&[&RawHeader::new("Location", "../about")]).unwrap();
+    ///
+    /// assert_eq!("https://example.com/about#comments", location.resolve_redirect(&base).to_string());
+    /// ```
+    LocationHeader, "location", "Location");
+
+uri_reference_header!(
+    /// The resource the request URI was obtained from.
+    /// Senders must not include a fragment.
+    /// [RFC7231, Section 5.5.2](http://www.iana.org/go/rfc7231)
+    RefererHeader, "referer", "Referer");
+
+uri_reference_header!(
+    /// A URI identifying the representation enclosed in the message.
+    /// [RFC7231, Section 3.1.4.2](http://www.iana.org/go/rfc7231)
+    ContentLocationHeader, "content-location", "Content-Location");
+
+impl LocationHeader {
+    /// Resolves the value against the effective request URI. A fragment of the
+    /// request URI is inherited if the value has none.
+    /// [RFC7231, Section 7.1.2](http://www.iana.org/go/rfc7231)
+    pub fn resolve_redirect(&self, request_uri: &UriReference) -> UriReference {
+        let mut target = self.value.resolve(request_uri);
+
+        if target.fragment().is_none() {
+            target.set_fragment(request_uri.fragment().map(str::to_string));
+        }
+
+        target
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let location = LocationHeader::parse(&[&RawHeader::new("Location", " /a?b ")]).unwrap();
+
+        assert_eq!("/a", location.value().path());
+        assert_eq!(vec!["/a?b".to_string()], location.raw_values());
+        assert_eq!(None, LocationHeader::parse(&[&RawHeader::new("Location", "/a b")]));
+        assert_eq!(None, ContentLocationHeader::parse(&[]));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let base: UriReference = "http://example.com/a/b#top".parse().unwrap();
+        let location = LocationHeader::parse(&[&RawHeader::new("Location", "c")]).unwrap();
+        let absolute = LocationHeader::parse(&[&RawHeader::new("Location",
+                                                               "https://example.org/#x")])
+            .unwrap();
+
+        assert_eq!("http://example.com/a/c", location.resolve(&base).to_string());
+        assert_eq!("http://example.com/a/c#top", location.resolve_redirect(&base).to_string());
+        assert_eq!("https://example.org/#x", absolute.resolve_redirect(&base).to_string());
+    }
+}
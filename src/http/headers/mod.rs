@@ -0,0 +1,470 @@
+use std::fmt;
+use std::slice;
+
+mod allow;
+mod alt_svc;
+mod authorization;
+mod cache_control;
+mod client_hints;
+mod cookie;
+mod connection;
+mod content_disposition;
+mod cors;
+mod date;
+mod encoding;
+mod etag;
+mod expect;
+mod fetch_metadata;
+mod forwarded;
+mod gpc;
+mod language;
+mod link;
+mod list;
+mod location;
+mod prefer;
+mod quality;
+mod range;
+mod security;
+mod server_timing;
+mod structured;
+mod trailer;
+mod user_agent;
+mod vary;
+
+pub(crate) mod syntax;
+
+pub use self::allow::AllowHeader;
+pub use self::alt_svc::{AltService, AltSvc, AltSvcHeader, InvalidAltService};
+pub use self::authorization::{AuthParams, AuthorizationHeader, Challenge, Credentials,
+                              ProxyAuthenticateHeader, ProxyAuthorizationHeader,
+                              WwwAuthenticateHeader};
+pub use self::cache_control::{CacheControlHeader, CacheDirective};
+pub use self::client_hints::{AcceptCh, AcceptChHeader, Brand, SecChUa, SecChUaArch,
+                             SecChUaArchHeader, SecChUaBitness, SecChUaBitnessHeader,
+                             SecChUaFullVersionList, SecChUaFullVersionListHeader, SecChUaHeader,
+                             SecChUaMobile, SecChUaMobileHeader, SecChUaModel, SecChUaModelHeader,
+                             SecChUaPlatform, SecChUaPlatformHeader, SecChUaPlatformVersion,
+                             SecChUaPlatformVersionHeader, SecChUaWow64, SecChUaWow64Header};
+pub use self::connection::{ConnectionHeader, InvalidProtocol, KeepAliveHeader, Protocol,
+                           UpgradeHeader, Via, ViaHeader};
+pub use self::content_disposition::{ContentDispositionHeader, DispositionType};
+pub use self::cookie::{CookieHeader, CookieJar, SameSite, SetCookie, SetCookieHeader};
+pub use self::cors::{AccessControlAllowCredentialsHeader, AccessControlAllowHeadersHeader,
+                     AccessControlAllowMethodsHeader, AccessControlAllowOriginHeader,
+                     AccessControlExposeHeadersHeader, AccessControlMaxAgeHeader,
+                     AccessControlRequestHeadersHeader, AccessControlRequestMethodHeader,
+                     AllowOrigin, OriginHeader};
+pub use self::date::{DateHeader, ExpiresHeader, IfModifiedSinceHeader, IfUnmodifiedSinceHeader,
+                     LastModifiedHeader, RetryAfter, RetryAfterHeader};
+pub use self::encoding::{AcceptEncodingHeader, CodingRange, ContentCoding, ContentEncodingHeader,
+                         InvalidContentCoding};
+pub use self::etag::{ETagHeader, EntityTag, EntityTagMatch, IfMatchHeader, IfNoneMatchHeader,
+                     InvalidEntityTag};
+pub use self::expect::{ExpectHeader, Expectation};
+pub use self::fetch_metadata::{SecFetchDest, SecFetchDestHeader, SecFetchMode, SecFetchModeHeader,
+                               SecFetchSite, SecFetchSiteHeader, SecFetchUser,
+                               SecFetchUserHeader};
+pub use self::forwarded::{ForwardedElement, ForwardedHeader, InvalidForwardedElement, InvalidNode,
+                          Node, NodeName, NodePort, XForwardedForHeader, XForwardedHostHeader,
+                          XForwardedProtoHeader};
+pub use self::gpc::SecGpcHeader;
+pub use self::language::{AcceptLanguageHeader, ContentLanguageHeader, InvalidLanguageTag,
+                         LanguageRange, LanguageTag};
+pub use self::link::{Link, LinkHeader};
+pub use self::list::CommaDelimited;
+pub use self::location::{ContentLocationHeader, LocationHeader, RefererHeader};
+pub use self::prefer::{ExtensionPreference, Handling, InvalidPreference, PreferHeader, Preference,
+                         PreferenceAppliedHeader, ReturnPreference};
+pub use self::quality::{InvalidQuality, InvalidQualityItem, Quality, QualityItem};
+pub use self::range::{AcceptRangesHeader, ByteRange, ByteRangeSpec, ContentRangeHeader, IfRange,
+                      IfRangeHeader, InvalidByteRangeSpec, RangeHeader};
+pub use self::security::{AllowlistMember, ContentSecurityPolicyHeader, FrameOptions,
+                         InvalidReferrerPolicy, PermissionsPolicyHeader, ReferrerPolicy,
+                         ReferrerPolicyHeader, StrictTransportSecurityHeader,
+                         XContentTypeOptionsHeader, XFrameOptionsHeader};
+pub use self::server_timing::{InvalidMetric, Metric, ServerTimingHeader};
+pub use self::structured::{BareItem, Decimal, Dictionary, InnerList, InvalidStructuredField, Item,
+                           List, Member, Parameters, StructuredField, StructuredHeader,
+                           StructuredValue};
+pub use self::trailer::TrailerHeader;
+pub use self::user_agent::{Product, ProductToken, ServerHeader, UserAgentHeader};
+pub use self::vary::{Vary, VaryHeader};
+
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{TypedHeader, RawHeader};
+///
+/// #[derive(PartialEq, Eq, Debug)]
+/// pub struct UserAgentHeader {
+///     value: String
+/// }
+///
+/// impl UserAgentHeader {
+///     pub fn value(&self) -> &str {
+///         &self.value
+///     }
+/// }
+///
+/// impl TypedHeader for UserAgentHeader {
+///     fn name() -> &'static str {
+///         "user-agent"
+///     }
+///
+///     fn canonical_name() -> &'static str {
+///        "User-Agent"
+///     }
+///
+///     fn parse(raw: &[&RawHeader]) -> Option<Self> {
+///         if raw.is_empty() {
+///             return None;
+///         }
+///
+///         Some(UserAgentHeader { value: raw[0].value().to_string() })
+///     }
+///
+///     fn raw_values(&self) -> Vec<String> {
+///       vec![format!("{}", self.value)]
+///     }
+/// }
+/// ```
+pub trait TypedHeader: Eq + Sized {
+    /// This is the name of the header in lower case.
+    /// It is used in [`Headers`] to look up the raw header(s).
+    ///
+    /// [`Headers`]: struct.Headers.html#method.get
+    fn name() -> &'static str;
+
+    /// This is the name of the header in its canonical form.
+    /// Used by [`to_raw`] as the header name.
+    ///
+    /// [`to_raw`]: trait.TypedHeader.html#method.to_raw
+    fn canonical_name() -> &'static str;
+
+    /// Converts a list of raw values to a `TypedHeader`
+    /// The list is required for headers like `Set-Cookie` which might appear
+    /// multiple times in a response. Other headers might only
+    /// use the first value of `raw` and ignore the rest.
+    // TODO: consider using Result instead of Option
+    // (however we still need to be able to represent the absence of a header)
+    fn parse(raw: &[&RawHeader]) -> Option<Self>;
+
+    /// Returns the raw values of this header.
+    /// Used by [`to_raw`] as the header value.
+    /// When multiple values are returned,
+    /// [`to_raw`] will return multiple raw headers for each value.
+    ///
+    /// [`to_raw`]: trait.TypedHeader.html#method.to_raw
+    fn raw_values(&self) -> Vec<String>;
+
+    /// Converts the header back to one or more [`RawHeader`]s
+    ///
+    /// [`RawHeader`]: struct.RawHeader.html
+    fn to_raw(&self) -> Vec<RawHeader> {
+        self.raw_values()
+            .iter()
+            .map(|value| RawHeader::new(Self::canonical_name(), value))
+            .collect()
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+pub struct RawHeader {
+    name: String,
+    value: String,
+}
+
+impl RawHeader {
+    pub fn new<S: Into<String>>(name: S, value: S) -> Self {
+        RawHeader {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn parse<S: Into<String>>(raw: S) -> Self {
+        let raw = raw.into();
+
+        match raw.find(':') {
+            Some(index) => RawHeader::new(&raw[..index], raw[index + 1..].trim()),
+            None => RawHeader::new(raw.as_str(), ""),
+        }
+    }
+
+    pub fn lower_name(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{Headers, DntHeader, Dnt};
+///
+/// let headers = Headers::new();
+/// let dnt : DntHeader = headers.get().unwrap();
+///
+/// assert_eq!(Dnt::Unspecified, dnt.value());
+/// ```
+#[derive(Default, Debug)]
+pub struct Headers {
+    headers: Vec<RawHeader>,
+}
+
+/// Fields that only apply to a single connection and must not be forwarded.
+/// [RFC7230, Section 6.1](http://www.iana.org/go/rfc7230)
+const HOP_BY_HOP_HEADERS: [&str; 9] = ["connection",
+                                       "keep-alive",
+                                       "proxy-authenticate",
+                                       "proxy-authorization",
+                                       "proxy-connection",
+                                       "te",
+                                       "trailer",
+                                       "transfer-encoding",
+                                       "upgrade"];
+
+// TODO: allow creation from iterator
+impl Headers {
+    pub fn new() -> Self {
+        Headers { headers: Vec::new() }
+    }
+
+    pub fn append<H: TypedHeader>(&mut self, header: H) {
+        for header in header.to_raw() {
+            self.append_raw(header);
+        }
+    }
+
+    pub fn append_raw(&mut self, header: RawHeader) {
+        self.headers.push(header);
+    }
+
+    /// Replaces all raw headers of the same name with `header`.
+    pub fn set<H: TypedHeader>(&mut self, header: H) {
+        self.remove(H::name());
+        self.append(header);
+    }
+
+    /// Removes all raw headers named `name` (in lower case).
+    pub fn remove(&mut self, name: &str) {
+        self.headers.retain(|header| header.lower_name() != name);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.headers.iter().any(|header| header.lower_name() == name)
+    }
+
+    /// Iterates over the raw headers in the order they were appended.
+    pub fn iter(&self) -> slice::Iter<'_, RawHeader> {
+        self.headers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Removes the hop-by-hop headers defined by
+    /// [RFC7230, Section 6.1](http://www.iana.org/go/rfc7230)
+    /// as well as every header named in `Connection`.
+    /// A proxy must do this before forwarding a message.
+    pub fn strip_hop_by_hop(&mut self) {
+        let mut names: Vec<String> = self.get::<ConnectionHeader>()
+            .map(|connection| connection.options().iter().map(|o| o.to_lowercase()).collect())
+            .unwrap_or_default();
+
+        names.extend(HOP_BY_HOP_HEADERS.iter().map(|name| name.to_string()));
+
+        self.headers.retain(|header| !names.contains(&header.lower_name()));
+    }
+
+    pub fn get<H: TypedHeader>(&self) -> Option<H> {
+        let raw = self.get_raw(H::name());
+
+        H::parse(raw.as_slice())
+    }
+
+    pub fn get_raw(&self, name: &str) -> Vec<&RawHeader> {
+        // TODO: ask @SirRade for an opinion on this
+        self.headers
+            .iter()
+            .filter(|header| header.lower_name() == name)
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Dnt {
+    Disabled,
+    Enabled,
+    Unspecified,
+}
+
+impl fmt::Display for Dnt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = match *self {
+            Dnt::Disabled => "0",
+            Dnt::Enabled => "1",
+            Dnt::Unspecified => "",
+        };
+
+        write!(f, "{}", value)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug)]
+pub struct DntHeader {
+    value: Dnt,
+}
+
+impl DntHeader {
+    pub fn new(value: Dnt) -> Self {
+        DntHeader { value }
+    }
+
+    pub fn value(&self) -> Dnt {
+        self.value
+    }
+}
+
+impl TypedHeader for DntHeader {
+    fn name() -> &'static str {
+        "dnt"
+    }
+
+    fn canonical_name() -> &'static str {
+        "DNT"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return Some(DntHeader { value: Dnt::Unspecified });
+        }
+
+        let value = match raw[0].value() {
+            "1" => Dnt::Enabled,
+            "0" => Dnt::Disabled,
+            _ => Dnt::Unspecified,
+        };
+
+        Some(DntHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![format!("{}", self.value)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_raw_header() {
+        let header = RawHeader::new("X-Foo", "bar");
+
+        assert_eq!("X-Foo", header.name());
+        assert_eq!("bar", header.value());
+    }
+
+    #[test]
+    fn test_parse_raw_header() {
+        let header = RawHeader::parse("X-Foo:  bar: baz ");
+
+        assert_eq!("X-Foo", header.name());
+        assert_eq!("bar: baz", header.value());
+    }
+
+    #[test]
+    fn test_get_header() {
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("dnt", "1"));
+
+        let dnt: DntHeader = headers.get().unwrap();
+
+        assert_eq!(Dnt::Enabled, dnt.value());
+    }
+
+    #[test]
+    fn test_user_agent() {
+        {
+            let ua = UserAgentHeader::parse(&[&RawHeader::new("user-agent", "foobar/1.1")]);
+
+            assert!(ua.is_some());
+            assert_eq!("foobar/1.1", ua.unwrap().value());
+        }
+
+        {
+            let ua = UserAgentHeader::parse(&[]);
+
+            assert!(ua.is_none());
+        }
+    }
+
+    #[test]
+    fn test_dnt() {
+        assert_eq!("0", format!("{}", Dnt::Disabled));
+        assert_eq!("1", format!("{}", Dnt::Enabled));
+        assert_eq!("", format!("{}", Dnt::Unspecified));
+    }
+
+    #[test]
+    fn test_append() {
+        let mut headers = Headers::new();
+
+        headers.append(DntHeader::new(Dnt::Enabled));
+
+        let result: DntHeader = headers.get().unwrap();
+
+        assert_eq!(DntHeader::new(Dnt::Enabled), result);
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Via", "1.1 b"));
+        headers.append_raw(RawHeader::new("Accept", "*/*"));
+        headers.append_raw(RawHeader::new("Via", "1.1 a"));
+
+        let via: Vec<&str> = headers.get_raw("via").iter().map(|header| header.value()).collect();
+
+        assert_eq!(vec!["1.1 b", "1.1 a"], via);
+        assert_eq!(3, headers.len());
+
+        headers.remove("via");
+
+        assert!(!headers.contains("via"));
+        assert_eq!(1, headers.len());
+    }
+
+    #[test]
+    fn test_strip_hop_by_hop() {
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Connection", "close, X-Trace"));
+        headers.append_raw(RawHeader::new("Keep-Alive", "timeout=5"));
+        headers.append_raw(RawHeader::new("Transfer-Encoding", "chunked"));
+        headers.append_raw(RawHeader::new("X-Trace", "abc"));
+        headers.append_raw(RawHeader::new("Host", "example.com"));
+        headers.append_raw(RawHeader::new("TE", "trailers"));
+
+        headers.strip_hop_by_hop();
+
+        let names: Vec<&str> = headers.iter().map(|header| header.name()).collect();
+
+        assert_eq!(vec!["Host"], names);
+    }
+}
@@ -0,0 +1,397 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidPreference;
+
+/// The value of the `return` preference.
+/// [RFC7240, Section 4.2](http://www.iana.org/go/rfc7240)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ReturnPreference {
+    /// Only a minimal response, like `204 No Content`, is requested.
+    Minimal,
+    /// The current representation of the target resource is requested.
+    Representation,
+}
+
+/// The value of the `handling` preference.
+/// [RFC7240, Section 4.4](http://www.iana.org/go/rfc7240)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Handling {
+    /// Invalid requests should be rejected.
+    Strict,
+    /// The server should try to process invalid requests anyway.
+    Lenient,
+}
+
+/// A preference that is not defined by RFC7240, including standard
+/// preferences with an invalid value.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ExtensionPreference {
+    name: String,
+    value: Option<String>,
+    params: Vec<(String, Option<String>)>,
+}
+
+impl ExtensionPreference {
+    /// The name is stored in lowercase, since preference names are case-insensitive.
+    pub fn new<S: Into<String>>(name: S, value: Option<String>) -> Self {
+        ExtensionPreference {
+            name: name.into().to_lowercase(),
+            value,
+            params: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    pub fn params(&self) -> &[(String, Option<String>)] {
+        &self.params
+    }
+
+    /// Returns the value of the first parameter called `name`, ignoring case.
+    pub fn param(&self, name: &str) -> Option<Option<&str>> {
+        self.params
+            .iter()
+            .find(|&(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_deref())
+    }
+
+    pub fn push_param<S: Into<String>>(&mut self, name: S, value: Option<String>) {
+        self.params.push((name.into().to_lowercase(), value));
+    }
+}
+
+/// A single preference of the `Prefer` header.
+/// Parameters of the standard preferences are ignored.
+/// [RFC7240, Section 2](http://www.iana.org/go/rfc7240)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Preference {
+    Return(ReturnPreference),
+    /// The client prefers `202 Accepted` over waiting for a long running operation.
+    /// [RFC7240, Section 4.1](http://www.iana.org/go/rfc7240)
+    RespondAsync,
+    /// The number of seconds the client is willing to wait for a response.
+    /// [RFC7240, Section 4.3](http://www.iana.org/go/rfc7240)
+    Wait(u64),
+    Handling(Handling),
+    Extension(ExtensionPreference),
+}
+
+impl Preference {
+    pub fn name(&self) -> &str {
+        match *self {
+            Preference::Return(_) => "return",
+            Preference::RespondAsync => "respond-async",
+            Preference::Wait(_) => "wait",
+            Preference::Handling(_) => "handling",
+            Preference::Extension(ref extension) => extension.name(),
+        }
+    }
+
+    pub fn value(&self) -> Option<String> {
+        match *self {
+            Preference::Return(ReturnPreference::Minimal) => Some("minimal".to_string()),
+            Preference::Return(ReturnPreference::Representation) => {
+                Some("representation".to_string())
+            }
+            Preference::RespondAsync => None,
+            Preference::Wait(seconds) => Some(seconds.to_string()),
+            Preference::Handling(Handling::Strict) => Some("strict".to_string()),
+            Preference::Handling(Handling::Lenient) => Some("lenient".to_string()),
+            Preference::Extension(ref extension) => extension.value.clone(),
+        }
+    }
+
+    /// Formats the preference without parameters, as used in `Preference-Applied`.
+    fn write_applied(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value() {
+            Some(ref value) => write!(f, "{}={}", self.name(), syntax::quote_if_needed(value)),
+            None => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+impl FromStr for Preference {
+    type Err = InvalidPreference;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = syntax::split_quoted(s, ';').into_iter();
+        let (name, value) = syntax::split_pair(parts.next().ok_or(InvalidPreference)?);
+
+        if !syntax::is_token(name) {
+            return Err(InvalidPreference);
+        }
+
+        let lower_name = name.to_ascii_lowercase();
+        let value = value.map(syntax::unquote);
+        let preference = match (lower_name.as_str(), value.as_deref()) {
+            ("return", Some("minimal")) => Some(Preference::Return(ReturnPreference::Minimal)),
+            ("return", Some("representation")) => {
+                Some(Preference::Return(ReturnPreference::Representation))
+            }
+            ("respond-async", None) => Some(Preference::RespondAsync),
+            ("wait", Some(seconds)) => syntax::parse_delta_seconds(seconds).map(Preference::Wait),
+            ("handling", Some("strict")) => Some(Preference::Handling(Handling::Strict)),
+            ("handling", Some("lenient")) => Some(Preference::Handling(Handling::Lenient)),
+            _ => None,
+        };
+
+        if let Some(preference) = preference {
+            return Ok(preference);
+        }
+
+        let mut extension = ExtensionPreference::new(lower_name, value);
+
+        for param in parts {
+            let (name, value) = syntax::split_pair(param);
+
+            if syntax::is_token(name) {
+                extension.push_param(name, value.map(syntax::unquote));
+            }
+        }
+
+        Ok(Preference::Extension(extension))
+    }
+}
+
+impl fmt::Display for Preference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_applied(f)?;
+
+        if let Preference::Extension(ref extension) = *self {
+            for (name, value) in &extension.params {
+                match *value {
+                    Some(ref value) => write!(f, "; {}={}", name, syntax::quote_if_needed(value))?,
+                    None => write!(f, "; {}", name)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a preference without parameters.
+struct Applied<'a>(&'a Preference);
+
+impl<'a> fmt::Display for Applied<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.write_applied(f)
+    }
+}
+
+fn parse_preferences(raw: &[&RawHeader]) -> Option<Vec<Preference>> {
+    let preferences = CommaDelimited::from_raw_lossy(raw).into_items();
+
+    if preferences.is_empty() {
+        None
+    } else {
+        Some(preferences)
+    }
+}
+
+/// Optional behaviors the client would like the server to apply.
+/// Servers may ignore any preference. Only the first occurrence of a
+/// preference is considered.
+/// [RFC7240, Section 2](http://www.iana.org/go/rfc7240)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{PreferHeader, PreferenceAppliedHeader, Preference, RawHeader,
+///                             ReturnPreference, TypedHeader};
+///
+/// let raw = RawHeader::new("Prefer", "return=minimal, wait=10; foo=bar");
+/// let prefer = PreferHeader::parse(&[&raw]).unwrap();
+///
+/// assert_eq!(Some(ReturnPreference::Minimal), prefer.return_preference());
+/// assert_eq!(Some(10), prefer.wait());
+///
+/// let applied = PreferenceAppliedHeader::new(vec![Preference::Return(ReturnPreference::Minimal)]);
+///
+/// assert_eq!(vec!["return=minimal".to_string()], applied.raw_values());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PreferHeader {
+    preferences: Vec<Preference>,
+}
+
+impl PreferHeader {
+    pub fn new(preferences: Vec<Preference>) -> Self {
+        PreferHeader { preferences }
+    }
+
+    pub fn preferences(&self) -> &[Preference] {
+        &self.preferences
+    }
+
+    pub fn push(&mut self, preference: Preference) {
+        self.preferences.push(preference);
+    }
+
+    /// Returns the first preference called `name`, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&Preference> {
+        self.preferences.iter().find(|preference| preference.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn return_preference(&self) -> Option<ReturnPreference> {
+        match self.get("return") {
+            Some(&Preference::Return(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn respond_async(&self) -> bool {
+        self.get("respond-async") == Some(&Preference::RespondAsync)
+    }
+
+    pub fn wait(&self) -> Option<u64> {
+        match self.get("wait") {
+            Some(&Preference::Wait(seconds)) => Some(seconds),
+            _ => None,
+        }
+    }
+
+    pub fn handling(&self) -> Option<Handling> {
+        match self.get("handling") {
+            Some(&Preference::Handling(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl TypedHeader for PreferHeader {
+    fn name() -> &'static str {
+        "prefer"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Prefer"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        parse_preferences(raw).map(PreferHeader::new)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let preferences: Vec<String> = self.preferences
+            .iter()
+            .map(|preference| preference.to_string())
+            .collect();
+
+        vec![preferences.join(", ")]
+    }
+}
+
+/// The preferences the server applied when processing the request.
+/// Parameters are not sent.
+/// [RFC7240, Section 3](http://www.iana.org/go/rfc7240)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PreferenceAppliedHeader {
+    preferences: Vec<Preference>,
+}
+
+impl PreferenceAppliedHeader {
+    pub fn new(preferences: Vec<Preference>) -> Self {
+        PreferenceAppliedHeader { preferences }
+    }
+
+    pub fn preferences(&self) -> &[Preference] {
+        &self.preferences
+    }
+
+    pub fn push(&mut self, preference: Preference) {
+        self.preferences.push(preference);
+    }
+
+    /// Checks whether a preference called `name` was applied, ignoring case.
+    pub fn contains(&self, name: &str) -> bool {
+        self.preferences.iter().any(|preference| preference.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl TypedHeader for PreferenceAppliedHeader {
+    fn name() -> &'static str {
+        "preference-applied"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Preference-Applied"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        parse_preferences(raw).map(PreferenceAppliedHeader::new)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let preferences: Vec<String> = self.preferences
+            .iter()
+            .map(|preference| Applied(preference).to_string())
+            .collect();
+
+        vec![preferences.join(", ")]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(value: &str) -> Option<PreferHeader> {
+        PreferHeader::parse(&[&RawHeader::new("Prefer", value)])
+    }
+
+    #[test]
+    fn test_parse_standard() {
+        let prefer = parse("RETURN=representation; foo, respond-async, wait=\"5\", \
+                            handling=lenient, return=minimal")
+            .unwrap();
+
+        assert_eq!(Some(ReturnPreference::Representation), prefer.return_preference());
+        assert!(prefer.respond_async());
+        assert_eq!(Some(5), prefer.wait());
+        assert_eq!(Some(Handling::Lenient), prefer.handling());
+        assert_eq!(vec!["return=representation, respond-async, wait=5, handling=lenient, \
+                         return=minimal"
+                            .to_string()],
+                   prefer.raw_values());
+    }
+
+    #[test]
+    fn test_parse_extension() {
+        let prefer = parse("Foo=\"a b\"; Bar; baz=1, return=nothing, =x").unwrap();
+        let mut foo = ExtensionPreference::new("foo", Some("a b".to_string()));
+
+        foo.push_param("bar", None);
+        foo.push_param("baz", Some("1".to_string()));
+
+        assert_eq!(&[Preference::Extension(foo.clone()),
+                     Preference::Extension(ExtensionPreference::new("return",
+                                                                    Some("nothing".to_string())))],
+                   prefer.preferences());
+        assert_eq!(Some(None), foo.param("BAR"));
+        assert_eq!(None, prefer.return_preference());
+        assert_eq!(vec!["foo=\"a b\"; bar; baz=1, return=nothing".to_string()],
+                   prefer.raw_values());
+        assert_eq!(None, parse(" , "));
+    }
+
+    #[test]
+    fn test_preference_applied() {
+        let raw = RawHeader::new("Preference-Applied", "respond-async, foo=bar; ignored");
+        let applied = PreferenceAppliedHeader::parse(&[&raw]).unwrap();
+
+        assert!(applied.contains("Respond-Async"));
+        assert!(!applied.contains("wait"));
+        assert_eq!(vec!["respond-async, foo=bar".to_string()], applied.raw_values());
+    }
+}
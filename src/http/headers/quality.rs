@@ -0,0 +1,194 @@
+use std::fmt;
+use std::str::FromStr;
+use super::syntax;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidQuality;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidQualityItem;
+
+/// A relative weight between `0` and `1` with up to three decimals.
+/// A weight of `0` means "not acceptable".
+/// [RFC7231, Section 5.3.1](http://www.iana.org/go/rfc7231)
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Quality {
+    millis: u16,
+}
+
+impl Quality {
+    /// Returns `None` if `millis` is greater than `1000`.
+    pub fn from_millis(millis: u16) -> Option<Self> {
+        if millis > 1000 {
+            return None;
+        }
+
+        Some(Quality { millis })
+    }
+
+    pub fn one() -> Self {
+        Quality { millis: 1000 }
+    }
+
+    pub fn zero() -> Self {
+        Quality { millis: 0 }
+    }
+
+    pub fn millis(&self) -> u16 {
+        self.millis
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.millis == 0
+    }
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Quality::one()
+    }
+}
+
+impl FromStr for Quality {
+    type Err = InvalidQuality;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = match s.find('.') {
+            Some(index) => (&s[..index], &s[index + 1..]),
+            None => (s, ""),
+        };
+
+        if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(InvalidQuality);
+        }
+
+        let fraction = fraction.bytes()
+            .chain("000".bytes())
+            .take(3)
+            .fold(0, |millis, digit| millis * 10 + u16::from(digit - b'0'));
+
+        match integer {
+            "0" => Ok(Quality { millis: fraction }),
+            "1" if fraction == 0 => Ok(Quality::one()),
+            _ => Err(InvalidQuality),
+        }
+    }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.millis {
+            1000 => write!(f, "1"),
+            0 => write!(f, "0"),
+            millis => {
+                let fraction = format!("{:03}", millis);
+
+                write!(f, "0.{}", fraction.trim_end_matches('0'))
+            }
+        }
+    }
+}
+
+/// A value together with its `q` parameter, as used by the `Accept-*` headers.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct QualityItem<T> {
+    item: T,
+    quality: Quality,
+}
+
+impl<T> QualityItem<T> {
+    pub fn new(item: T, quality: Quality) -> Self {
+        QualityItem { item, quality }
+    }
+
+    pub fn item(&self) -> &T {
+        &self.item
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+}
+
+/// Parameters other than `q` are ignored.
+impl<T: FromStr> FromStr for QualityItem<T> {
+    type Err = InvalidQualityItem;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (item, quality) = parse_weighted(s).ok_or(InvalidQualityItem)?;
+        let item = item.parse().map_err(|_| InvalidQualityItem)?;
+
+        Ok(QualityItem::new(item, quality))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for QualityItem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.quality == Quality::one() {
+            write!(f, "{}", self.item)
+        } else {
+            write!(f, "{};q={}", self.item, self.quality)
+        }
+    }
+}
+
+/// Splits an element like `gzip;q=0.5` into its value and weight.
+/// Parameters other than `q` are ignored, an invalid weight yields `None`.
+fn parse_weighted(element: &str) -> Option<(&str, Quality)> {
+    let mut parts = syntax::split_quoted(element, ';').into_iter();
+    let value = parts.next()?;
+    let mut quality = Quality::one();
+
+    for param in parts {
+        if let (name, Some(weight)) = syntax::split_pair(param) {
+            if name.eq_ignore_ascii_case("q") {
+                quality = weight.parse().ok()?;
+            }
+        }
+    }
+
+    Some((value, quality))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Quality::one()), "1".parse());
+        assert_eq!(Ok(Quality::one()), "1.000".parse());
+        assert_eq!(Ok(Quality::zero()), "0".parse());
+        assert_eq!(Quality::from_millis(500), "0.5".parse().ok());
+        assert_eq!(Quality::from_millis(1), "0.001".parse().ok());
+        assert_eq!(Err(InvalidQuality), "1.001".parse::<Quality>());
+        assert_eq!(Err(InvalidQuality), "0.0001".parse::<Quality>());
+        assert_eq!(Err(InvalidQuality), "2".parse::<Quality>());
+        assert_eq!(Err(InvalidQuality), "".parse::<Quality>());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("0.25", Quality::from_millis(250).unwrap().to_string());
+        assert_eq!("0.001", Quality::from_millis(1).unwrap().to_string());
+        assert_eq!("gzip;q=0.8",
+                   QualityItem::new("gzip", Quality::from_millis(800).unwrap()).to_string());
+        assert_eq!("br", QualityItem::new("br", Quality::one()).to_string());
+    }
+
+    #[test]
+    fn test_parse_weighted() {
+        assert_eq!(Some(("gzip", Quality::from_millis(500).unwrap())),
+                   parse_weighted("gzip ; Q=0.5"));
+        assert_eq!(Some(("br", Quality::one())), parse_weighted("br"));
+        assert_eq!(None, parse_weighted("br;q=abc"));
+    }
+
+    #[test]
+    fn test_parse_item() {
+        assert_eq!(Ok(QualityItem::new(7, Quality::from_millis(300).unwrap())),
+                   "7;q=0.3".parse::<QualityItem<u8>>());
+        assert_eq!(Err(InvalidQualityItem), "x;q=0.3".parse::<QualityItem<u8>>());
+        assert_eq!(Err(InvalidQualityItem), "7;q=x".parse::<QualityItem<u8>>());
+    }
+}
@@ -0,0 +1,484 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, TypedHeader, RawHeader, EntityTag};
+use super::super::date::HttpDate;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidByteRangeSpec;
+
+/// A single `byte-range-spec` or `suffix-byte-range-spec` of a `Range` header.
+/// [RFC7233, Section 2.1](http://www.iana.org/go/rfc7233)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ByteRangeSpec {
+    /// `first-last`, both inclusive
+    FromTo(u64, u64),
+    /// `first-`, until the end of the representation
+    From(u64),
+    /// `-length`, the last `length` bytes of the representation
+    Suffix(u64),
+}
+
+impl ByteRangeSpec {
+    /// Resolves the spec against the length of a representation.
+    /// Returns `None` if the range is not satisfiable.
+    pub fn to_satisfiable_range(self, length: u64) -> Option<ByteRange> {
+        match self {
+            ByteRangeSpec::FromTo(first, _) | ByteRangeSpec::From(first) if first >= length => None,
+            ByteRangeSpec::FromTo(first, last) => {
+                Some(ByteRange { first, last: last.min(length - 1) })
+            }
+            ByteRangeSpec::From(first) => Some(ByteRange { first, last: length - 1 }),
+            ByteRangeSpec::Suffix(0) => None,
+            ByteRangeSpec::Suffix(_) if length == 0 => None,
+            ByteRangeSpec::Suffix(suffix) => {
+                Some(ByteRange { first: length - suffix.min(length), last: length - 1 })
+            }
+        }
+    }
+}
+
+impl FromStr for ByteRangeSpec {
+    type Err = InvalidByteRangeSpec;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let index = s.find('-').ok_or(InvalidByteRangeSpec)?;
+        let first = &s[..index];
+        let last = &s[index + 1..];
+
+        match (parse_position(first), parse_position(last)) {
+            (Some(first), Some(last)) if first <= last => Ok(ByteRangeSpec::FromTo(first, last)),
+            (Some(first), None) if last.is_empty() => Ok(ByteRangeSpec::From(first)),
+            (None, Some(length)) if first.is_empty() => Ok(ByteRangeSpec::Suffix(length)),
+            _ => Err(InvalidByteRangeSpec),
+        }
+    }
+}
+
+impl fmt::Display for ByteRangeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ByteRangeSpec::FromTo(first, last) => write!(f, "{}-{}", first, last),
+            ByteRangeSpec::From(first) => write!(f, "{}-", first),
+            ByteRangeSpec::Suffix(length) => write!(f, "-{}", length),
+        }
+    }
+}
+
+fn parse_position(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    value.parse().ok()
+}
+
+/// A satisfiable range of bytes, both positions are inclusive.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ByteRange {
+    first: u64,
+    last: u64,
+}
+
+impl ByteRange {
+    /// Returns `None` if `first` is greater than `last`.
+    pub fn new(first: u64, last: u64) -> Option<Self> {
+        if first > last {
+            return None;
+        }
+
+        Some(ByteRange { first, last })
+    }
+
+    pub fn first(&self) -> u64 {
+        self.first
+    }
+
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+
+    /// The number of bytes in this range, which is never zero.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.last - self.first + 1
+    }
+}
+
+impl fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.first, self.last)
+    }
+}
+
+/// The `Range` request header. Only the `bytes` unit is supported,
+/// ranges in any other unit are ignored.
+/// [RFC7233, Section 3.1](http://www.iana.org/go/rfc7233)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{ByteRangeSpec, RangeHeader, RawHeader, TypedHeader};
+///
+/// let range = RangeHeader::parse(&[&RawHeader::new("Range", "bytes=0-99, -100")]).unwrap();
+///
+/// assert_eq!(&[ByteRangeSpec::FromTo(0, 99), ByteRangeSpec::Suffix(100)],
+///            range.ranges());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RangeHeader {
+    ranges: Vec<ByteRangeSpec>,
+}
+
+impl RangeHeader {
+    /// Returns `None` if `ranges` is empty.
+    pub fn new(ranges: Vec<ByteRangeSpec>) -> Option<Self> {
+        if ranges.is_empty() {
+            return None;
+        }
+
+        Some(RangeHeader { ranges })
+    }
+
+    pub fn ranges(&self) -> &[ByteRangeSpec] {
+        &self.ranges
+    }
+}
+
+impl TypedHeader for RangeHeader {
+    fn name() -> &'static str {
+        "range"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Range"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value().trim();
+        let index = value.find('=')?;
+
+        if !value[..index].trim().eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let ranges = value[index + 1..].parse::<CommaDelimited<ByteRangeSpec>>().ok()?;
+
+        if ranges.is_empty() {
+            return None;
+        }
+
+        Some(RangeHeader { ranges: ranges.into_items() })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let ranges: Vec<String> = self.ranges.iter().map(|range| range.to_string()).collect();
+
+        vec![format!("bytes={}", ranges.join(", "))]
+    }
+}
+
+/// The `Content-Range` header in the `bytes` unit.
+/// [RFC7233, Section 4.2](http://www.iana.org/go/rfc7233)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ContentRangeHeader {
+    range: Option<ByteRange>,
+    complete_length: Option<u64>,
+}
+
+impl ContentRangeHeader {
+    /// Describes a partial response, e.g. `bytes 0-499/1234`
+    /// or `bytes 0-499/*` if the complete length is unknown.
+    pub fn new(range: ByteRange, complete_length: Option<u64>) -> Self {
+        ContentRangeHeader {
+            range: Some(range),
+            complete_length,
+        }
+    }
+
+    /// Used in `416 Range Not Satisfiable` responses, e.g. `bytes */1234`.
+    pub fn unsatisfied(complete_length: u64) -> Self {
+        ContentRangeHeader {
+            range: None,
+            complete_length: Some(complete_length),
+        }
+    }
+
+    pub fn range(&self) -> Option<ByteRange> {
+        self.range
+    }
+
+    pub fn complete_length(&self) -> Option<u64> {
+        self.complete_length
+    }
+}
+
+impl fmt::Display for ContentRangeHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bytes ")?;
+
+        match self.range {
+            Some(range) => write!(f, "{}/", range)?,
+            None => write!(f, "*/")?,
+        }
+
+        match self.complete_length {
+            Some(length) => write!(f, "{}", length),
+            None => write!(f, "*"),
+        }
+    }
+}
+
+impl TypedHeader for ContentRangeHeader {
+    fn name() -> &'static str {
+        "content-range"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Content-Range"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value().trim();
+        let index = value.find(' ')?;
+
+        if !value[..index].eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let rest = &value[index + 1..];
+        let slash = rest.find('/')?;
+
+        let range = match &rest[..slash] {
+            "*" => None,
+            range => {
+                match range.parse().ok()? {
+                    ByteRangeSpec::FromTo(first, last) => Some(ByteRange { first, last }),
+                    _ => return None,
+                }
+            }
+        };
+
+        let complete_length = match &rest[slash + 1..] {
+            "*" => None,
+            length => Some(parse_position(length)?),
+        };
+
+        match (range, complete_length) {
+            (None, None) => None,
+            (Some(range), Some(length)) if range.last() >= length => None,
+            _ => {
+                Some(ContentRangeHeader {
+                    range,
+                    complete_length,
+                })
+            }
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+/// [RFC7233, Section 2.3](http://www.iana.org/go/rfc7233)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AcceptRangesHeader {
+    units: Vec<String>,
+}
+
+impl AcceptRangesHeader {
+    pub fn new(units: Vec<String>) -> Self {
+        AcceptRangesHeader { units }
+    }
+
+    /// `Accept-Ranges: bytes`
+    pub fn bytes() -> Self {
+        AcceptRangesHeader::new(vec!["bytes".to_string()])
+    }
+
+    /// `Accept-Ranges: none`
+    pub fn none() -> Self {
+        AcceptRangesHeader::new(vec![])
+    }
+
+    /// The accepted range units, empty if ranges are not supported.
+    pub fn units(&self) -> &[String] {
+        &self.units
+    }
+
+    pub fn accepts_bytes(&self) -> bool {
+        self.units.iter().any(|unit| unit.eq_ignore_ascii_case("bytes"))
+    }
+}
+
+impl TypedHeader for AcceptRangesHeader {
+    fn name() -> &'static str {
+        "accept-ranges"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Accept-Ranges"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let units = CommaDelimited::<String>::from_raw(raw)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|unit| !unit.eq_ignore_ascii_case("none"))
+            .collect();
+
+        Some(AcceptRangesHeader { units })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        if self.units.is_empty() {
+            vec!["none".to_string()]
+        } else {
+            vec![self.units.join(", ")]
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IfRange {
+    EntityTag(EntityTag),
+    Date(HttpDate),
+}
+
+/// [RFC7233, Section 3.2](http://www.iana.org/go/rfc7233)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IfRangeHeader {
+    value: IfRange,
+}
+
+impl IfRangeHeader {
+    pub fn new(value: IfRange) -> Self {
+        IfRangeHeader { value }
+    }
+
+    pub fn value(&self) -> &IfRange {
+        &self.value
+    }
+
+    /// Returns `true` if the validator matches the current representation,
+    /// in which case the `Range` header should be honored.
+    /// Entity tags use the strong comparison function and dates must match exactly.
+    pub fn matches(&self, etag: Option<&EntityTag>, last_modified: Option<HttpDate>) -> bool {
+        match self.value {
+            IfRange::EntityTag(ref tag) => etag.is_some_and(|etag| tag.strong_eq(etag)),
+            IfRange::Date(date) => last_modified == Some(date),
+        }
+    }
+}
+
+impl TypedHeader for IfRangeHeader {
+    fn name() -> &'static str {
+        "if-range"
+    }
+
+    fn canonical_name() -> &'static str {
+        "If-Range"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value().trim();
+
+        let value = match value.parse() {
+            Ok(tag) => IfRange::EntityTag(tag),
+            Err(_) => IfRange::Date(value.parse().ok()?),
+        };
+
+        Some(IfRangeHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        match self.value {
+            IfRange::EntityTag(ref tag) => vec![tag.to_string()],
+            IfRange::Date(date) => vec![date.to_string()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn range(value: &str) -> Option<RangeHeader> {
+        RangeHeader::parse(&[&RawHeader::new("Range", value)])
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(RangeHeader::new(vec![ByteRangeSpec::FromTo(500, 999)]),
+                   range("bytes=500-999"));
+        assert_eq!(RangeHeader::new(vec![ByteRangeSpec::From(9500), ByteRangeSpec::Suffix(500)]),
+                   range("Bytes = 9500-, ,-500"));
+        assert_eq!(None, range("bytes=5-1"));
+        assert_eq!(None, range("bytes=-"));
+        assert_eq!(None, range("bytes="));
+        assert_eq!(None, range("items=1-2"));
+        assert_eq!(vec!["bytes=0-0, -1".to_string()],
+                   range("bytes=0-0,-1").unwrap().raw_values());
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        assert_eq!(None, ByteRange::new(2, 1));
+        assert_eq!(Some(1), ByteRange::new(1, 1).map(|range| range.len()));
+        assert_eq!(None, RangeHeader::new(vec![]));
+    }
+
+    #[test]
+    fn test_satisfiable_range() {
+        assert_eq!(ByteRange::new(0, 9), ByteRangeSpec::FromTo(0, 100).to_satisfiable_range(10));
+        assert_eq!(None, ByteRangeSpec::From(10).to_satisfiable_range(10));
+        assert_eq!(ByteRange::new(0, 9), ByteRangeSpec::Suffix(20).to_satisfiable_range(10));
+        assert_eq!(ByteRange::new(8, 9), ByteRangeSpec::Suffix(2).to_satisfiable_range(10));
+        assert_eq!(None, ByteRangeSpec::Suffix(0).to_satisfiable_range(10));
+        assert_eq!(None, ByteRangeSpec::Suffix(1).to_satisfiable_range(0));
+    }
+
+    #[test]
+    fn test_content_range() {
+        let parse = |value| ContentRangeHeader::parse(&[&RawHeader::new("Content-Range", value)]);
+
+        assert_eq!(Some(ContentRangeHeader::new(ByteRange::new(42, 1233).unwrap(), Some(1234))),
+                   parse("bytes 42-1233/1234"));
+        assert_eq!(Some(ContentRangeHeader::new(ByteRange::new(42, 1233).unwrap(), None)),
+                   parse("bytes 42-1233/*"));
+        assert_eq!(Some(ContentRangeHeader::unsatisfied(1234)), parse("bytes */1234"));
+        assert_eq!(None, parse("bytes */*"));
+        assert_eq!(None, parse("bytes 0-1234/1234"));
+        assert_eq!(None, parse("bytes 5-"));
+        assert_eq!("bytes */10", ContentRangeHeader::unsatisfied(10).to_string());
+    }
+
+    #[test]
+    fn test_accept_ranges() {
+        let none = AcceptRangesHeader::parse(&[&RawHeader::new("Accept-Ranges", "none")]).unwrap();
+
+        assert!(!none.accepts_bytes());
+        assert_eq!(AcceptRangesHeader::none(), none);
+        assert_eq!(vec!["none".to_string()], none.raw_values());
+        assert!(AcceptRangesHeader::bytes().accepts_bytes());
+    }
+
+    #[test]
+    fn test_if_range() {
+        let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+        let etag = IfRangeHeader::parse(&[&RawHeader::new("If-Range", "\"abc\"")]).unwrap();
+        let modified = IfRangeHeader::parse(&[&RawHeader::new("If-Range",
+                                                              "Sun, 06 Nov 1994 08:49:37 GMT")])
+            .unwrap();
+
+        assert!(etag.matches(Some(&EntityTag::strong("abc").unwrap()), None));
+        assert!(!etag.matches(Some(&EntityTag::weak("abc").unwrap()), None));
+        assert!(modified.matches(None, Some(date)));
+        assert!(!modified.matches(Some(&EntityTag::strong("abc").unwrap()), None));
+        assert!(IfRangeHeader::parse(&[&RawHeader::new("If-Range", "abc")]).is_none());
+    }
+}
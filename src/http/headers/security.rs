@@ -0,0 +1,685 @@
+use std::fmt;
+use std::str::FromStr;
+use super::{CommaDelimited, Headers, TypedHeader, RawHeader};
+use super::syntax;
+
+/// Instructs browsers to only connect to the host using HTTPS.
+/// [RFC6797, Section 6.1](http://www.iana.org/go/rfc6797)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct StrictTransportSecurityHeader {
+    max_age: u64,
+    include_subdomains: bool,
+    preload: bool,
+}
+
+impl StrictTransportSecurityHeader {
+    pub fn new(max_age: u64) -> Self {
+        StrictTransportSecurityHeader {
+            max_age,
+            include_subdomains: false,
+            preload: false,
+        }
+    }
+
+    pub fn max_age(&self) -> u64 {
+        self.max_age
+    }
+
+    pub fn include_subdomains(&self) -> bool {
+        self.include_subdomains
+    }
+
+    pub fn set_include_subdomains(&mut self, include_subdomains: bool) {
+        self.include_subdomains = include_subdomains;
+    }
+
+    /// The non-standard `preload` directive, required by browser preload lists.
+    pub fn preload(&self) -> bool {
+        self.preload
+    }
+
+    pub fn set_preload(&mut self, preload: bool) {
+        self.preload = preload;
+    }
+}
+
+impl TypedHeader for StrictTransportSecurityHeader {
+    fn name() -> &'static str {
+        "strict-transport-security"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Strict-Transport-Security"
+    }
+
+    /// Only the first header is used. Directives must not appear more than once.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let mut max_age = None;
+        let mut include_subdomains = false;
+        let mut preload = false;
+
+        for directive in syntax::split_quoted(raw.first()?.value(), ';') {
+            let (name, value) = syntax::split_pair(directive);
+
+            match (name.to_lowercase().as_str(), value) {
+                ("max-age", Some(value)) if max_age.is_none() => {
+                    max_age = Some(syntax::parse_delta_seconds(&syntax::unquote(value))?);
+                }
+                ("includesubdomains", None) if !include_subdomains => include_subdomains = true,
+                ("preload", None) if !preload => preload = true,
+                ("max-age", _) | ("includesubdomains", _) | ("preload", _) => return None,
+                _ => {}
+            }
+        }
+
+        Some(StrictTransportSecurityHeader {
+            max_age: max_age?,
+            include_subdomains,
+            preload,
+        })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let mut value = format!("max-age={}", self.max_age);
+
+        if self.include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
+
+        if self.preload {
+            value.push_str("; preload");
+        }
+
+        vec![value]
+    }
+}
+
+/// A policy restricting the resources a document may load.
+/// [CSP Level 3](https://www.w3.org/TR/CSP3/#csp-header)
+///
+/// Directive names are stored in lower case and keep their order.
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::ContentSecurityPolicyHeader;
+///
+/// let mut csp = ContentSecurityPolicyHeader::new();
+///
+/// csp.set_directive("default-src", vec!["'self'".to_string()]);
+/// csp.set_directive("upgrade-insecure-requests", vec![]);
+///
+/// assert_eq!("default-src 'self'; upgrade-insecure-requests", csp.to_string());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ContentSecurityPolicyHeader {
+    directives: Vec<(String, Vec<String>)>,
+}
+
+impl ContentSecurityPolicyHeader {
+    pub fn new() -> Self {
+        ContentSecurityPolicyHeader::default()
+    }
+
+    pub fn directives(&self) -> &[(String, Vec<String>)] {
+        &self.directives
+    }
+
+    pub fn directive(&self, name: &str) -> Option<&[String]> {
+        self.directives
+            .iter()
+            .find(|&(other, _)| other.eq_ignore_ascii_case(name))
+            .map(|(_, sources)| sources.as_slice())
+    }
+
+    /// Replaces the directive or appends it if it does not exist yet.
+    pub fn set_directive<S: Into<String>>(&mut self, name: S, sources: Vec<String>) {
+        let name = name.into().to_lowercase();
+
+        match self.directives.iter_mut().find(|(other, _)| *other == name) {
+            Some(directive) => directive.1 = sources,
+            None => self.directives.push((name, sources)),
+        }
+    }
+
+    pub fn remove_directive(&mut self, name: &str) {
+        self.directives.retain(|(other, _)| !other.eq_ignore_ascii_case(name));
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicyHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let directives: Vec<String> = self.directives
+            .iter()
+            .map(|(name, sources)| if sources.is_empty() {
+                name.clone()
+            } else {
+                format!("{} {}", name, sources.join(" "))
+            })
+            .collect();
+
+        write!(f, "{}", directives.join("; "))
+    }
+}
+
+impl TypedHeader for ContentSecurityPolicyHeader {
+    fn name() -> &'static str {
+        "content-security-policy"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Content-Security-Policy"
+    }
+
+    /// Several headers are separate policies that are all enforced,
+    /// only the first one is parsed. Repeated directives are ignored.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let mut header = ContentSecurityPolicyHeader::new();
+
+        for directive in raw.first()?.value().split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let name = match tokens.next() {
+                Some(name) => name.to_lowercase(),
+                None => continue,
+            };
+
+            if header.directive(&name).is_none() {
+                header.directives.push((name, tokens.map(str::to_string).collect()));
+            }
+        }
+
+        Some(header)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+/// `nosniff`, which prevents browsers from guessing the media type.
+/// [Fetch Standard](https://fetch.spec.whatwg.org/#x-content-type-options-header)
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct XContentTypeOptionsHeader;
+
+impl TypedHeader for XContentTypeOptionsHeader {
+    fn name() -> &'static str {
+        "x-content-type-options"
+    }
+
+    fn canonical_name() -> &'static str {
+        "X-Content-Type-Options"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let values: CommaDelimited<String> = CommaDelimited::from_raw(raw).unwrap_or_default();
+
+        if values.items().first()?.eq_ignore_ascii_case("nosniff") {
+            Some(XContentTypeOptionsHeader)
+        } else {
+            None
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec!["nosniff".to_string()]
+    }
+}
+
+/// [RFC7034](http://www.iana.org/go/rfc7034)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FrameOptions {
+    Deny,
+    SameOrigin,
+}
+
+/// Controls whether the document may be displayed in a frame.
+/// The obsolete `ALLOW-FROM` is not supported,
+/// `frame-ancestors` of [`ContentSecurityPolicyHeader`] should be used instead.
+///
+/// [`ContentSecurityPolicyHeader`]: struct.ContentSecurityPolicyHeader.html
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct XFrameOptionsHeader {
+    value: FrameOptions,
+}
+
+impl XFrameOptionsHeader {
+    pub fn new(value: FrameOptions) -> Self {
+        XFrameOptionsHeader { value }
+    }
+
+    pub fn value(&self) -> FrameOptions {
+        self.value
+    }
+}
+
+impl TypedHeader for XFrameOptionsHeader {
+    fn name() -> &'static str {
+        "x-frame-options"
+    }
+
+    fn canonical_name() -> &'static str {
+        "X-Frame-Options"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let value = raw.first()?.value().trim();
+        let value = if value.eq_ignore_ascii_case("deny") {
+            FrameOptions::Deny
+        } else if value.eq_ignore_ascii_case("sameorigin") {
+            FrameOptions::SameOrigin
+        } else {
+            return None;
+        };
+
+        Some(XFrameOptionsHeader { value })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let value = match self.value {
+            FrameOptions::Deny => "DENY",
+            FrameOptions::SameOrigin => "SAMEORIGIN",
+        };
+
+        vec![value.to_string()]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidReferrerPolicy;
+
+/// [Referrer Policy](https://www.w3.org/TR/referrer-policy/#referrer-policies)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReferrerPolicy {
+    NoReferrer,
+    NoReferrerWhenDowngrade,
+    SameOrigin,
+    Origin,
+    StrictOrigin,
+    OriginWhenCrossOrigin,
+    StrictOriginWhenCrossOrigin,
+    UnsafeUrl,
+}
+
+impl ReferrerPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ReferrerPolicy::NoReferrer => "no-referrer",
+            ReferrerPolicy::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            ReferrerPolicy::SameOrigin => "same-origin",
+            ReferrerPolicy::Origin => "origin",
+            ReferrerPolicy::StrictOrigin => "strict-origin",
+            ReferrerPolicy::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            ReferrerPolicy::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            ReferrerPolicy::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+impl FromStr for ReferrerPolicy {
+    type Err = InvalidReferrerPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let policies = [ReferrerPolicy::NoReferrer,
+                        ReferrerPolicy::NoReferrerWhenDowngrade,
+                        ReferrerPolicy::SameOrigin,
+                        ReferrerPolicy::Origin,
+                        ReferrerPolicy::StrictOrigin,
+                        ReferrerPolicy::OriginWhenCrossOrigin,
+                        ReferrerPolicy::StrictOriginWhenCrossOrigin,
+                        ReferrerPolicy::UnsafeUrl];
+
+        policies.iter()
+            .cloned()
+            .find(|policy| policy.as_str().eq_ignore_ascii_case(s))
+            .ok_or(InvalidReferrerPolicy)
+    }
+}
+
+/// [Referrer Policy](https://www.w3.org/TR/referrer-policy/#referrer-policy-header)
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ReferrerPolicyHeader {
+    policy: ReferrerPolicy,
+}
+
+impl ReferrerPolicyHeader {
+    pub fn new(policy: ReferrerPolicy) -> Self {
+        ReferrerPolicyHeader { policy }
+    }
+
+    pub fn policy(&self) -> ReferrerPolicy {
+        self.policy
+    }
+}
+
+impl TypedHeader for ReferrerPolicyHeader {
+    fn name() -> &'static str {
+        "referrer-policy"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Referrer-Policy"
+    }
+
+    /// Unknown policies are ignored and the last known one is used,
+    /// which allows sending fallbacks for older browsers.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        CommaDelimited::from_raw_lossy(raw)
+            .into_items()
+            .pop()
+            .map(ReferrerPolicyHeader::new)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.policy.as_str().to_string()]
+    }
+}
+
+/// A member of a feature's allowlist in [`PermissionsPolicyHeader`].
+///
+/// [`PermissionsPolicyHeader`]: struct.PermissionsPolicyHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AllowlistMember {
+    /// `*`
+    Any,
+    /// `self`
+    SelfOrigin,
+    /// A serialized origin like `https://example.com`
+    Origin(String),
+}
+
+impl fmt::Display for AllowlistMember {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AllowlistMember::Any => write!(f, "*"),
+            AllowlistMember::SelfOrigin => write!(f, "self"),
+            AllowlistMember::Origin(ref origin) => write!(f, "{}", syntax::quote(origin)),
+        }
+    }
+}
+
+impl AllowlistMember {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "*" => Some(AllowlistMember::Any),
+            "self" => Some(AllowlistMember::SelfOrigin),
+            value if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') => {
+                Some(AllowlistMember::Origin(syntax::unquote(value)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Controls which origins may use browser features like the camera.
+/// An empty allowlist disables the feature entirely.
+/// [Permissions Policy](https://www.w3.org/TR/permissions-policy-1/#permissions-policy-http-header-field)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{AllowlistMember, PermissionsPolicyHeader};
+///
+/// let mut policy = PermissionsPolicyHeader::new();
+///
+/// policy.set_feature("camera", vec![]);
+/// policy.set_feature("geolocation",
+///                    vec![AllowlistMember::SelfOrigin,
+///                         AllowlistMember::Origin("https://maps.example".to_string())]);
+///
+/// assert_eq!("camera=(), geolocation=(self \"https://maps.example\")", policy.to_string());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PermissionsPolicyHeader {
+    features: Vec<(String, Vec<AllowlistMember>)>,
+}
+
+impl PermissionsPolicyHeader {
+    pub fn new() -> Self {
+        PermissionsPolicyHeader::default()
+    }
+
+    pub fn features(&self) -> &[(String, Vec<AllowlistMember>)] {
+        &self.features
+    }
+
+    pub fn allowlist(&self, feature: &str) -> Option<&[AllowlistMember]> {
+        self.features
+            .iter()
+            .find(|&(other, _)| other == feature)
+            .map(|(_, allowlist)| allowlist.as_slice())
+    }
+
+    /// Replaces the allowlist of `feature` or appends it if it does not exist yet.
+    pub fn set_feature<S: Into<String>>(&mut self, feature: S, allowlist: Vec<AllowlistMember>) {
+        let feature = feature.into();
+
+        match self.features.iter_mut().find(|(other, _)| *other == feature) {
+            Some(entry) => entry.1 = allowlist,
+            None => self.features.push((feature, allowlist)),
+        }
+    }
+}
+
+impl fmt::Display for PermissionsPolicyHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let features: Vec<String> = self.features
+            .iter()
+            .map(|(feature, allowlist)| match allowlist.as_slice() {
+                [member @ AllowlistMember::Any] | [member @ AllowlistMember::SelfOrigin] => {
+                    format!("{}={}", feature, member)
+                }
+                members => {
+                    let members: Vec<String> = members.iter().map(|m| m.to_string()).collect();
+
+                    format!("{}=({})", feature, members.join(" "))
+                }
+            })
+            .collect();
+
+        write!(f, "{}", features.join(", "))
+    }
+}
+
+impl TypedHeader for PermissionsPolicyHeader {
+    fn name() -> &'static str {
+        "permissions-policy"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Permissions-Policy"
+    }
+
+    /// The value is a structured field dictionary, later entries replace earlier ones.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let mut header = PermissionsPolicyHeader::new();
+        let entries: CommaDelimited<String> = CommaDelimited::from_raw(raw).unwrap_or_default();
+
+        for entry in &entries {
+            let (feature, value) = syntax::split_pair(entry);
+
+            if feature.is_empty() || !feature.chars().all(|c| c.is_ascii_lowercase() ||
+                                                       c.is_ascii_digit() ||
+                                                       "_-.*".contains(c)) {
+                return None;
+            }
+
+            let value = value?;
+            let allowlist = match value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+                Some(members) => {
+                    members.split_ascii_whitespace()
+                        .map(AllowlistMember::parse)
+                        .collect::<Option<Vec<_>>>()?
+                }
+                None => vec![AllowlistMember::parse(value)?],
+            };
+
+            header.set_feature(feature, allowlist);
+        }
+
+        Some(header)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+impl Headers {
+    /// Adds a hardened set of security headers, keeping any that are already present:
+    ///
+    /// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+    /// - `Content-Security-Policy: default-src 'self'; object-src 'none'; base-uri 'self'; frame-ancestors 'none'`
+    /// - `X-Content-Type-Options: nosniff`
+    /// - `X-Frame-Options: DENY`
+    /// - `Referrer-Policy: strict-origin-when-cross-origin`
+    /// - `Permissions-Policy: camera=(), geolocation=(), microphone=()`
+    pub fn append_security_defaults(&mut self) {
+        if !self.contains(StrictTransportSecurityHeader::name()) {
+            let mut hsts = StrictTransportSecurityHeader::new(31_536_000);
+
+            hsts.set_include_subdomains(true);
+            self.append(hsts);
+        }
+
+        if !self.contains(ContentSecurityPolicyHeader::name()) {
+            let mut csp = ContentSecurityPolicyHeader::new();
+
+            csp.set_directive("default-src", vec!["'self'".to_string()]);
+            csp.set_directive("object-src", vec!["'none'".to_string()]);
+            csp.set_directive("base-uri", vec!["'self'".to_string()]);
+            csp.set_directive("frame-ancestors", vec!["'none'".to_string()]);
+            self.append(csp);
+        }
+
+        if !self.contains(XContentTypeOptionsHeader::name()) {
+            self.append(XContentTypeOptionsHeader);
+        }
+
+        if !self.contains(XFrameOptionsHeader::name()) {
+            self.append(XFrameOptionsHeader::new(FrameOptions::Deny));
+        }
+
+        if !self.contains(ReferrerPolicyHeader::name()) {
+            self.append(ReferrerPolicyHeader::new(ReferrerPolicy::StrictOriginWhenCrossOrigin));
+        }
+
+        if !self.contains(PermissionsPolicyHeader::name()) {
+            let mut permissions = PermissionsPolicyHeader::new();
+
+            for feature in &["camera", "geolocation", "microphone"] {
+                permissions.set_feature(*feature, vec![]);
+            }
+
+            self.append(permissions);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip<H: TypedHeader>(header: &H) -> Option<H> {
+        let raw = header.to_raw();
+        let raw: Vec<&RawHeader> = raw.iter().collect();
+
+        H::parse(&raw)
+    }
+
+    fn parse<H: TypedHeader>(value: &str) -> Option<H> {
+        H::parse(&[&RawHeader::new(H::canonical_name(), value)])
+    }
+
+    #[test]
+    fn test_hsts() {
+        let hsts: StrictTransportSecurityHeader =
+            parse("max-age=\"31536000\"; includeSubDomains; PRELOAD; foo=bar").unwrap();
+
+        assert_eq!(31_536_000, hsts.max_age());
+        assert!(hsts.include_subdomains());
+        assert!(hsts.preload());
+        assert_eq!(vec!["max-age=31536000; includeSubDomains; preload".to_string()],
+                   hsts.raw_values());
+        assert_eq!(Some(hsts), round_trip(&hsts));
+        assert!(parse::<StrictTransportSecurityHeader>("includeSubDomains").is_none());
+        assert!(parse::<StrictTransportSecurityHeader>("max-age=1; max-age=2").is_none());
+    }
+
+    #[test]
+    fn test_csp() {
+        let csp: ContentSecurityPolicyHeader =
+            parse("Default-Src 'self' https://cdn.example;; img-src *; default-src 'none'")
+                .unwrap();
+
+        assert_eq!(Some(&["'self'".to_string(), "https://cdn.example".to_string()][..]),
+                   csp.directive("default-src"));
+        assert_eq!(Some(&["*".to_string()][..]), csp.directive("img-src"));
+        assert_eq!(2, csp.directives().len());
+        assert_eq!(Some(csp.clone()), round_trip(&csp));
+
+        let mut csp = csp;
+
+        csp.remove_directive("img-src");
+
+        assert_eq!("default-src 'self' https://cdn.example", csp.to_string());
+    }
+
+    #[test]
+    fn test_frame_and_content_type_options() {
+        assert_eq!(Some(XFrameOptionsHeader::new(FrameOptions::SameOrigin)),
+                   parse("sameorigin"));
+        assert!(parse::<XFrameOptionsHeader>("ALLOW-FROM https://example.com").is_none());
+        assert_eq!(Some(XContentTypeOptionsHeader), parse("NoSniff"));
+        assert!(parse::<XContentTypeOptionsHeader>("sniff").is_none());
+    }
+
+    #[test]
+    fn test_referrer_policy() {
+        assert_eq!(Some(ReferrerPolicyHeader::new(ReferrerPolicy::StrictOrigin)),
+                   parse("no-referrer, unknown-policy, strict-origin"));
+        assert_eq!(Some(ReferrerPolicyHeader::new(ReferrerPolicy::NoReferrer)),
+                   parse("no-referrer, unknown-policy"));
+        assert!(parse::<ReferrerPolicyHeader>("unknown").is_none());
+    }
+
+    #[test]
+    fn test_permissions_policy() {
+        let policy: PermissionsPolicyHeader =
+            parse("fullscreen=*, camera=(), geolocation=(self \"https://a.example\")").unwrap();
+
+        assert_eq!(Some(&[AllowlistMember::Any][..]), policy.allowlist("fullscreen"));
+        assert_eq!(Some(&[][..]), policy.allowlist("camera"));
+        assert_eq!(Some(&[AllowlistMember::SelfOrigin,
+                          AllowlistMember::Origin("https://a.example".to_string())][..]),
+                   policy.allowlist("geolocation"));
+        assert_eq!(Some(policy.clone()), round_trip(&policy));
+        assert!(parse::<PermissionsPolicyHeader>("Camera=()").is_none());
+        assert!(parse::<PermissionsPolicyHeader>("camera=(none)").is_none());
+    }
+
+    #[test]
+    fn test_security_defaults() {
+        let mut headers = Headers::new();
+
+        headers.append(XFrameOptionsHeader::new(FrameOptions::SameOrigin));
+        headers.append_security_defaults();
+
+        assert_eq!(6, headers.len());
+        assert_eq!(Some(XFrameOptionsHeader::new(FrameOptions::SameOrigin)),
+                   headers.get());
+        assert_eq!(Some(XContentTypeOptionsHeader), headers.get());
+        assert_eq!(Some(31_536_000),
+                   headers.get::<StrictTransportSecurityHeader>().map(|hsts| hsts.max_age()));
+        assert_eq!(Some(ReferrerPolicy::StrictOriginWhenCrossOrigin),
+                   headers.get::<ReferrerPolicyHeader>().map(|header| header.policy()));
+        assert_eq!(Some(&["'none'".to_string()][..]),
+                   headers.get::<ContentSecurityPolicyHeader>()
+                       .as_ref()
+                       .and_then(|csp| csp.directive("frame-ancestors")));
+        assert!(headers.contains("permissions-policy"));
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidMetric;
+
+/// A single metric of the `Server-Timing` header.
+/// [Server Timing, Section 3](https://www.w3.org/TR/server-timing/)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Metric {
+    name: String,
+    duration: Option<Duration>,
+    description: Option<String>,
+}
+
+impl Metric {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Metric {
+            name: name.into(),
+            duration: None,
+            description: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// The duration is sent in milliseconds with up to six decimal places.
+    pub fn set_duration(&mut self, duration: Option<Duration>) {
+        self.duration = duration;
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description;
+    }
+}
+
+impl FromStr for Metric {
+    type Err = InvalidMetric;
+
+    /// Only the first `dur` and `desc` parameters are used, others are ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = syntax::split_quoted(s, ';').into_iter();
+        let name = parts.next().ok_or(InvalidMetric)?;
+
+        if !syntax::is_token(name) {
+            return Err(InvalidMetric);
+        }
+
+        let mut metric = Metric::new(name);
+        let mut seen_duration = false;
+        let mut seen_description = false;
+
+        for param in parts {
+            match syntax::split_pair(param) {
+                (name, value) if name.eq_ignore_ascii_case("dur") && !seen_duration => {
+                    seen_duration = true;
+                    metric.duration = value.and_then(|value| {
+                        parse_milliseconds(&syntax::unquote(value))
+                    });
+                }
+                (name, value) if name.eq_ignore_ascii_case("desc") && !seen_description => {
+                    seen_description = true;
+                    metric.description = value.map(syntax::unquote);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(metric)
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if let Some(duration) = self.duration {
+            write!(f, ";dur={}", format_milliseconds(duration))?;
+        }
+
+        if let Some(ref description) = self.description {
+            write!(f, ";desc={}", syntax::quote_if_needed(description))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a non-negative decimal number of milliseconds.
+/// Digits beyond nanosecond precision are truncated.
+fn parse_milliseconds(value: &str) -> Option<Duration> {
+    let (integer, fraction) = match value.find('.') {
+        Some(index) => (&value[..index], &value[index + 1..]),
+        None => (value, ""),
+    };
+
+    if integer.is_empty() || !integer.bytes().all(|byte| byte.is_ascii_digit()) ||
+       !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let milliseconds: u64 = integer.parse().ok()?;
+    let nanos = fraction.bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(6)
+        .fold(0, |nanos, digit| nanos * 10 + u64::from(digit - b'0'));
+
+    Some(Duration::from_millis(milliseconds) + Duration::from_nanos(nanos))
+}
+
+fn format_milliseconds(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    let fraction = format!("{:06}", nanos % 1_000_000);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        format!("{}", nanos / 1_000_000)
+    } else {
+        format!("{}.{}", nanos / 1_000_000, fraction)
+    }
+}
+
+/// Performance metrics of the server for the request, shown in the
+/// developer tools of browsers. Metrics from multiple header lines are merged,
+/// so every part of the application can append its own line.
+/// [Server Timing, Section 3](https://www.w3.org/TR/server-timing/)
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use teapot::http::headers::{Headers, Metric, ServerTimingHeader};
+///
+/// let mut db = Metric::new("db");
+/// let mut headers = Headers::new();
+///
+/// db.set_duration(Some(Duration::from_micros(53_200)));
+/// db.set_description(Some("Query users".to_string()));
+/// headers.append(ServerTimingHeader::new(vec![db]));
+/// headers.append(ServerTimingHeader::new(vec![Metric::new("cache-miss")]));
+///
+/// let header: ServerTimingHeader = headers.get().unwrap();
+///
+/// assert_eq!("db;dur=53.2;desc=\"Query users\"", headers.get_raw("server-timing")[0].value());
+/// assert_eq!(Some(Duration::from_micros(53_200)), header.metric("db").unwrap().duration());
+/// assert_eq!(2, header.metrics().len());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ServerTimingHeader {
+    metrics: Vec<Metric>,
+}
+
+impl ServerTimingHeader {
+    pub fn new(metrics: Vec<Metric>) -> Self {
+        ServerTimingHeader { metrics }
+    }
+
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    pub fn push(&mut self, metric: Metric) {
+        self.metrics.push(metric);
+    }
+
+    /// Returns the first metric called `name`.
+    pub fn metric(&self, name: &str) -> Option<&Metric> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+}
+
+impl TypedHeader for ServerTimingHeader {
+    fn name() -> &'static str {
+        "server-timing"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Server-Timing"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let metrics = CommaDelimited::from_raw_lossy(raw).into_items();
+
+        Some(ServerTimingHeader { metrics })
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        let metrics: Vec<String> = self.metrics.iter().map(Metric::to_string).collect();
+
+        vec![metrics.join(", ")]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let raw = RawHeader::new("Server-Timing",
+                                 "miss, db;dur=53, app;dur=47.2;DUR=1;desc=\"a, b\", total;dur=x, \
+                                  no name");
+        let header = ServerTimingHeader::parse(&[&raw]).unwrap();
+        let metrics = header.metrics();
+
+        assert_eq!(4, metrics.len());
+        assert_eq!(None, metrics[0].duration());
+        assert_eq!(Some(Duration::from_millis(53)), metrics[1].duration());
+        assert_eq!(Some(Duration::from_micros(47_200)), metrics[2].duration());
+        assert_eq!(Some("a, b"), metrics[2].description());
+        assert_eq!(None, header.metric("total").unwrap().duration());
+        assert_eq!(vec!["miss, db;dur=53, app;dur=47.2;desc=\"a, b\", total".to_string()],
+                   header.raw_values());
+    }
+
+    #[test]
+    fn test_milliseconds() {
+        assert_eq!(Some(Duration::from_nanos(1_000_001)), parse_milliseconds("1.0000019"));
+        assert_eq!(Some(Duration::from_millis(7)), parse_milliseconds("7."));
+        assert_eq!(None, parse_milliseconds(".5"));
+        assert_eq!(None, parse_milliseconds("-1"));
+        assert_eq!("0.000001", format_milliseconds(Duration::from_nanos(1)));
+        assert_eq!("1500", format_milliseconds(Duration::from_millis(1500)));
+    }
+}
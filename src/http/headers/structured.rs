@@ -0,0 +1,1004 @@
+use std::fmt;
+use std::slice;
+use std::str::FromStr;
+use super::{TypedHeader, RawHeader};
+use super::syntax;
+use super::super::super::base64;
+
+const MAX_INTEGER: i64 = 999_999_999_999_999;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidStructuredField;
+
+/// A decimal with up to twelve integer and three fractional digits.
+/// [RFC8941, Section 3.3.2](http://www.iana.org/go/rfc8941)
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Decimal {
+    thousandths: i64,
+}
+
+impl Decimal {
+    /// Returns `None` if the integer part has more than twelve digits.
+    pub fn from_thousandths(thousandths: i64) -> Option<Self> {
+        if thousandths.abs() > MAX_INTEGER {
+            return None;
+        }
+
+        Some(Decimal { thousandths })
+    }
+
+    pub fn thousandths(&self) -> i64 {
+        self.thousandths
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let abs = self.thousandths.unsigned_abs();
+        let fraction = format!("{:03}", abs % 1000);
+        let fraction = match fraction.trim_end_matches('0') {
+            "" => "0",
+            fraction => fraction,
+        };
+
+        if self.thousandths < 0 {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}.{}", abs / 1000, fraction)
+    }
+}
+
+/// [RFC8941, Section 3.3](http://www.iana.org/go/rfc8941)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BareItem {
+    /// At most fifteen digits
+    Integer(i64),
+    Decimal(Decimal),
+    /// Printable ASCII only
+    String(String),
+    Token(String),
+    ByteSequence(Vec<u8>),
+    Boolean(bool),
+}
+
+impl BareItem {
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            BareItem::Integer(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match *self {
+            BareItem::Decimal(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_string(&self) -> Option<&str> {
+        match *self {
+            BareItem::String(ref value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_token(&self) -> Option<&str> {
+        match *self {
+            BareItem::Token(ref value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte_sequence(&self) -> Option<&[u8]> {
+        match *self {
+            BareItem::ByteSequence(ref value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_boolean(&self) -> Option<bool> {
+        match *self {
+            BareItem::Boolean(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters of an item or inner list, in order of their first occurrence.
+/// [RFC8941, Section 3.1.2](http://www.iana.org/go/rfc8941)
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Parameters {
+    params: Vec<(String, BareItem)>,
+}
+
+impl Parameters {
+    pub fn new() -> Self {
+        Parameters { params: vec![] }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&BareItem> {
+        self.params
+            .iter()
+            .find(|&(other, _)| other == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Overwrites the value of an existing parameter in place.
+    pub fn insert<S: Into<String>>(&mut self, key: S, value: BareItem) {
+        let key = key.into();
+
+        match self.params.iter_mut().find(|(other, _)| *other == key) {
+            Some(param) => param.1 = value,
+            None => self.params.push((key, value)),
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, (String, BareItem)> {
+        self.params.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+/// [RFC8941, Section 3.3](http://www.iana.org/go/rfc8941)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Item {
+    bare_item: BareItem,
+    params: Parameters,
+}
+
+impl Item {
+    pub fn new(bare_item: BareItem) -> Self {
+        Item {
+            bare_item,
+            params: Parameters::new(),
+        }
+    }
+
+    pub fn bare_item(&self) -> &BareItem {
+        &self.bare_item
+    }
+
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    pub fn params_mut(&mut self) -> &mut Parameters {
+        &mut self.params
+    }
+}
+
+/// [RFC8941, Section 3.1.1](http://www.iana.org/go/rfc8941)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct InnerList {
+    items: Vec<Item>,
+    params: Parameters,
+}
+
+impl InnerList {
+    pub fn new(items: Vec<Item>) -> Self {
+        InnerList {
+            items,
+            params: Parameters::new(),
+        }
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    pub fn params_mut(&mut self) -> &mut Parameters {
+        &mut self.params
+    }
+}
+
+/// A member of a [`List`] or [`Dictionary`].
+///
+/// [`List`]: struct.List.html
+/// [`Dictionary`]: struct.Dictionary.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Member {
+    Item(Item),
+    InnerList(InnerList),
+}
+
+/// [RFC8941, Section 3.1](http://www.iana.org/go/rfc8941)
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct List {
+    members: Vec<Member>,
+}
+
+impl List {
+    pub fn new(members: Vec<Member>) -> Self {
+        List { members }
+    }
+
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    pub fn push(&mut self, member: Member) {
+        self.members.push(member);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// An ordered map with unique keys.
+/// [RFC8941, Section 3.2](http://www.iana.org/go/rfc8941)
+///
+/// Members without a value are items with the value `true`.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Dictionary {
+    members: Vec<(String, Member)>,
+}
+
+impl Dictionary {
+    pub fn new() -> Self {
+        Dictionary { members: vec![] }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Member> {
+        self.members
+            .iter()
+            .find(|&(other, _)| other == key)
+            .map(|(_, member)| member)
+    }
+
+    /// Overwrites the value of an existing member in place.
+    pub fn insert<S: Into<String>>(&mut self, key: S, member: Member) {
+        let key = key.into();
+
+        match self.members.iter_mut().find(|(other, _)| *other == key) {
+            Some(entry) => entry.1 = member,
+            None => self.members.push((key, member)),
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, (String, Member)> {
+        self.members.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// One of the three top-level types: [`List`], [`Dictionary`] or [`Item`].
+///
+/// [`List`]: struct.List.html
+/// [`Dictionary`]: struct.Dictionary.html
+/// [`Item`]: struct.Item.html
+pub trait StructuredValue: FromStr<Err = InvalidStructuredField> {
+    /// Fails if a value can't be represented, e.g. a token containing spaces
+    /// or an integer with more than fifteen digits.
+    fn serialize(&self) -> Result<String, InvalidStructuredField>;
+}
+
+impl FromStr for List {
+    type Err = InvalidStructuredField;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::new(s).parse_field(Parser::list)
+    }
+}
+
+impl StructuredValue for List {
+    fn serialize(&self) -> Result<String, InvalidStructuredField> {
+        let mut output = String::new();
+
+        for (index, member) in self.members.iter().enumerate() {
+            if index > 0 {
+                output.push_str(", ");
+            }
+
+            write_member(&mut output, member)?;
+        }
+
+        Ok(output)
+    }
+}
+
+impl FromStr for Dictionary {
+    type Err = InvalidStructuredField;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::new(s).parse_field(Parser::dictionary)
+    }
+}
+
+impl StructuredValue for Dictionary {
+    fn serialize(&self) -> Result<String, InvalidStructuredField> {
+        let mut output = String::new();
+
+        for (index, (key, member)) in self.members.iter().enumerate() {
+            if index > 0 {
+                output.push_str(", ");
+            }
+
+            write_key(&mut output, key)?;
+
+            match *member {
+                Member::Item(ref item) if item.bare_item == BareItem::Boolean(true) => {
+                    write_params(&mut output, &item.params)?;
+                }
+                ref member => {
+                    output.push('=');
+                    write_member(&mut output, member)?;
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+impl FromStr for Item {
+    type Err = InvalidStructuredField;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::new(s).parse_field(Parser::item)
+    }
+}
+
+impl StructuredValue for Item {
+    fn serialize(&self) -> Result<String, InvalidStructuredField> {
+        let mut output = String::new();
+
+        write_item(&mut output, self)?;
+
+        Ok(output)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { input, position: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+
+        self.position += 1;
+
+        Some(byte)
+    }
+
+    fn eat(&mut self, expected: u8) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), InvalidStructuredField> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(InvalidStructuredField)
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn take_while<F: Fn(u8) -> bool>(&mut self, predicate: F) -> &'a str {
+        let start = self.position;
+
+        while self.peek().is_some_and(&predicate) {
+            self.position += 1;
+        }
+
+        &self.input[start..self.position]
+    }
+
+    fn skip_sp(&mut self) {
+        self.take_while(|byte| byte == b' ');
+    }
+
+    fn skip_ows(&mut self) {
+        self.take_while(|byte| byte == b' ' || byte == b'\t');
+    }
+
+    /// Surrounding spaces are allowed, any other trailing characters are not.
+    fn parse_field<T, F>(mut self, parse: F) -> Result<T, InvalidStructuredField>
+        where F: FnOnce(&mut Self) -> Result<T, InvalidStructuredField>
+    {
+        self.skip_sp();
+
+        let value = parse(&mut self)?;
+
+        self.skip_sp();
+
+        if !self.is_empty() {
+            return Err(InvalidStructuredField);
+        }
+
+        Ok(value)
+    }
+
+    /// Consumes the comma between members. Returns `false` at the end of the input.
+    fn member_separator(&mut self) -> Result<bool, InvalidStructuredField> {
+        self.skip_ows();
+
+        if self.is_empty() {
+            return Ok(false);
+        }
+
+        self.expect(b',')?;
+        self.skip_ows();
+
+        // trailing commas are not allowed
+        if self.is_empty() {
+            return Err(InvalidStructuredField);
+        }
+
+        Ok(true)
+    }
+
+    fn list(&mut self) -> Result<List, InvalidStructuredField> {
+        let mut list = List::default();
+
+        if self.is_empty() {
+            return Ok(list);
+        }
+
+        loop {
+            list.push(self.member()?);
+
+            if !self.member_separator()? {
+                return Ok(list);
+            }
+        }
+    }
+
+    fn dictionary(&mut self) -> Result<Dictionary, InvalidStructuredField> {
+        let mut dictionary = Dictionary::new();
+
+        if self.is_empty() {
+            return Ok(dictionary);
+        }
+
+        loop {
+            let key = self.key()?;
+            let member = if self.eat(b'=') {
+                self.member()?
+            } else {
+                Member::Item(Item {
+                    bare_item: BareItem::Boolean(true),
+                    params: self.params()?,
+                })
+            };
+
+            dictionary.insert(key, member);
+
+            if !self.member_separator()? {
+                return Ok(dictionary);
+            }
+        }
+    }
+
+    fn member(&mut self) -> Result<Member, InvalidStructuredField> {
+        if self.peek() == Some(b'(') {
+            self.inner_list().map(Member::InnerList)
+        } else {
+            self.item().map(Member::Item)
+        }
+    }
+
+    fn inner_list(&mut self) -> Result<InnerList, InvalidStructuredField> {
+        let mut items = vec![];
+
+        self.expect(b'(')?;
+
+        loop {
+            self.skip_sp();
+
+            if self.eat(b')') {
+                return Ok(InnerList {
+                    items,
+                    params: self.params()?,
+                });
+            }
+
+            items.push(self.item()?);
+
+            match self.peek() {
+                Some(b' ') | Some(b')') => {}
+                _ => return Err(InvalidStructuredField),
+            }
+        }
+    }
+
+    fn item(&mut self) -> Result<Item, InvalidStructuredField> {
+        Ok(Item {
+            bare_item: self.bare_item()?,
+            params: self.params()?,
+        })
+    }
+
+    fn params(&mut self) -> Result<Parameters, InvalidStructuredField> {
+        let mut params = Parameters::new();
+
+        while self.eat(b';') {
+            self.skip_sp();
+
+            let key = self.key()?;
+            let value = if self.eat(b'=') {
+                self.bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+
+            params.insert(key, value);
+        }
+
+        Ok(params)
+    }
+
+    fn key(&mut self) -> Result<&'a str, InvalidStructuredField> {
+        match self.peek() {
+            Some(b'*') | Some(b'a'..=b'z') => Ok(self.take_while(is_key_char)),
+            _ => Err(InvalidStructuredField),
+        }
+    }
+
+    fn bare_item(&mut self) -> Result<BareItem, InvalidStructuredField> {
+        match self.peek() {
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(b'"') => self.string(),
+            Some(b'*') | Some(b'a'..=b'z') | Some(b'A'..=b'Z') => {
+                let token = self.take_while(is_token_char);
+
+                Ok(BareItem::Token(token.to_string()))
+            }
+            Some(b':') => self.byte_sequence(),
+            Some(b'?') => self.boolean(),
+            _ => Err(InvalidStructuredField),
+        }
+    }
+
+    fn number(&mut self) -> Result<BareItem, InvalidStructuredField> {
+        let negative = self.eat(b'-');
+        let start = self.position;
+        let mut decimal_point = None;
+
+        if !self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            return Err(InvalidStructuredField);
+        }
+
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'0'..=b'9' => {}
+                b'.' if decimal_point.is_none() => {
+                    if self.position - start > 12 {
+                        return Err(InvalidStructuredField);
+                    }
+
+                    decimal_point = Some(self.position);
+                }
+                _ => break,
+            }
+
+            self.position += 1;
+
+            let length = self.position - start;
+
+            if length > 16 || (decimal_point.is_none() && length > 15) {
+                return Err(InvalidStructuredField);
+            }
+        }
+
+        let sign = if negative { -1 } else { 1 };
+        let parse = |digits: &str| digits.parse::<i64>().map_err(|_| InvalidStructuredField);
+
+        match decimal_point {
+            None => Ok(BareItem::Integer(sign * parse(&self.input[start..self.position])?)),
+            Some(point) => {
+                let fraction = &self.input[point + 1..self.position];
+
+                if fraction.is_empty() || fraction.len() > 3 {
+                    return Err(InvalidStructuredField);
+                }
+
+                let thousandths = parse(&self.input[start..point])? * 1000 +
+                                  parse(&format!("{:0<3}", fraction))?;
+
+                Ok(BareItem::Decimal(Decimal { thousandths: sign * thousandths }))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<BareItem, InvalidStructuredField> {
+        let mut value = String::new();
+
+        self.expect(b'"')?;
+
+        loop {
+            match self.next() {
+                Some(b'"') => return Ok(BareItem::String(value)),
+                Some(b'\\') => {
+                    match self.next() {
+                        Some(byte @ b'"') | Some(byte @ b'\\') => value.push(byte as char),
+                        _ => return Err(InvalidStructuredField),
+                    }
+                }
+                Some(byte @ 0x20..=0x7e) => value.push(byte as char),
+                _ => return Err(InvalidStructuredField),
+            }
+        }
+    }
+
+    fn byte_sequence(&mut self) -> Result<BareItem, InvalidStructuredField> {
+        self.expect(b':')?;
+
+        let encoded = self.take_while(|byte| byte != b':');
+
+        self.expect(b':')?;
+
+        base64::decode(encoded)
+            .map(BareItem::ByteSequence)
+            .ok_or(InvalidStructuredField)
+    }
+
+    fn boolean(&mut self) -> Result<BareItem, InvalidStructuredField> {
+        self.expect(b'?')?;
+
+        match self.next() {
+            Some(b'1') => Ok(BareItem::Boolean(true)),
+            Some(b'0') => Ok(BareItem::Boolean(false)),
+            _ => Err(InvalidStructuredField),
+        }
+    }
+}
+
+fn is_key_char(byte: u8) -> bool {
+    matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'*')
+}
+
+fn is_token_char(byte: u8) -> bool {
+    syntax::is_tchar(byte as char) || byte == b':' || byte == b'/'
+}
+
+fn write_key(output: &mut String, key: &str) -> Result<(), InvalidStructuredField> {
+    match key.bytes().next() {
+        Some(b'*') | Some(b'a'..=b'z') if key.bytes().all(is_key_char) => {
+            output.push_str(key);
+            Ok(())
+        }
+        _ => Err(InvalidStructuredField),
+    }
+}
+
+fn write_bare_item(output: &mut String,
+                   bare_item: &BareItem)
+                   -> Result<(), InvalidStructuredField> {
+    match *bare_item {
+        BareItem::Integer(value) if value.abs() <= MAX_INTEGER => {
+            output.push_str(&value.to_string());
+        }
+        BareItem::Integer(_) => return Err(InvalidStructuredField),
+        BareItem::Decimal(value) => output.push_str(&value.to_string()),
+        BareItem::String(ref value) => {
+            output.push('"');
+
+            for c in value.chars() {
+                match c {
+                    '"' | '\\' => {
+                        output.push('\\');
+                        output.push(c);
+                    }
+                    ' '..='~' => output.push(c),
+                    _ => return Err(InvalidStructuredField),
+                }
+            }
+
+            output.push('"');
+        }
+        BareItem::Token(ref value) => {
+            match value.bytes().next() {
+                Some(b'*') | Some(b'a'..=b'z') | Some(b'A'..=b'Z')
+                    if value.bytes().all(is_token_char) => output.push_str(value),
+                _ => return Err(InvalidStructuredField),
+            }
+        }
+        BareItem::ByteSequence(ref value) => {
+            output.push(':');
+            output.push_str(&base64::encode(value));
+            output.push(':');
+        }
+        BareItem::Boolean(value) => output.push_str(if value { "?1" } else { "?0" }),
+    }
+
+    Ok(())
+}
+
+fn write_params(output: &mut String, params: &Parameters) -> Result<(), InvalidStructuredField> {
+    for (key, value) in params.iter() {
+        output.push(';');
+        write_key(output, key)?;
+
+        if *value != BareItem::Boolean(true) {
+            output.push('=');
+            write_bare_item(output, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_item(output: &mut String, item: &Item) -> Result<(), InvalidStructuredField> {
+    write_bare_item(output, &item.bare_item)?;
+    write_params(output, &item.params)
+}
+
+fn write_member(output: &mut String, member: &Member) -> Result<(), InvalidStructuredField> {
+    match *member {
+        Member::Item(ref item) => write_item(output, item),
+        Member::InnerList(ref inner_list) => {
+            output.push('(');
+
+            for (index, item) in inner_list.items.iter().enumerate() {
+                if index > 0 {
+                    output.push(' ');
+                }
+
+                write_item(output, item)?;
+            }
+
+            output.push(')');
+            write_params(output, &inner_list.params)
+        }
+    }
+}
+
+/// A header whose value is a structured field, used through [`StructuredHeader`].
+///
+/// [`StructuredHeader`]: struct.StructuredHeader.html
+pub trait StructuredField: Eq + Sized {
+    /// [`List`], [`Dictionary`] or [`Item`]
+    ///
+    /// [`List`]: struct.List.html
+    /// [`Dictionary`]: struct.Dictionary.html
+    /// [`Item`]: struct.Item.html
+    type Value: StructuredValue;
+
+    /// The name of the header in lower case.
+    fn name() -> &'static str;
+
+    fn canonical_name() -> &'static str;
+
+    /// Converts the parsed value, returns `None` if it is not valid for this header.
+    fn from_value(value: Self::Value) -> Option<Self>;
+
+    fn to_value(&self) -> Self::Value;
+}
+
+/// Adapts a [`StructuredField`] to [`TypedHeader`].
+/// Multiple header lines are combined before parsing,
+/// a value that can't be serialized or is empty is omitted.
+///
+/// [`StructuredField`]: trait.StructuredField.html
+/// [`TypedHeader`]: trait.TypedHeader.html
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{BareItem, Dictionary, Item, Member, RawHeader, StructuredField,
+///                             StructuredHeader, TypedHeader};
+///
+/// #[derive(PartialEq, Eq, Debug)]
+/// struct Priority {
+///     urgency: i64,
+///     incremental: bool,
+/// }
+///
+/// impl StructuredField for Priority {
+///     type Value = Dictionary;
+///
+///     fn name() -> &'static str {
+///         "priority"
+///     }
+///
+///     fn canonical_name() -> &'static str {
+///         "Priority"
+///     }
+///
+///     fn from_value(value: Dictionary) -> Option<Self> {
+///         let get = |key| match value.get(key) {
+///             Some(&Member::Item(ref item)) => Some(item.bare_item().clone()),
+///             _ => None,
+///         };
+///
+///         Some(Priority {
+///             urgency: get("u").and_then(|u| u.as_integer()).unwrap_or(3),
+///             incremental: get("i").and_then(|i| i.as_boolean()).unwrap_or(false),
+///         })
+///     }
+///
+///     fn to_value(&self) -> Dictionary {
+///         let mut value = Dictionary::new();
+///
+///         value.insert("u", Member::Item(Item::new(BareItem::Integer(self.urgency))));
+///         value.insert("i", Member::Item(Item::new(BareItem::Boolean(self.incremental))));
+///         value
+///     }
+/// }
+///
+/// let raw = RawHeader::new("Priority", "u=5, i");
+/// let header = StructuredHeader::<Priority>::parse(&[&raw]).unwrap();
+///
+/// assert_eq!(&Priority { urgency: 5, incremental: true }, header.field());
+/// assert_eq!(vec!["u=5, i".to_string()], header.raw_values());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StructuredHeader<T> {
+    field: T,
+}
+
+impl<T> StructuredHeader<T> {
+    pub fn new(field: T) -> Self {
+        StructuredHeader { field }
+    }
+
+    pub fn field(&self) -> &T {
+        &self.field
+    }
+
+    pub fn into_field(self) -> T {
+        self.field
+    }
+}
+
+impl<T: StructuredField> TypedHeader for StructuredHeader<T> {
+    fn name() -> &'static str {
+        T::name()
+    }
+
+    fn canonical_name() -> &'static str {
+        T::canonical_name()
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+
+        let values: Vec<&str> = raw.iter().map(|raw| raw.value()).collect();
+        let value = values.join(", ").parse().ok()?;
+
+        T::from_value(value).map(StructuredHeader::new)
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        match self.field.to_value().serialize() {
+            Ok(value) if !value.is_empty() => vec![value],
+            _ => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_structure() {
+        let dictionary: Dictionary = "a=(1 \"b\");x, c=:AQI=:, d".parse().unwrap();
+
+        match dictionary.get("a") {
+            Some(Member::InnerList(inner_list)) => {
+                assert_eq!(Some(1), inner_list.items()[0].bare_item().as_integer());
+                assert_eq!(Some("b"), inner_list.items()[1].bare_item().as_string());
+                assert_eq!(Some(&BareItem::Boolean(true)), inner_list.params().get("x"));
+            }
+            member => panic!("unexpected member {:?}", member),
+        }
+
+        assert_eq!(Some(&Member::Item(Item::new(BareItem::ByteSequence(vec![1, 2])))),
+                   dictionary.get("c"));
+        assert_eq!(Some(&Member::Item(Item::new(BareItem::Boolean(true)))),
+                   dictionary.get("d"));
+        assert_eq!(Some(Decimal::from_thousandths(-1500).unwrap()),
+                   "-1.5".parse::<Item>().unwrap().bare_item().as_decimal());
+    }
+
+    #[test]
+    fn test_serialize_invalid() {
+        let serialize = |bare_item| Item::new(bare_item).serialize();
+
+        assert!(serialize(BareItem::Integer(1_000_000_000_000_000)).is_err());
+        assert!(serialize(BareItem::String("é".to_string())).is_err());
+        assert!(serialize(BareItem::String("\n".to_string())).is_err());
+        assert!(serialize(BareItem::Token("a b".to_string())).is_err());
+        assert!(serialize(BareItem::Token("1a".to_string())).is_err());
+        assert!(serialize(BareItem::Token(String::new())).is_err());
+        assert_eq!(None, Decimal::from_thousandths(1_000_000_000_000_000));
+
+        let mut item = Item::new(BareItem::Integer(1));
+
+        item.params_mut().insert("A", BareItem::Boolean(true));
+
+        assert!(item.serialize().is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut inner_list = InnerList::new(vec![Item::new(BareItem::Token("a".to_string())),
+                                                 Item::new(BareItem::Boolean(false))]);
+
+        inner_list.params_mut().insert("q", BareItem::Decimal(Decimal::from_thousandths(500)
+                                                                  .unwrap()));
+
+        let list = List::new(vec![Member::InnerList(inner_list),
+                                  Member::Item(Item::new(BareItem::ByteSequence(b"hello"
+                                                                                    .to_vec())))]);
+
+        assert_eq!(Ok("(a ?0);q=0.5, :aGVsbG8=:".to_string()), list.serialize());
+    }
+
+    #[test]
+    fn test_multiple_lines() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct Numbers(List);
+
+        impl StructuredField for Numbers {
+            type Value = List;
+
+            fn name() -> &'static str {
+                "x-numbers"
+            }
+
+            fn canonical_name() -> &'static str {
+                "X-Numbers"
+            }
+
+            fn from_value(value: List) -> Option<Self> {
+                Some(Numbers(value))
+            }
+
+            fn to_value(&self) -> List {
+                self.0.clone()
+            }
+        }
+
+        let first = RawHeader::new("X-Numbers", "1, 2");
+        let second = RawHeader::new("X-Numbers", "3");
+        let header = StructuredHeader::<Numbers>::parse(&[&first, &second]).unwrap();
+
+        assert_eq!(vec!["1, 2, 3".to_string()], header.raw_values());
+        assert!(StructuredHeader::<Numbers>::parse(&[&RawHeader::new("X-Numbers", "1,")])
+            .is_none());
+        assert!(StructuredHeader::new(Numbers(List::default())).raw_values().is_empty());
+    }
+}
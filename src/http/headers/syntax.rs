@@ -0,0 +1,229 @@
+//! Helpers for the common header value grammar.
+//! [RFC7230, Section 3.2.6](http://www.iana.org/go/rfc7230)
+
+/// Checks whether `c` is a `tchar`.
+pub fn is_tchar(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+pub fn is_token(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(is_tchar)
+}
+
+/// Splits `value` at every `delimiter` that is not inside a quoted string
+/// and trims the surrounding whitespace of each part.
+/// Empty parts are skipped.
+pub fn split_quoted(value: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if in_quotes && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            in_quotes = !in_quotes;
+        } else if !in_quotes && c == delimiter {
+            parts.push(&value[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+
+    parts.push(&value[start..]);
+
+    parts.into_iter()
+        .map(|part| part.trim_matches(|c| c == ' ' || c == '\t'))
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Splits a comma separated list (`#element`).
+pub fn split_list(value: &str) -> Vec<&str> {
+    split_quoted(value, ',')
+}
+
+/// Splits `name=value` into its trimmed parts.
+pub fn split_pair(value: &str) -> (&str, Option<&str>) {
+    match value.find('=') {
+        Some(index) => (value[..index].trim(), Some(value[index + 1..].trim())),
+        None => (value.trim(), None),
+    }
+}
+
+/// Removes the quotes and escapes of a quoted string.
+/// Values that are not quoted are returned as is.
+pub fn unquote(value: &str) -> String {
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return value.to_string();
+    }
+
+    let mut result = String::with_capacity(value.len() - 2);
+    let mut escaped = false;
+
+    for c in value[1..value.len() - 1].chars() {
+        if !escaped && c == '\\' {
+            escaped = true;
+        } else {
+            result.push(c);
+            escaped = false;
+        }
+    }
+
+    result
+}
+
+/// Checks whether `value` can be written as a quoted string,
+/// which can't contain control characters other than tab.
+pub fn is_quotable(value: &str) -> bool {
+    !value.chars().any(|c| c.is_ascii_control() && c != '\t')
+}
+
+/// Formats `value` as a quoted string.
+/// Values that are not [`is_quotable`] must be rejected before.
+///
+/// [`is_quotable`]: fn.is_quotable.html
+pub fn quote(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+
+    result.push('"');
+
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            result.push('\\');
+        }
+
+        result.push(c);
+    }
+
+    result.push('"');
+    result
+}
+
+/// Formats `value` as a token if possible and as a quoted string otherwise.
+pub fn quote_if_needed(value: &str) -> String {
+    if is_token(value) {
+        value.to_string()
+    } else {
+        quote(value)
+    }
+}
+
+/// Parses `delta-seconds`, saturating at 2^31 as recommended by
+/// [RFC7234, Section 1.2.1](http://www.iana.org/go/rfc7234).
+pub fn parse_delta_seconds(value: &str) -> Option<u64> {
+    const MAX: u64 = 2_147_483_648;
+
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(value.parse().map(|seconds: u64| seconds.min(MAX)).unwrap_or(MAX))
+}
+
+fn is_attr_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte)
+}
+
+/// Decodes an extended parameter value like `UTF-8'en'%e2%82%ac%20rates`.
+/// Only the `UTF-8` and `ISO-8859-1` charsets are supported, the language is ignored.
+/// [RFC8187, Section 3.2](http://www.iana.org/go/rfc8187)
+pub fn decode_ext_value(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let encoded = parts.next()?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+
+    while index < encoded.len() {
+        match encoded[index] {
+            b'%' => {
+                let hex = encoded.get(index + 1..index + 3)?;
+                let hex = ::std::str::from_utf8(hex).ok()?;
+
+                if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return None;
+                }
+
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            }
+            byte if is_attr_char(byte) => {
+                bytes.push(byte);
+                index += 1;
+            }
+            _ => return None,
+        }
+    }
+
+    if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes).ok()
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+        Some(bytes.into_iter().map(char::from).collect())
+    } else {
+        None
+    }
+}
+
+/// Encodes `value` as an extended parameter value using `UTF-8`.
+/// [RFC8187, Section 3.2](http://www.iana.org/go/rfc8187)
+pub fn encode_ext_value(value: &str) -> String {
+    let mut result = String::from("UTF-8''");
+
+    for byte in value.bytes() {
+        if is_attr_char(byte) {
+            result.push(char::from(byte));
+        } else {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(vec!["a", "b=\"c, d\"", "e"], split_list(" a,, b=\"c, d\" ,e, "));
+        assert_eq!(vec!["\"a\\\", b\""], split_list("\"a\\\", b\""));
+        assert!(split_list(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!("\"a \\\"b\\\"\"", quote("a \"b\""));
+        assert_eq!("a \"b\"", unquote(&quote("a \"b\"")));
+        assert!(is_quotable("a\tb"));
+        assert!(!is_quotable("a\r\nX: y"));
+        assert!(!is_quotable("a\x7f"));
+        assert_eq!("token", quote_if_needed("token"));
+        assert_eq!("\"\"", quote_if_needed(""));
+    }
+
+    #[test]
+    fn test_delta_seconds() {
+        assert_eq!(Some(60), parse_delta_seconds("60"));
+        assert_eq!(Some(2_147_483_648), parse_delta_seconds("99999999999999999999999"));
+        assert_eq!(None, parse_delta_seconds("-1"));
+    }
+
+    #[test]
+    fn test_ext_value() {
+        assert_eq!(Some("€ rates".to_string()),
+                   decode_ext_value("utf-8'en'%e2%82%ac%20rates"));
+        assert_eq!(Some("£ rates".to_string()),
+                   decode_ext_value("iso-8859-1'en'%A3%20rates"));
+        assert_eq!(None, decode_ext_value("UTF-8''a b"));
+        assert_eq!(None, decode_ext_value("UTF-8''%e2%82"));
+        assert_eq!(None, decode_ext_value("UTF-8''%g0"));
+        assert_eq!(None, decode_ext_value("US-ASCII''abc"));
+        assert_eq!(None, decode_ext_value("abc"));
+        assert_eq!("UTF-8''%E2%82%AC%20rates", encode_ext_value("€ rates"));
+    }
+}
@@ -0,0 +1,60 @@
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+/// The fields the sender will send in the trailer section of a chunked message.
+/// [RFC7230, Section 4.4](http://www.iana.org/go/rfc7230)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{RawHeader, TrailerHeader, TypedHeader};
+///
+/// let trailer = TrailerHeader::parse(&[&RawHeader::new("Trailer", "Digest, grpc-status")])
+///     .unwrap();
+///
+/// assert!(trailer.contains("Grpc-Status"));
+/// assert_eq!(None, TrailerHeader::parse(&[&RawHeader::new("Trailer", "")]));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TrailerHeader {
+    names: CommaDelimited<String>,
+}
+
+impl TrailerHeader {
+    pub fn new(names: Vec<String>) -> Self {
+        TrailerHeader { names: CommaDelimited::new(names) }
+    }
+
+    pub fn names(&self) -> &[String] {
+        self.names.items()
+    }
+
+    /// Checks whether `name` is declared, ignoring case.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|other| other.eq_ignore_ascii_case(name))
+    }
+}
+
+impl TypedHeader for TrailerHeader {
+    fn name() -> &'static str {
+        "trailer"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Trailer"
+    }
+
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let names: CommaDelimited<String> = CommaDelimited::from_raw(raw).ok()?;
+
+        if !names.is_empty() && names.iter().all(|name| syntax::is_token(name)) {
+            Some(TrailerHeader { names })
+        } else {
+            None
+        }
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.names.to_string()]
+    }
+}
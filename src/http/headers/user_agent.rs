@@ -0,0 +1,264 @@
+use std::fmt;
+use super::{TypedHeader, RawHeader};
+
+/// A product identifier like `Firefox/121.0`.
+/// [RFC7231, Section 5.5.3](http://www.iana.org/go/rfc7231)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Product {
+    name: String,
+    version: Option<String>,
+}
+
+impl Product {
+    pub fn new<S: Into<String>>(name: S, version: Option<S>) -> Self {
+        Product {
+            name: name.into(),
+            version: version.map(Into::into),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    fn parse(value: &str) -> Self {
+        match value.find('/') {
+            Some(index) => Product::new(&value[..index], Some(&value[index + 1..])),
+            None => Product::new(value, None),
+        }
+    }
+}
+
+impl fmt::Display for Product {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.version {
+            Some(ref version) => write!(f, "{}/{}", self.name, version),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// An element of [`UserAgentHeader`] or [`ServerHeader`].
+///
+/// [`UserAgentHeader`]: struct.UserAgentHeader.html
+/// [`ServerHeader`]: struct.ServerHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProductToken {
+    Product(Product),
+    /// The text between the parentheses, nested comments and
+    /// escapes are kept as they are.
+    Comment(String),
+}
+
+impl fmt::Display for ProductToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProductToken::Product(ref product) => write!(f, "{}", product),
+            ProductToken::Comment(ref comment) => write!(f, "({})", comment),
+        }
+    }
+}
+
+/// Splits `value` into products and comments.
+/// Real world values don't always follow the grammar,
+/// so this never fails and an unterminated comment extends to the end.
+fn parse_tokens(value: &str) -> Vec<ProductToken> {
+    let mut tokens = vec![];
+    let mut rest = value.trim_start();
+
+    while !rest.is_empty() {
+        let end = if rest.starts_with('(') {
+            let end = comment_end(rest);
+            let comment = rest[1..end].strip_suffix(')').unwrap_or(&rest[1..end]);
+
+            tokens.push(ProductToken::Comment(comment.to_string()));
+            end
+        } else {
+            let end = rest.find([' ', '\t', '(']).unwrap_or(rest.len());
+
+            tokens.push(ProductToken::Product(Product::parse(&rest[..end])));
+            end
+        };
+
+        rest = rest[end..].trim_start();
+    }
+
+    tokens
+}
+
+/// Returns the index after the parenthesis closing the comment at the start of `value`.
+fn comment_end(value: &str) -> usize {
+    let mut depth = 0usize;
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '(' {
+            depth += 1;
+        } else if c == ')' {
+            depth -= 1;
+
+            if depth == 0 {
+                return index + 1;
+            }
+        }
+    }
+
+    value.len()
+}
+
+macro_rules! product_header {
+    ($(#[$attr:meta])* $header:ident, $name:expr, $canonical_name:expr) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $header {
+            value: String,
+            tokens: Vec<ProductToken>,
+        }
+
+        impl $header {
+            pub fn new(tokens: Vec<ProductToken>) -> Self {
+                let value: Vec<String> = tokens.iter().map(ProductToken::to_string).collect();
+
+                $header {
+                    value: value.join(" "),
+                    tokens,
+                }
+            }
+
+            /// The value as it was received.
+            pub fn value(&self) -> &str {
+                &self.value
+            }
+
+            pub fn tokens(&self) -> &[ProductToken] {
+                &self.tokens
+            }
+
+            pub fn products(&self) -> impl Iterator<Item = &Product> {
+                self.tokens.iter().filter_map(|token| match *token {
+                    ProductToken::Product(ref product) => Some(product),
+                    ProductToken::Comment(_) => None,
+                })
+            }
+
+            pub fn comments(&self) -> impl Iterator<Item = &str> {
+                self.tokens.iter().filter_map(|token| match *token {
+                    ProductToken::Comment(ref comment) => Some(comment.as_str()),
+                    ProductToken::Product(_) => None,
+                })
+            }
+
+            /// Finds a product by name, ignoring case.
+            pub fn product(&self, name: &str) -> Option<&Product> {
+                self.products().find(|product| product.name().eq_ignore_ascii_case(name))
+            }
+        }
+
+        impl TypedHeader for $header {
+            fn name() -> &'static str {
+                $name
+            }
+
+            fn canonical_name() -> &'static str {
+                $canonical_name
+            }
+
+            fn parse(raw: &[&RawHeader]) -> Option<Self> {
+                let value = raw.first()?.value();
+
+                Some($header {
+                    value: value.to_string(),
+                    tokens: parse_tokens(value),
+                })
+            }
+
+            fn raw_values(&self) -> Vec<String> {
+                vec![self.value.clone()]
+            }
+        }
+    }
+}
+
+product_header!(
+    /// The software of the client, most significant product first.
+    /// [RFC7231, Section 5.5.3](http://www.iana.org/go/rfc7231)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use teapot::http::headers::{Product, ProductToken, RawHeader, TypedHeader, UserAgentHeader};
+    ///
+    /// let raw = RawHeader::new("User-Agent",
+    ///                          "Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 \
+    ///                           Firefox/121.0");
+    /// let header = UserAgentHeader::parse(&[&raw]).unwrap();
+    ///
+    /// assert_eq!(Some("121.0"), header.product("firefox").and_then(Product::version));
+    /// assert_eq!(vec!["X11; Linux x86_64; rv:121.0"], header.comments().collect::<Vec<_>>());
+    ///
+    /// let header = UserAgentHeader::new(vec![
+    ///     ProductToken::Product(Product::new("teapot", Some("0.1"))),
+    ///     ProductToken::Comment("+https://example.com".to_string()),
+    /// ]);
+    ///
+    /// assert_eq!("teapot/0.1 (+https://example.com)", header.value());
+    /// ```
+    UserAgentHeader,
+    "user-agent",
+    "User-Agent");
+
+product_header!(
+    /// The software used by the origin server.
+    /// [RFC7231, Section 7.4.2](http://www.iana.org/go/rfc7231)
+    ServerHeader,
+    "server",
+    "Server");
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_tokens() {
+        let tokens = parse_tokens("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                                   (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36");
+
+        assert_eq!(vec![ProductToken::Product(Product::new("Mozilla", Some("5.0"))),
+                        ProductToken::Comment("Windows NT 10.0; Win64; x64".to_string()),
+                        ProductToken::Product(Product::new("AppleWebKit", Some("537.36"))),
+                        ProductToken::Comment("KHTML, like Gecko".to_string()),
+                        ProductToken::Product(Product::new("Chrome", Some("120.0.0.0"))),
+                        ProductToken::Product(Product::new("Safari", Some("537.36")))],
+                   tokens);
+    }
+
+    #[test]
+    fn test_parse_nested_comments() {
+        assert_eq!(vec![ProductToken::Product(Product::new("a", None)),
+                        ProductToken::Comment("b (c\\)) d".to_string()),
+                        ProductToken::Product(Product::new("e", Some("1")))],
+                   parse_tokens("a(b (c\\)) d)e/1"));
+        assert_eq!(vec![ProductToken::Comment("unterminated (".to_string())],
+                   parse_tokens("  (unterminated ("));
+    }
+
+    #[test]
+    fn test_server() {
+        let header = ServerHeader::parse(&[&RawHeader::new("Server", "nginx/1.25.3")]).unwrap();
+
+        assert_eq!(Some(&Product::new("nginx", Some("1.25.3"))), header.product("NGINX"));
+        assert_eq!(vec!["nginx/1.25.3".to_string()], header.raw_values());
+
+        let header = ServerHeader::new(vec![ProductToken::Product(Product::new("teapot", None))]);
+
+        assert_eq!("teapot", header.value());
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use super::{CommaDelimited, TypedHeader, RawHeader};
+use super::syntax;
+
+/// The value of [`VaryHeader`].
+///
+/// [`VaryHeader`]: struct.VaryHeader.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Vary {
+    /// `*`, the response varies on more than the request headers
+    Any,
+    Fields(Vec<String>),
+}
+
+impl fmt::Display for Vary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Vary::Any => write!(f, "*"),
+            Vary::Fields(ref names) => write!(f, "{}", names.join(", ")),
+        }
+    }
+}
+
+/// The request headers that were used to select the representation.
+/// [RFC7231, Section 7.1.4](http://www.iana.org/go/rfc7231)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{RawHeader, TypedHeader, Vary, VaryHeader};
+///
+/// let raw = [RawHeader::new("Vary", "Accept-Encoding"), RawHeader::new("Vary", "origin")];
+/// let vary = VaryHeader::parse(&[&raw[0], &raw[1]]).unwrap();
+///
+/// assert!(vary.contains("Origin"));
+/// assert_eq!(Some(VaryHeader::any()), VaryHeader::parse(&[&RawHeader::new("Vary", "*")]));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VaryHeader {
+    value: Vary,
+}
+
+impl VaryHeader {
+    pub fn new(value: Vary) -> Self {
+        VaryHeader { value }
+    }
+
+    pub fn any() -> Self {
+        VaryHeader::new(Vary::Any)
+    }
+
+    pub fn fields(names: Vec<String>) -> Self {
+        VaryHeader::new(Vary::Fields(names))
+    }
+
+    pub fn value(&self) -> &Vary {
+        &self.value
+    }
+
+    pub fn is_any(&self) -> bool {
+        self.value == Vary::Any
+    }
+
+    /// Checks whether the response varies on `name`, ignoring case.
+    /// Always `true` for `*`.
+    pub fn contains(&self, name: &str) -> bool {
+        match self.value {
+            Vary::Any => true,
+            Vary::Fields(ref names) => names.iter().any(|other| other.eq_ignore_ascii_case(name)),
+        }
+    }
+
+    /// Adds `name` unless it is already listed or the value is `*`.
+    pub fn push<S: Into<String>>(&mut self, name: S) {
+        let name = name.into();
+
+        if self.contains(&name) {
+            return;
+        }
+
+        if let Vary::Fields(ref mut names) = self.value {
+            names.push(name);
+        }
+    }
+}
+
+impl TypedHeader for VaryHeader {
+    fn name() -> &'static str {
+        "vary"
+    }
+
+    fn canonical_name() -> &'static str {
+        "Vary"
+    }
+
+    /// A `*` anywhere in the list makes the whole value `*`.
+    fn parse(raw: &[&RawHeader]) -> Option<Self> {
+        let names: CommaDelimited<String> = CommaDelimited::from_raw(raw).ok()?;
+
+        if names.is_empty() {
+            return None;
+        }
+
+        if names.iter().any(|name| name == "*") {
+            return Some(VaryHeader::any());
+        }
+
+        if !names.iter().all(|name| syntax::is_token(name)) {
+            return None;
+        }
+
+        Some(VaryHeader::fields(names.into_items()))
+    }
+
+    fn raw_values(&self) -> Vec<String> {
+        vec![self.value.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let parse = |value| VaryHeader::parse(&[&RawHeader::new("Vary", value)]);
+
+        assert_eq!(Some(VaryHeader::fields(vec!["Accept".to_string(), "Cookie".to_string()])),
+                   parse("Accept,  Cookie"));
+        assert_eq!(Some(VaryHeader::any()), parse("Accept, *"));
+        assert_eq!(None, parse(""));
+        assert_eq!(None, parse("Accept Cookie"));
+    }
+
+    #[test]
+    fn test_push() {
+        let mut vary = VaryHeader::fields(vec!["Accept".to_string()]);
+
+        vary.push("accept");
+        vary.push("Origin");
+
+        assert_eq!(vec!["Accept, Origin".to_string()], vary.raw_values());
+
+        let mut any = VaryHeader::any();
+
+        any.push("Origin");
+
+        assert_eq!(vec!["*".to_string()], any.raw_values());
+    }
+}
@@ -0,0 +1,124 @@
+//! Informational (1xx) responses, which a server may send any number of
+//! before the final response, e.g. `103 Early Hints` with preload links.
+//! [RFC7231, Section 6.2](http://www.iana.org/go/rfc7231)
+
+use std::io::{self, Write};
+use super::headers::Headers;
+use super::parse;
+use super::status::StatusCode;
+
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{Headers, Link, LinkHeader};
+/// use teapot::http::interim::InterimResponse;
+///
+/// let mut preload = Link::new("/style.css");
+/// let mut headers = Headers::new();
+/// let mut output = Vec::new();
+///
+/// preload.push_rel("preload");
+/// preload.push_param("as", "style");
+/// headers.append(LinkHeader::new(vec![preload]));
+///
+/// InterimResponse::early_hints(headers).write_to(&mut output).unwrap();
+///
+/// assert_eq!("HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload; as=style\r\n\r\n",
+///            String::from_utf8(output).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct InterimResponse {
+    status: StatusCode,
+    headers: Headers,
+}
+
+impl InterimResponse {
+    /// Returns `None` unless `status` is informational.
+    /// `101 Switching Protocols` is not an interim response,
+    /// since the connection is handed over to another protocol.
+    pub fn new(status: StatusCode, headers: Headers) -> Option<Self> {
+        if !is_interim(status) {
+            return None;
+        }
+
+        Some(InterimResponse { status, headers })
+    }
+
+    pub fn hundred_continue() -> Self {
+        InterimResponse {
+            status: StatusCode::Continue,
+            headers: Headers::new(),
+        }
+    }
+
+    /// `103 Early Hints`, usually with `Link` headers the client can preload
+    /// while the server prepares the final response.
+    /// [RFC8297](http://www.iana.org/go/rfc8297)
+    pub fn early_hints(headers: Headers) -> Self {
+        InterimResponse {
+            status: StatusCode::EarlyHints,
+            headers,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// Writes and flushes the response, so the client receives it
+    /// before the final response is ready.
+    pub fn write_to(&self, writer: &mut dyn Write) -> io::Result<()> {
+        write!(writer, "HTTP/1.1 {}\r\n", self.status)?;
+        parse::write_headers(writer, &self.headers)?;
+        writer.flush()
+    }
+}
+
+/// `101 Switching Protocols` ends the response, since the connection
+/// no longer speaks HTTP/1.1 afterwards.
+pub(crate) fn is_interim(status: StatusCode) -> bool {
+    status.is_informational() && status != StatusCode::SwitchingProtocols
+}
+
+/// Returns the status code of `start_line` if it is the status line
+/// of an interim response.
+pub(crate) fn interim_status(start_line: &str) -> Option<StatusCode> {
+    parse_status_line(start_line).filter(|&status| is_interim(status))
+}
+
+pub(crate) fn parse_status_line(status_line: &str) -> Option<StatusCode> {
+    let mut parts = status_line.split(' ');
+    let version = parts.next()?;
+    let code = parts.next()?;
+
+    if !version.starts_with("HTTP/") || code.len() != 3 {
+        return None;
+    }
+
+    code.parse().ok().map(StatusCode::from_u16)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(InterimResponse::new(StatusCode::Processing, Headers::new()).is_some());
+        assert!(InterimResponse::new(StatusCode::SwitchingProtocols, Headers::new()).is_none());
+        assert!(InterimResponse::new(StatusCode::Ok, Headers::new()).is_none());
+    }
+
+    #[test]
+    fn test_interim_status() {
+        assert_eq!(Some(StatusCode::EarlyHints), interim_status("HTTP/1.1 103 Early Hints"));
+        assert_eq!(Some(StatusCode::Unregistered(199)), interim_status("HTTP/1.1 199"));
+        assert_eq!(None, interim_status("HTTP/1.1 101 Switching Protocols"));
+        assert_eq!(None, interim_status("HTTP/1.1 200 OK"));
+        assert_eq!(None, interim_status("GET /100 HTTP/1.1"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Matching of language tags against a user's language preferences.
+//! [RFC4647, Section 3](http://www.iana.org/go/rfc4647)
+
+use super::headers::{LanguageRange, LanguageTag};
+
+/// Returns the tags matched by any of `ranges`, which should be ordered
+/// by priority. The result is ordered by the first matching range, then by
+/// the order of `tags`.
+/// [RFC4647, Section 3.3.1](http://www.iana.org/go/rfc4647)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{LanguageRange, LanguageTag};
+/// use teapot::http::language::basic_filter;
+///
+/// let ranges: Vec<LanguageRange> = vec!["de-DE".parse().unwrap(), "en".parse().unwrap()];
+/// let tags: Vec<LanguageTag> = vec!["en-GB".parse().unwrap(),
+///                                   "de".parse().unwrap(),
+///                                   "de-DE-1996".parse().unwrap()];
+///
+/// assert_eq!(vec![&tags[2], &tags[0]], basic_filter(&ranges, &tags));
+/// ```
+pub fn basic_filter<'a>(ranges: &[LanguageRange],
+                        tags: &'a [LanguageTag])
+                        -> Vec<&'a LanguageTag> {
+    let mut matched: Vec<&LanguageTag> = vec![];
+
+    for range in ranges {
+        for tag in tags {
+            if range.matches(tag) && !matched.contains(&tag) {
+                matched.push(tag);
+            }
+        }
+    }
+
+    matched
+}
+
+/// Returns the single tag best matching `ranges`, which should be ordered
+/// by priority. Each range is progressively truncated until a tag
+/// equals it, e.g. `zh-Hant-CN-x-private` is tried as `zh-Hant-CN`,
+/// `zh-Hant` and `zh`. `*` is ignored.
+///
+/// Returns `None` if no tag matches, the caller should use a default then.
+/// [RFC4647, Section 3.4](http://www.iana.org/go/rfc4647)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{LanguageRange, LanguageTag};
+/// use teapot::http::language::lookup;
+///
+/// let ranges: Vec<LanguageRange> = vec!["fr-CA".parse().unwrap(), "en-US".parse().unwrap()];
+/// let tags: Vec<LanguageTag> = vec!["en".parse().unwrap(), "fr".parse().unwrap()];
+///
+/// assert_eq!(Some(&tags[1]), lookup(&ranges, &tags));
+/// assert_eq!(None, lookup(&ranges, &tags[..0]));
+/// ```
+pub fn lookup<'a>(ranges: &[LanguageRange], tags: &'a [LanguageTag]) -> Option<&'a LanguageTag> {
+    for range in ranges {
+        let mut range = match *range {
+            LanguageRange::Any => continue,
+            LanguageRange::Range(ref range) => range.as_str(),
+        };
+
+        loop {
+            if let Some(tag) = tags.iter().find(|tag| tag.as_str().eq_ignore_ascii_case(range)) {
+                return Some(tag);
+            }
+
+            range = match range.rfind('-') {
+                Some(index) => &range[..index],
+                None => break,
+            };
+
+            if range.len() >= 2 && range.as_bytes()[range.len() - 2] == b'-' {
+                range = &range[..range.len() - 2];
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ranges(values: &[&str]) -> Vec<LanguageRange> {
+        values.iter().map(|value| value.parse().unwrap()).collect()
+    }
+
+    fn tags(values: &[&str]) -> Vec<LanguageTag> {
+        values.iter().map(|value| value.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_basic_filter() {
+        let tags = tags(&["de", "de-CH", "en-US", "fr"]);
+
+        assert_eq!(vec![&tags[1], &tags[0]], basic_filter(&ranges(&["DE-ch", "de"]), &tags));
+        assert_eq!(vec![&tags[2], &tags[0], &tags[1], &tags[3]],
+                   basic_filter(&ranges(&["en", "*"]), &tags));
+        assert!(basic_filter(&ranges(&["e"]), &tags).is_empty());
+    }
+
+    /// [RFC4647, Section 3.4](http://www.iana.org/go/rfc4647)
+    #[test]
+    fn test_lookup() {
+        let tags = tags(&["zh", "zh-Hant", "de-CH"]);
+
+        assert_eq!(Some(&tags[1]), lookup(&ranges(&["zh-Hant-CN-x-private1-private2"]), &tags));
+        assert_eq!(Some(&tags[0]), lookup(&ranges(&["zh-Hans-CN-a-ext"]), &tags));
+        assert_eq!(Some(&tags[2]), lookup(&ranges(&["*", "de-ch-1996", "zh"]), &tags));
+        assert_eq!(None, lookup(&ranges(&["de", "*"]), &tags));
+    }
+}
@@ -20,7 +20,7 @@ pub struct Lines<R> {
 
 impl<R> Lines<R> {
     fn new(inner: R) -> Self {
-        Lines { inner: inner }
+        Lines { inner }
     }
 }
 
@@ -1,14 +1,21 @@
-use std::io::Read;
+use std::io::{self, Read, Write};
 use std::convert::From;
 use std::string::FromUtf8Error;
-use super::lines::{ReadLines, LinesError};
-use super::headers::{Headers, RawHeader};
+use super::lines::LinesError;
+use super::chunked::{self, ChunkedReader, ChunkedWriter};
+use super::date::HttpDate;
+use super::encoding::{CodecRegistry, UnsupportedCoding};
+use super::headers::{CommaDelimited, ContentEncodingHeader, DateHeader, Headers, TypedHeader};
+use super::interim::{self, InterimResponse};
+use super::parse;
 
 #[derive(Debug)]
 pub enum ParseError {
     Hello,
     FromUtf8Error(FromUtf8Error),
     ReadError(::std::io::Error),
+    /// The input ended before the end of the head.
+    UnexpectedEof,
 }
 
 impl From<FromUtf8Error> for ParseError {
@@ -17,6 +24,19 @@ impl From<FromUtf8Error> for ParseError {
     }
 }
 
+impl From<io::Error> for ParseError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            return ParseError::UnexpectedEof;
+        }
+
+        match err.get_ref().and_then(|inner| inner.downcast_ref::<FromUtf8Error>()) {
+            Some(inner) => ParseError::FromUtf8Error(inner.clone()),
+            None => ParseError::ReadError(err),
+        }
+    }
+}
+
 impl From<LinesError> for ParseError {
     fn from(err: LinesError) -> Self {
         match err {
@@ -26,48 +46,363 @@ impl From<LinesError> for ParseError {
 }
 
 pub struct Message<'a> {
+    interim: Vec<InterimResponse>,
     start_line: String,
     headers: Headers,
-    body: &'a Read,
+    body: &'a mut dyn Read,
+    trailers: Headers,
 }
 
 impl<'a> Message<'a> {
+    /// The informational responses that preceded a response, in order.
+    pub fn interim(&self) -> &[InterimResponse] {
+        &self.interim
+    }
+
+    pub fn push_interim(&mut self, response: InterimResponse) {
+        self.interim.push(response);
+    }
+
     pub fn start_line(&self) -> &String {
         &self.start_line
     }
 
-    pub fn new<S: Into<String>>(start_line: S, headers: Headers, body: &'a Read) -> Self {
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    /// The trailer section of a chunked message, filled by [`decode_chunked_body`].
+    ///
+    /// [`decode_chunked_body`]: #method.decode_chunked_body
+    pub fn trailers(&self) -> &Headers {
+        &self.trailers
+    }
+
+    /// Trailers are only written if the message uses the chunked transfer coding.
+    pub fn trailers_mut(&mut self) -> &mut Headers {
+        &mut self.trailers
+    }
+
+    /// Checks whether `chunked` is the final transfer coding.
+    pub fn is_chunked(&self) -> bool {
+        let codings: CommaDelimited<String> =
+            CommaDelimited::from_raw(&self.headers.get_raw("transfer-encoding"))
+                .unwrap_or_default();
+
+        codings.iter().last().is_some_and(|coding| coding.eq_ignore_ascii_case("chunked"))
+    }
+
+    /// Decodes the chunked body of a parsed message into `output`
+    /// and stores the trailer section in [`trailers`].
+    /// Returns the length of the decoded body.
+    ///
+    /// [`trailers`]: #method.trailers
+    pub fn decode_chunked_body(&mut self, output: &mut dyn Write) -> io::Result<u64> {
+        let mut reader = ChunkedReader::new(&mut *self.body);
+        let length = io::copy(&mut reader, output)?;
+
+        self.trailers = reader.into_trailers().unwrap_or_default();
+
+        Ok(length)
+    }
+
+    pub fn body(&self) -> &dyn Read {
+        self.body
+    }
+
+    pub fn body_mut(&mut self) -> &mut dyn Read {
+        self.body
+    }
+
+    /// Returns the body with all codings listed in `Content-Encoding` removed.
+    pub fn decoded_body(self,
+                        registry: &CodecRegistry)
+                        -> Result<Box<dyn Read + 'a>, UnsupportedCoding> {
+        let codings = self.headers
+            .get::<ContentEncodingHeader>()
+            .map(|header| header.codings().to_vec())
+            .unwrap_or_default();
+
+        registry.decode(&codings, Box::new(self.body))
+    }
+
+    pub fn new<S: Into<String>>(start_line: S, headers: Headers, body: &'a mut dyn Read) -> Self {
         Message {
+            interim: vec![],
             start_line: start_line.into(),
-            headers: headers,
-            body: body,
+            headers,
+            body,
+            trailers: Headers::new(),
         }
     }
 
-    pub fn parse(buffer: &mut Read) -> Result<Message, ParseError> {
-        let start_line = {
-            let mut lines = buffer.lines();
+    /// Interim responses before the final response are collected
+    /// into [`interim`].
+    /// Fails with `UnexpectedEof` if the input ends before the end of a head.
+    ///
+    /// [`interim`]: #method.interim
+    pub fn parse(buffer: &mut dyn Read) -> Result<Message<'_>, ParseError> {
+        let mut interim = vec![];
+
+        loop {
+            let (start_line, headers) = parse::read_head(&mut &mut *buffer)?;
 
-            // TODO: replace .unwrap() with proper error
-            let raw = try!(lines.next().unwrap());
+            let status = match interim::interim_status(&start_line) {
+                Some(status) => status,
+                None => {
+                    let mut message = Message::new(start_line, headers, buffer);
 
-            try!(String::from_utf8(raw))
-        };
+                    message.interim = interim;
 
-        Ok(Message::new(start_line, Headers::new(), buffer))
+                    return Ok(message);
+                }
+            };
+
+            interim.extend(InterimResponse::new(status, headers));
+        }
     }
-}
 
+    /// Writes the interim responses, the head and the body.
+    /// If the message [`is_chunked`], the body is written in chunks
+    /// followed by the [`trailers`].
+    /// Responses without a `Date` header get one with the current time.
+    /// [RFC7231, Section 7.1.1.2](http://www.iana.org/go/rfc7231)
+    ///
+    /// Fails with `InvalidData` if a trailer field is not allowed in trailers
+    /// and with `InvalidInput` if there are trailers but the message isn't chunked,
+    /// in both cases before anything is written.
+    ///
+    /// [`is_chunked`]: #method.is_chunked
+    /// [`trailers`]: #method.trailers
+    pub fn write_to(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        let chunked = self.is_chunked();
+
+        if !chunked && !self.trailers.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "trailers require the chunked transfer coding"));
+        }
+
+        chunked::check_trailers(&self.trailers)?;
+
+        if interim::parse_status_line(&self.start_line).is_some() &&
+           !self.headers.contains(DateHeader::name()) {
+            self.headers.append(DateHeader::new(HttpDate::now()));
+        }
+
+        for response in &self.interim {
+            response.write_to(writer)?;
+        }
+
+        write!(writer, "{}\r\n", self.start_line)?;
+        parse::write_headers(writer, &self.headers)?;
+
+        if chunked {
+            let mut body = ChunkedWriter::new(&mut *writer);
+
+            io::copy(self.body, &mut body)?;
+            body.finish(&self.trailers)?;
+        } else {
+            io::copy(self.body, writer)?;
+        }
+
+        writer.flush()
+    }
+}
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use super::super::headers::RawHeader;
+    use super::super::status::StatusCode;
 
     #[test]
     fn test_parse() {
-        let mut bytes = "HTTP/1.1\r\nX-Foo: Bar".as_bytes();
+        let mut bytes = "HTTP/1.1\r\nX-Foo: Bar\r\n\r\n".as_bytes();
         let message = Message::parse(&mut bytes).unwrap();
 
         assert_eq!("HTTP/1.1", message.start_line());
+        assert_eq!("Bar", message.headers().get_raw("x-foo")[0].value());
+    }
+
+    #[test]
+    fn test_parse_unexpected_eof() {
+        let parse = |input: &str| Message::parse(&mut input.as_bytes()).err();
+
+        assert!(matches!(parse(""), Some(ParseError::UnexpectedEof)));
+        assert!(matches!(parse("HTTP/1.1 200 OK\r\nX-Foo: Bar"), Some(ParseError::UnexpectedEof)));
+        assert!(matches!(parse("HTTP/1.1 100 Continue\r\n\r\n"),
+                         Some(ParseError::UnexpectedEof)));
+        assert!(matches!(Message::parse(&mut &b"HTTP/1.1 \xff\r\n\r\n"[..]).err(),
+                         Some(ParseError::FromUtf8Error(_))));
+    }
+
+    #[test]
+    fn test_parse_interim() {
+        let mut bytes = "HTTP/1.1 100 Continue\r\n\r\n\
+                         HTTP/1.1 103 Early Hints\r\nLink: </a.css>; rel=preload\r\n\r\n\
+                         HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nHello"
+            .as_bytes();
+        let mut message = Message::parse(&mut bytes).unwrap();
+        let mut body = String::new();
+
+        assert_eq!("HTTP/1.1 200 OK", message.start_line());
+        assert_eq!(2, message.interim().len());
+        assert_eq!(StatusCode::Continue, message.interim()[0].status());
+        assert_eq!(StatusCode::EarlyHints, message.interim()[1].status());
+        assert!(message.interim()[1].headers().contains("link"));
+        assert!(!message.headers().contains("link"));
+
+        message.body_mut().read_to_string(&mut body).unwrap();
+
+        assert_eq!("Hello", body);
+    }
+
+    #[test]
+    fn test_write_to() {
+        let mut hints = Headers::new();
+        let mut headers = Headers::new();
+        let mut body = "Hello".as_bytes();
+        let mut output = Vec::new();
+
+        hints.append_raw(RawHeader::new("Link", "</a.css>; rel=preload"));
+        headers.append_raw(RawHeader::new("Content-Length", "5"));
+        headers.append_raw(RawHeader::new("Date", "Sun, 06 Nov 1994 08:49:37 GMT"));
+
+        let mut message = Message::new("HTTP/1.1 200 OK", headers, &mut body);
+
+        message.push_interim(InterimResponse::early_hints(hints));
+        message.write_to(&mut output).unwrap();
+
+        assert_eq!("HTTP/1.1 103 Early Hints\r\nLink: </a.css>; rel=preload\r\n\r\n\
+                    HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\
+                    Date: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\nHello",
+                   String::from_utf8(output.clone()).unwrap());
+
+        let mut bytes = output.as_slice();
+
+        assert_eq!(1, Message::parse(&mut bytes).unwrap().interim().len());
+    }
+
+    #[test]
+    fn test_write_date() {
+        let mut body = "".as_bytes();
+        let mut output = Vec::new();
+        let before = HttpDate::now();
+        let mut message = Message::new("HTTP/1.1 204 No Content", Headers::new(), &mut body);
+
+        message.write_to(&mut output).unwrap();
+
+        let date = message.headers().get::<DateHeader>().unwrap();
+
+        assert!(date.value() >= before && date.value() <= HttpDate::now());
+        assert_eq!(format!("HTTP/1.1 204 No Content\r\nDate: {}\r\n\r\n", date.value()),
+                   String::from_utf8(output).unwrap());
+
+        let mut output = Vec::new();
+        let mut message = Message::new("GET / HTTP/1.1", Headers::new(), &mut body);
+
+        message.write_to(&mut output).unwrap();
+
+        assert_eq!("GET / HTTP/1.1\r\n\r\n", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_trailers() {
+        let mut headers = Headers::new();
+        let mut body = "Hello".as_bytes();
+        let mut output = Vec::new();
+
+        headers.append_raw(RawHeader::new("Transfer-Encoding", "gzip, Chunked"));
+        headers.append_raw(RawHeader::new("Trailer", "grpc-status"));
+        headers.append_raw(RawHeader::new("Date", "Sun, 06 Nov 1994 08:49:37 GMT"));
+
+        let mut message = Message::new("HTTP/1.1 200 OK", headers, &mut body);
+
+        message.trailers_mut().append_raw(RawHeader::new("grpc-status", "0"));
+        message.write_to(&mut output).unwrap();
+
+        assert_eq!("HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip, Chunked\r\n\
+                    Trailer: grpc-status\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n\
+                    5\r\nHello\r\n0\r\ngrpc-status: 0\r\n\r\n",
+                   String::from_utf8(output.clone()).unwrap());
+
+        let mut bytes = output.as_slice();
+        let mut message = Message::parse(&mut bytes).unwrap();
+        let mut body = Vec::new();
+
+        assert!(message.is_chunked());
+        assert_eq!(5, message.decode_chunked_body(&mut body).unwrap());
+        assert_eq!(b"Hello".to_vec(), body);
+        assert_eq!("0", message.trailers().get_raw("grpc-status")[0].value());
+    }
+
+    #[test]
+    fn test_invalid_trailers() {
+        let mut body = "".as_bytes();
+        let mut output = Vec::new();
+        let mut message = Message::new("HTTP/1.1 200 OK", Headers::new(), &mut body);
+
+        message.trailers_mut().append_raw(RawHeader::new("X-Checksum", "1"));
+
+        assert!(!message.is_chunked());
+        assert_eq!(io::ErrorKind::InvalidInput,
+                   message.write_to(&mut output).unwrap_err().kind());
+        assert!(output.is_empty());
+
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Transfer-Encoding", "chunked"));
+
+        let mut message = Message::new("HTTP/1.1 200 OK", headers, &mut body);
+
+        message.trailers_mut().append_raw(RawHeader::new("Content-Type", "text/plain"));
+
+        assert_eq!(io::ErrorKind::InvalidData,
+                   message.write_to(&mut output).unwrap_err().kind());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_decoded_body() {
+        let mut bytes = "HTTP/1.1 200 OK\r\nContent-Encoding: identity\r\n\r\nHello".as_bytes();
+        let message = Message::parse(&mut bytes).unwrap();
+        let mut body = String::new();
+
+        message.decoded_body(&CodecRegistry::new())
+            .unwrap()
+            .read_to_string(&mut body)
+            .unwrap();
+
+        assert_eq!("Hello", body);
+
+        let mut bytes = "HTTP/1.1 200 OK\r\nContent-Encoding: compress\r\n\r\n".as_bytes();
+        let message = Message::parse(&mut bytes).unwrap();
+
+        assert!(message.decoded_body(&CodecRegistry::new()).is_err());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_decoded_gzip_body() {
+        use std::io::Write;
+        use super::super::headers::ContentCoding;
+
+        let registry = CodecRegistry::default();
+        let mut bytes = b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\n\r\n".to_vec();
+
+        {
+            let mut encoder = registry.encode(&ContentCoding::Gzip, Box::new(&mut bytes)).unwrap();
+
+            encoder.write_all(b"compressed").unwrap();
+            encoder.finish().unwrap();
+        }
+
+        let mut bytes = bytes.as_slice();
+        let message = Message::parse(&mut bytes).unwrap();
+        let mut body = String::new();
+
+        message.decoded_body(&registry).unwrap().read_to_string(&mut body).unwrap();
+
+        assert_eq!("compressed", body);
     }
 }
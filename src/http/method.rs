@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+use super::headers::syntax;
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum Method {
+    /// [RFC7231, Section 4.3.1](http://www.iana.org/go/rfc7231)
+    Get,
+    /// [RFC7231, Section 4.3.2](http://www.iana.org/go/rfc7231)
+    Head,
+    /// [RFC7231, Section 4.3.3](http://www.iana.org/go/rfc7231)
+    Post,
+    /// [RFC7231, Section 4.3.4](http://www.iana.org/go/rfc7231)
+    Put,
+    /// [RFC7231, Section 4.3.5](http://www.iana.org/go/rfc7231)
+    Delete,
+    /// [RFC7231, Section 4.3.6](http://www.iana.org/go/rfc7231)
+    Connect,
+    /// [RFC7231, Section 4.3.7](http://www.iana.org/go/rfc7231)
+    Options,
+    /// [RFC7231, Section 4.3.8](http://www.iana.org/go/rfc7231)
+    Trace,
+    /// [RFC5789](http://www.iana.org/go/rfc5789)
+    Patch,
+    /// Catch-All for all other methods
+    Extension(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidMethod;
+
+impl Method {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Connect => "CONNECT",
+            Method::Options => "OPTIONS",
+            Method::Trace => "TRACE",
+            Method::Patch => "PATCH",
+            Method::Extension(ref method) => method,
+        }
+    }
+
+    /// [RFC7231, Section 4.2.1](http://www.iana.org/go/rfc7231)
+    pub fn is_safe(&self) -> bool {
+        matches!(*self, Method::Get | Method::Head | Method::Options | Method::Trace)
+    }
+
+    /// [RFC7231, Section 4.2.2](http://www.iana.org/go/rfc7231)
+    pub fn is_idempotent(&self) -> bool {
+        match *self {
+            Method::Put | Method::Delete => true,
+            _ => self.is_safe(),
+        }
+    }
+}
+
+impl FromStr for Method {
+    type Err = InvalidMethod;
+
+    /// Method names are case-sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "GET" => Method::Get,
+            "HEAD" => Method::Head,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "DELETE" => Method::Delete,
+            "CONNECT" => Method::Connect,
+            "OPTIONS" => Method::Options,
+            "TRACE" => Method::Trace,
+            "PATCH" => Method::Patch,
+            _ if syntax::is_token(s) => Method::Extension(s.to_string()),
+            _ => return Err(InvalidMethod),
+        })
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Method::Get), "GET".parse());
+        assert_eq!(Ok(Method::Extension("get".to_string())), "get".parse());
+        assert_eq!(Ok(Method::Extension("PROPFIND".to_string())),
+                   "PROPFIND".parse());
+        assert_eq!(Err(InvalidMethod), "GET /".parse::<Method>());
+        assert_eq!(Err(InvalidMethod), "".parse::<Method>());
+    }
+
+    #[test]
+    fn test_properties() {
+        assert!(Method::Head.is_safe());
+        assert!(!Method::Put.is_safe());
+        assert!(Method::Put.is_idempotent());
+        assert!(!Method::Post.is_idempotent());
+        assert_eq!("PATCH", Method::Patch.to_string());
+    }
+}
@@ -1,6 +1,22 @@
 mod lines;
 mod parse;
 
+pub mod cache;
+pub mod chunked;
+pub mod conditional;
+pub mod cors;
+pub mod date;
+pub mod digest;
+pub mod encoding;
+pub mod expect;
+pub mod fetch_metadata;
+pub mod forwarded;
 pub mod headers;
+pub mod interim;
+pub mod language;
 pub mod message;
+pub mod method;
+pub mod range;
 pub mod status;
+#[cfg(feature = "user-agent-classifier")]
+pub mod user_agent;
@@ -1,7 +1,3 @@
-use super::super::lines::Lines;
-use super::super::headers::RawHeader;
-use std::io::Read;
-
 /*enum ParserState {
     Error,
     BeforeName,
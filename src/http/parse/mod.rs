@@ -1 +1,84 @@
+use std::io::{self, Read, Write};
+use super::headers::{Headers, RawHeader};
+use super::lines::{LinesError, ReadLines};
+
 mod headers;
+
+/// Writes the header fields followed by the empty line ending the head.
+/// Fails with `InvalidInput` before writing anything if a name or value contains
+/// CR or LF, which would allow injecting header fields.
+pub(crate) fn write_headers(writer: &mut dyn Write, headers: &Headers) -> io::Result<()> {
+    let has_line_break = |value: &str| value.contains(['\r', '\n']);
+
+    if let Some(header) = headers.iter()
+        .find(|header| has_line_break(header.name()) || has_line_break(header.value())) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  format!("line break in header field {:?}", header.name())));
+    }
+
+    for header in headers.iter() {
+        write!(writer, "{}: {}\r\n", header.name(), header.value())?;
+    }
+
+    write!(writer, "\r\n")
+}
+
+/// Reads a start line and the header fields following it.
+pub(crate) fn read_head<R: Read>(reader: &mut R) -> io::Result<(String, Headers)> {
+    let start_line = read_line(reader)?;
+    let mut headers = Headers::new();
+
+    loop {
+        let line = read_line(reader)?;
+
+        if line.is_empty() {
+            return Ok((start_line, headers));
+        }
+
+        headers.append_raw(RawHeader::parse(line));
+    }
+}
+
+pub(crate) fn read_line<R: Read>(reader: &mut R) -> io::Result<String> {
+    let line = match reader.lines().next() {
+        Some(Ok(line)) => line,
+        Some(Err(LinesError::ReadError(err))) => return Err(err),
+        None => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+    };
+
+    String::from_utf8(line).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_headers() {
+        let mut headers = Headers::new();
+        let mut output = vec![];
+
+        headers.append_raw(RawHeader::new("Link", "</a.js>"));
+        write_headers(&mut output, &headers).unwrap();
+
+        assert_eq!(b"Link: </a.js>\r\n\r\n".to_vec(), output);
+
+        headers.append_raw(RawHeader::new("X-Name", "a\r\nSet-Cookie: x=1"));
+        output.clear();
+
+        assert_eq!(io::ErrorKind::InvalidInput,
+                   write_headers(&mut output, &headers).unwrap_err().kind());
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_read_head() {
+        let mut bytes = "HTTP/1.1 103 Early Hints\r\nLink: </a.js>\r\n\r\nrest".as_bytes();
+        let (start_line, headers) = read_head(&mut bytes).unwrap();
+
+        assert_eq!("HTTP/1.1 103 Early Hints", start_line);
+        assert_eq!("</a.js>", headers.get_raw("link")[0].value());
+        assert_eq!(b"rest", bytes);
+        assert!(read_head(&mut "HTTP/1.1 200 OK\r\n".as_bytes()).is_err());
+    }
+}
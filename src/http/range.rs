@@ -0,0 +1,326 @@
+use std::io::{self, Write};
+use super::date::HttpDate;
+use super::headers::{ByteRange, ContentRangeHeader, EntityTag, Headers, IfRangeHeader,
+                     RangeHeader, TypedHeader};
+use super::headers::syntax;
+use super::status::StatusCode;
+
+/// The maximum number of ranges [`resolve_ranges`] returns.
+///
+/// [`resolve_ranges`]: fn.resolve_ranges.html
+pub const MAX_RANGES: usize = 16;
+
+/// Resolves the ranges of a `Range` header against the length of the selected representation.
+/// Unsatisfiable ranges are dropped. If none of the ranges is satisfiable,
+/// the request must be answered with `416 Range Not Satisfiable`.
+/// [RFC7233, Section 4.4](http://www.iana.org/go/rfc7233)
+///
+/// The ranges are sorted and overlapping or adjacent ranges are coalesced.
+/// If more than [`MAX_RANGES`] remain, they are collapsed into a single range
+/// from the first to the last requested byte, so that many small ranges can't
+/// be used to inflate the response.
+/// [RFC7233, Section 6.1](http://www.iana.org/go/rfc7233)
+///
+/// [`MAX_RANGES`]: constant.MAX_RANGES.html
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{ByteRange, ByteRangeSpec, RangeHeader};
+/// use teapot::http::range::resolve_ranges;
+/// use teapot::http::status::StatusCode;
+///
+/// let range = RangeHeader::new(vec![ByteRangeSpec::Suffix(100), ByteRangeSpec::FromTo(0, 99)])
+///     .unwrap();
+///
+/// assert_eq!(Ok(vec![ByteRange::new(0, 99).unwrap(), ByteRange::new(900, 999).unwrap()]),
+///            resolve_ranges(&range, 1000));
+///
+/// let range = RangeHeader::new(vec![ByteRangeSpec::From(500), ByteRangeSpec::Suffix(100)])
+///     .unwrap();
+///
+/// assert_eq!(Ok(vec![ByteRange::new(500, 999).unwrap()]), resolve_ranges(&range, 1000));
+///
+/// let range = RangeHeader::new(vec![ByteRangeSpec::From(500)]).unwrap();
+///
+/// assert_eq!(Err(StatusCode::RangeNotSatisfiable), resolve_ranges(&range, 500));
+/// ```
+pub fn resolve_ranges(range: &RangeHeader, length: u64) -> Result<Vec<ByteRange>, StatusCode> {
+    let mut bounds: Vec<(u64, u64)> = range.ranges()
+        .iter()
+        .filter_map(|spec| spec.to_satisfiable_range(length))
+        .map(|range| (range.first(), range.last()))
+        .collect();
+
+    bounds.sort_unstable();
+
+    let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(bounds.len());
+
+    for (first, last) in bounds {
+        match coalesced.last_mut() {
+            Some(previous) if first <= previous.1.saturating_add(1) => {
+                previous.1 = previous.1.max(last);
+            }
+            _ => coalesced.push((first, last)),
+        }
+    }
+
+    if coalesced.len() > MAX_RANGES {
+        coalesced = vec![(coalesced[0].0, coalesced[coalesced.len() - 1].1)];
+    }
+
+    let ranges: Vec<ByteRange> = coalesced.into_iter()
+        .filter_map(|(first, last)| ByteRange::new(first, last))
+        .collect();
+
+    if ranges.is_empty() {
+        Err(StatusCode::RangeNotSatisfiable)
+    } else {
+        Ok(ranges)
+    }
+}
+
+/// Returns the `Range` header of a request, unless an `If-Range` header is present
+/// that does not match the current representation or can't be parsed, in which case
+/// the whole representation should be sent instead.
+/// [RFC7233, Section 3.2](http://www.iana.org/go/rfc7233)
+pub fn requested_range(headers: &Headers,
+                       etag: Option<&EntityTag>,
+                       last_modified: Option<HttpDate>)
+                       -> Option<RangeHeader> {
+    let range = headers.get::<RangeHeader>()?;
+
+    if !headers.contains(IfRangeHeader::name()) {
+        return Some(range);
+    }
+
+    match headers.get::<IfRangeHeader>() {
+        Some(ref if_range) if if_range.matches(etag, last_modified) => Some(range),
+        _ => None,
+    }
+}
+
+/// Writes a `multipart/byteranges` body.
+/// [RFC7233, Appendix A](http://www.iana.org/go/rfc7233)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::ByteRange;
+/// use teapot::http::range::MultipartByteRanges;
+///
+/// let data = b"Hello, World!";
+/// let mut body = vec![];
+///
+/// {
+///     let mut writer = MultipartByteRanges::new(&mut body, "SEP", "text/plain", Some(13))
+///         .unwrap();
+///
+///     writer.write_part(ByteRange::new(0, 4).unwrap(), &data[0..5]).unwrap();
+///     writer.write_part(ByteRange::new(7, 11).unwrap(), &data[7..12]).unwrap();
+///     writer.finish().unwrap();
+/// }
+///
+/// assert!(String::from_utf8(body).unwrap().starts_with(
+///     "--SEP\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-4/13\r\n\r\nHello\r\n"));
+/// ```
+pub struct MultipartByteRanges<W: Write> {
+    inner: W,
+    boundary: String,
+    content_type: String,
+    complete_length: Option<u64>,
+}
+
+impl<W: Write> MultipartByteRanges<W> {
+    /// `content_type` is the media type of the selected representation,
+    /// which is repeated in every part.
+    /// Returns `None` if `boundary` is not a valid multipart boundary
+    /// or `content_type` is not a media type.
+    pub fn new<B: Into<String>, C: Into<String>>(inner: W,
+                                                 boundary: B,
+                                                 content_type: C,
+                                                 complete_length: Option<u64>)
+                                                 -> Option<Self> {
+        let boundary = boundary.into();
+        let content_type = content_type.into();
+
+        if !is_boundary(&boundary) || !is_media_type(&content_type) {
+            return None;
+        }
+
+        Some(MultipartByteRanges {
+            inner,
+            boundary,
+            content_type,
+            complete_length,
+        })
+    }
+
+    /// The value for the `Content-Type` header of the response.
+    pub fn content_type(&self) -> String {
+        format!("multipart/byteranges; boundary={}", syntax::quote_if_needed(&self.boundary))
+    }
+
+    /// Writes a single part.
+    /// Fails with `InvalidInput` if `data` is not exactly as long as `range`.
+    pub fn write_part(&mut self, range: ByteRange, data: &[u8]) -> io::Result<()> {
+        if range.len() != data.len() as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "data does not match the range"));
+        }
+
+        let content_range = ContentRangeHeader::new(range, self.complete_length);
+
+        write!(self.inner,
+               "--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+               self.boundary,
+               self.content_type,
+               content_range)?;
+        self.inner.write_all(data)?;
+        self.inner.write_all(b"\r\n")
+    }
+
+    /// Writes the closing delimiter and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        write!(self.inner, "--{}--\r\n", self.boundary)?;
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+}
+
+/// 1 to 70 `bchars` that don't end with a space.
+/// [RFC2046, Section 5.1.1](http://www.iana.org/go/rfc2046)
+fn is_boundary(boundary: &str) -> bool {
+    let is_bchar = |c: char| c.is_ascii_alphanumeric() || "'()+_,-./:=? ".contains(c);
+
+    !boundary.is_empty() && boundary.len() <= 70 && !boundary.ends_with(' ') &&
+    boundary.chars().all(is_bchar)
+}
+
+/// `type "/" subtype`, optionally followed by parameters, without control characters.
+fn is_media_type(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+
+    match essence.find('/') {
+        Some(index) => {
+            syntax::is_token(&essence[..index]) && syntax::is_token(&essence[index + 1..]) &&
+            !content_type.chars().any(|c| c.is_control() && c != '\t')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::{ByteRangeSpec, RawHeader};
+
+    #[test]
+    fn test_resolve_ranges() {
+        let range = RangeHeader::new(vec![ByteRangeSpec::FromTo(0, 0),
+                                          ByteRangeSpec::FromTo(10, 20),
+                                          ByteRangeSpec::Suffix(0)]).unwrap();
+
+        assert_eq!(Ok(vec![ByteRange::new(0, 0).unwrap()]), resolve_ranges(&range, 5));
+        assert_eq!(Err(StatusCode::RangeNotSatisfiable),
+                   resolve_ranges(&range, 0));
+    }
+
+    #[test]
+    fn test_coalesce_ranges() {
+        let range = RangeHeader::new(vec![ByteRangeSpec::FromTo(20, 29),
+                                          ByteRangeSpec::FromTo(0, 9),
+                                          ByteRangeSpec::FromTo(10, 14),
+                                          ByteRangeSpec::FromTo(25, 40),
+                                          ByteRangeSpec::FromTo(50, 59)]).unwrap();
+
+        assert_eq!(Ok(vec![ByteRange::new(0, 14).unwrap(),
+                           ByteRange::new(20, 40).unwrap(),
+                           ByteRange::new(50, 59).unwrap()]),
+                   resolve_ranges(&range, 100));
+
+        let specs = (0..MAX_RANGES as u64 + 1)
+            .map(|index| ByteRangeSpec::FromTo(index * 10 + 5, index * 10 + 6))
+            .collect();
+
+        assert_eq!(Ok(vec![ByteRange::new(5, MAX_RANGES as u64 * 10 + 6).unwrap()]),
+                   resolve_ranges(&RangeHeader::new(specs).unwrap(), 1000));
+
+        let specs = (0..MAX_RANGES as u64 * 10).map(|_| ByteRangeSpec::FromTo(0, 0)).collect();
+
+        assert_eq!(Ok(vec![ByteRange::new(0, 0).unwrap()]),
+                   resolve_ranges(&RangeHeader::new(specs).unwrap(), 1000));
+    }
+
+    #[test]
+    fn test_requested_range() {
+        let etag = EntityTag::strong("v1").unwrap();
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Range", "bytes=100-"));
+
+        assert!(requested_range(&headers, Some(&etag), None).is_some());
+
+        headers.append_raw(RawHeader::new("If-Range", "\"v1\""));
+
+        assert!(requested_range(&headers, Some(&etag), None).is_some());
+        assert!(requested_range(&headers, Some(&EntityTag::strong("v2").unwrap()), None).is_none());
+
+        let mut headers = Headers::new();
+
+        headers.append_raw(RawHeader::new("Range", "bytes=100-"));
+        headers.append_raw(RawHeader::new("If-Range", "v1"));
+
+        assert!(requested_range(&headers, Some(&etag), None).is_none());
+    }
+
+    #[test]
+    fn test_multipart_byteranges() {
+        let data = b"0123456789";
+        let mut writer = MultipartByteRanges::new(vec![], "BOUNDARY", "text/plain", None)
+            .unwrap();
+
+        assert_eq!("multipart/byteranges; boundary=BOUNDARY", writer.content_type());
+
+        writer.write_part(ByteRange::new(0, 1).unwrap(), &data[..2]).unwrap();
+        writer.write_part(ByteRange::new(8, 9).unwrap(), &data[8..]).unwrap();
+
+        let body = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        assert_eq!("--BOUNDARY\r\n\
+                    Content-Type: text/plain\r\n\
+                    Content-Range: bytes 0-1/*\r\n\
+                    \r\n\
+                    01\r\n\
+                    --BOUNDARY\r\n\
+                    Content-Type: text/plain\r\n\
+                    Content-Range: bytes 8-9/*\r\n\
+                    \r\n\
+                    89\r\n\
+                    --BOUNDARY--\r\n",
+                   body);
+    }
+
+    #[test]
+    fn test_invalid_multipart_byteranges() {
+        let long = "a".repeat(71);
+        let new = |boundary, content_type| {
+            MultipartByteRanges::new(vec![], boundary, content_type, None)
+        };
+
+        assert!(new("", "text/plain").is_none());
+        assert!(new("a\r\nX-Injected: 1", "text/plain").is_none());
+        assert!(new("a ", "text/plain").is_none());
+        assert!(new(&long, "text/plain").is_none());
+        assert!(new("a", "text").is_none());
+        assert!(new("a", "text/plain\r\nX-Injected: 1").is_none());
+        assert!(new("a", "text/plain; charset=utf-8").is_some());
+
+        let mut writer = new("a b", "text/plain").unwrap();
+
+        assert_eq!("multipart/byteranges; boundary=\"a b\"", writer.content_type());
+        assert_eq!(io::ErrorKind::InvalidInput,
+                   writer.write_part(ByteRange::new(0, 1).unwrap(), b"0").unwrap_err().kind());
+    }
+}
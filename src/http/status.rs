@@ -11,6 +11,9 @@ pub enum StatusCode {
     /// 102 Processing
     /// [RFC2518](http://www.iana.org/go/rfc2518)
     Processing,
+    /// 103 Early Hints
+    /// [RFC8297](http://www.iana.org/go/rfc8297)
+    EarlyHints,
     /// 200 OK
     /// [RFC7231, Section 6.3.1](http://www.iana.org/go/rfc7231)
     Ok,
@@ -208,6 +211,7 @@ impl StatusCode {
             100 => StatusCode::Continue,
             101 => StatusCode::SwitchingProtocols,
             102 => StatusCode::Processing,
+            103 => StatusCode::EarlyHints,
             200 => StatusCode::Ok,
             201 => StatusCode::Created,
             202 => StatusCode::Accepted,
@@ -274,6 +278,7 @@ impl StatusCode {
             StatusCode::Continue => 100,
             StatusCode::SwitchingProtocols => 101,
             StatusCode::Processing => 102,
+            StatusCode::EarlyHints => 103,
             StatusCode::Ok => 200,
             StatusCode::Created => 201,
             StatusCode::Accepted => 202,
@@ -337,11 +342,11 @@ impl StatusCode {
 
     pub fn class(&self) -> StatusClass {
         match self.to_u16() {
-            100...199 => StatusClass::Informational,
-            200...299 => StatusClass::Success,
-            300...399 => StatusClass::Redirection,
-            400...499 => StatusClass::ClientError,
-            500...599 => StatusClass::ServerError,
+            100..=199 => StatusClass::Informational,
+            200..=299 => StatusClass::Success,
+            300..=399 => StatusClass::Redirection,
+            400..=499 => StatusClass::ClientError,
+            500..=599 => StatusClass::ServerError,
             _ => StatusClass::None,
         }
     }
@@ -351,6 +356,7 @@ impl StatusCode {
             StatusCode::Continue => Some("Continue"),
             StatusCode::SwitchingProtocols => Some("Switching Protocols"),
             StatusCode::Processing => Some("Processing"),
+            StatusCode::EarlyHints => Some("Early Hints"),
             StatusCode::Ok => Some("OK"),
             StatusCode::Created => Some("Created"),
             StatusCode::Accepted => Some("Accepted"),
@@ -437,9 +443,9 @@ impl StatusCode {
     }
 }
 
-impl Into<u16> for StatusCode {
-    fn into(self) -> u16 {
-        self.to_u16()
+impl From<StatusCode> for u16 {
+    fn from(status: StatusCode) -> Self {
+        status.to_u16()
     }
 }
 
@@ -484,6 +490,10 @@ mod test {
                          102,
                          "Processing",
                          StatusClass::Informational);
+        test_status_code(StatusCode::EarlyHints,
+                         103,
+                         "Early Hints",
+                         StatusClass::Informational);
         test_status_code(StatusCode::Ok, 200, "OK", StatusClass::Success);
         test_status_code(StatusCode::Created, 201, "Created", StatusClass::Success);
         test_status_code(StatusCode::Accepted, 202, "Accepted", StatusClass::Success);
@@ -0,0 +1,234 @@
+//! Heuristic classification of clients by their `User-Agent`.
+//!
+//! Only available with the `user-agent-classifier` feature.
+//! User agents can claim to be anything, so this is meant for
+//! segmenting traffic and not for access control.
+
+use super::headers::{Product, UserAgentHeader};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+    InternetExplorer,
+    Opera,
+    Safari,
+    SamsungInternet,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum OsFamily {
+    Android,
+    ChromeOs,
+    Ios,
+    Linux,
+    MacOs,
+    Windows,
+}
+
+/// The result of [`classify`].
+///
+/// [`classify`]: fn.classify.html
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Classification {
+    bot: bool,
+    browser: Option<Browser>,
+    browser_version: Option<String>,
+    os: Option<OsFamily>,
+}
+
+impl Classification {
+    /// Crawlers, headless browsers and command line tools.
+    pub fn is_bot(&self) -> bool {
+        self.bot
+    }
+
+    pub fn browser(&self) -> Option<Browser> {
+        self.browser
+    }
+
+    pub fn browser_version(&self) -> Option<&str> {
+        self.browser_version.as_deref()
+    }
+
+    pub fn os(&self) -> Option<OsFamily> {
+        self.os
+    }
+}
+
+/// Substrings of product names and comments, in lower case, that identify bots.
+const BOT_MARKERS: [&str; 6] = ["bot", "crawler", "spider", "slurp", "headless", "+http"];
+
+/// Product names, in lower case, of HTTP libraries and command line tools.
+const TOOLS: [&str; 7] = ["curl", "wget", "python-requests", "python-urllib", "go-http-client",
+                          "java", "libwww-perl"];
+
+/// Browsers are matched in order, since most of them also claim to be
+/// `Chrome` and `Safari` for compatibility.
+const BROWSERS: [(&str, Browser); 11] = [("edg", Browser::Edge),
+                                         ("edge", Browser::Edge),
+                                         ("edga", Browser::Edge),
+                                         ("edgios", Browser::Edge),
+                                         ("opr", Browser::Opera),
+                                         ("samsungbrowser", Browser::SamsungInternet),
+                                         ("firefox", Browser::Firefox),
+                                         ("fxios", Browser::Firefox),
+                                         ("chrome", Browser::Chrome),
+                                         ("crios", Browser::Chrome),
+                                         ("chromium", Browser::Chrome)];
+
+/// Matched in order against the parts of the comments,
+/// iOS also claims to be `like Mac OS X` and Android to be `Linux`.
+const OS_FAMILIES: [(&str, OsFamily); 9] = [("iphone", OsFamily::Ios),
+                                            ("ipad", OsFamily::Ios),
+                                            ("ipod", OsFamily::Ios),
+                                            ("android", OsFamily::Android),
+                                            ("cros", OsFamily::ChromeOs),
+                                            ("windows", OsFamily::Windows),
+                                            ("macintosh", OsFamily::MacOs),
+                                            ("mac os x", OsFamily::MacOs),
+                                            ("linux", OsFamily::Linux)];
+
+/// # Examples
+///
+/// ```
+/// use teapot::http::headers::{RawHeader, TypedHeader, UserAgentHeader};
+/// use teapot::http::user_agent::{classify, Browser, OsFamily};
+///
+/// let raw = RawHeader::new("User-Agent",
+///                          "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) \
+///                           AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 \
+///                           Mobile/15E148 Safari/604.1");
+/// let classification = classify(&UserAgentHeader::parse(&[&raw]).unwrap());
+///
+/// assert!(!classification.is_bot());
+/// assert_eq!(Some(Browser::Safari), classification.browser());
+/// assert_eq!(Some("17.2"), classification.browser_version());
+/// assert_eq!(Some(OsFamily::Ios), classification.os());
+/// ```
+pub fn classify(user_agent: &UserAgentHeader) -> Classification {
+    let products: Vec<&Product> = user_agent.products().collect();
+    let comments: Vec<String> = user_agent.comments().map(str::to_lowercase).collect();
+    let names: Vec<String> = products.iter()
+        .map(|product| product.name().to_lowercase())
+        .collect();
+
+    let is_tool = names.iter().any(|name| TOOLS.contains(&name.as_str()));
+    let has_bot_marker = names.iter()
+        .chain(comments.iter())
+        .any(|text| BOT_MARKERS.iter().any(|marker| text.contains(marker)));
+
+    let (browser, browser_version) = match detect_browser(&products, &names, &comments) {
+        Some((browser, version)) => (Some(browser), version),
+        None => (None, None),
+    };
+
+    let parts: Vec<&str> = comments.iter()
+        .flat_map(|comment| comment.split(';'))
+        .map(str::trim)
+        .collect();
+    let os = OS_FAMILIES.iter()
+        .find(|&&(marker, _)| parts.iter().any(|part| part.starts_with(marker)))
+        .map(|&(_, os)| os);
+
+    Classification {
+        bot: is_tool || has_bot_marker,
+        browser,
+        browser_version,
+        os,
+    }
+}
+
+fn detect_browser(products: &[&Product],
+                  names: &[String],
+                  comments: &[String])
+                  -> Option<(Browser, Option<String>)> {
+    let version = |name: &str| {
+        names.iter()
+            .position(|other| other == name)
+            .and_then(|index| products[index].version())
+            .map(str::to_string)
+    };
+
+    for &(name, browser) in BROWSERS.iter() {
+        if names.iter().any(|other| other == name) {
+            return Some((browser, version(name)));
+        }
+    }
+
+    // Safari reports its version in a separate product
+    if names.iter().any(|name| name == "safari") {
+        return Some((Browser::Safari, version("version")));
+    }
+
+    let msie = comments.iter()
+        .flat_map(|comment| comment.split(';'))
+        .filter_map(|part| part.trim().strip_prefix("msie "))
+        .next();
+
+    if let Some(version) = msie {
+        return Some((Browser::InternetExplorer, Some(version.to_string())));
+    }
+
+    if comments.iter().any(|comment| comment.contains("trident/")) {
+        return Some((Browser::InternetExplorer, None));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::headers::{RawHeader, TypedHeader};
+
+    fn classify_str(value: &str) -> Classification {
+        classify(&UserAgentHeader::parse(&[&RawHeader::new("User-Agent", value)]).unwrap())
+    }
+
+    #[test]
+    fn test_browsers() {
+        let chrome = classify_str("Mozilla/5.0 (Windows NT 10.0; Win64; x64) \
+                                   AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 \
+                                   Safari/537.36");
+        let edge = classify_str("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                                 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 \
+                                 Edg/120.0.2210.91");
+        let firefox = classify_str("Mozilla/5.0 (Macintosh; Intel Mac OS X 14.2; rv:121.0) \
+                                    Gecko/20100101 Firefox/121.0");
+        let samsung = classify_str("Mozilla/5.0 (Linux; Android 13; SM-S918B) \
+                                    AppleWebKit/537.36 (KHTML, like Gecko) \
+                                    SamsungBrowser/23.0 Chrome/115.0.0.0 Mobile \
+                                    Safari/537.36");
+        let ie = classify_str("Mozilla/4.0 (compatible; MSIE 8.0; Windows NT 6.1; Trident/4.0)");
+
+        assert_eq!((Some(Browser::Chrome), Some("120.0.0.0"), Some(OsFamily::Windows)),
+                   (chrome.browser(), chrome.browser_version(), chrome.os()));
+        assert_eq!((Some(Browser::Edge), Some("120.0.2210.91")),
+                   (edge.browser(), edge.browser_version()));
+        assert_eq!((Some(Browser::Firefox), Some(OsFamily::MacOs)),
+                   (firefox.browser(), firefox.os()));
+        assert_eq!((Some(Browser::SamsungInternet), Some(OsFamily::Android)),
+                   (samsung.browser(), samsung.os()));
+        assert_eq!((Some(Browser::InternetExplorer), Some("8.0")),
+                   (ie.browser(), ie.browser_version()));
+        assert!(!chrome.is_bot());
+    }
+
+    #[test]
+    fn test_bots() {
+        let googlebot = classify_str("Mozilla/5.0 (compatible; Googlebot/2.1; \
+                                      +http://www.google.com/bot.html)");
+        let headless = classify_str("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 \
+                                     (KHTML, like Gecko) HeadlessChrome/120.0.0.0 \
+                                     Safari/537.36");
+
+        assert!(googlebot.is_bot());
+        assert_eq!(None, googlebot.browser());
+        assert!(headless.is_bot());
+        assert_eq!(Some(OsFamily::Linux), headless.os());
+        assert!(classify_str("curl/8.4.0").is_bot());
+        assert!(!classify_str("MyApp/1.0").is_bot());
+    }
+}
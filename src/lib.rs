@@ -1,5 +1,13 @@
+#[cfg(feature = "brotli")]
+extern crate brotli as brotli_crate;
+#[cfg(any(feature = "gzip", feature = "deflate"))]
+extern crate flate2;
+
 #[macro_use]
 mod macros;
+mod base64;
+mod hash;
 
 pub mod mime;
 pub mod http;
+pub mod uri;
@@ -1,5 +1,4 @@
 use std::fmt;
-use std::str::FromStr;
 
 // TODO: add more types
 // (maybe automated from https://www.iana.org/assignments/media-types/media-types.xhtml)
@@ -24,8 +23,6 @@ pub trait Mime: fmt::Display {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     fn test_to_string() {}
 }
@@ -0,0 +1,336 @@
+//! URI references as defined by [RFC3986](http://www.iana.org/go/rfc3986).
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidUri;
+
+/// A URI or a relative reference, split into its components.
+/// The components are kept as they are, without normalization
+/// or percent-decoding.
+/// [RFC3986, Section 4.1](http://www.iana.org/go/rfc3986)
+///
+/// # Examples
+///
+/// ```
+/// use teapot::uri::UriReference;
+///
+/// let base: UriReference = "https://example.com/a/b?q".parse().unwrap();
+/// let reference: UriReference = "../c#top".parse().unwrap();
+///
+/// assert!(reference.is_relative());
+/// assert_eq!("https://example.com/c#top", reference.resolve(&base).to_string());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct UriReference {
+    scheme: Option<String>,
+    authority: Option<String>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl UriReference {
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+
+    pub fn authority(&self) -> Option<&str> {
+        self.authority.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+
+    pub fn set_fragment(&mut self, fragment: Option<String>) {
+        self.fragment = fragment;
+    }
+
+    /// A URI with a scheme, as opposed to a relative reference.
+    pub fn is_absolute(&self) -> bool {
+        self.scheme.is_some()
+    }
+
+    pub fn is_relative(&self) -> bool {
+        self.scheme.is_none()
+    }
+
+    /// Resolves this reference against `base`, which should be absolute.
+    /// [RFC3986, Section 5.2](http://www.iana.org/go/rfc3986)
+    pub fn resolve(&self, base: &UriReference) -> UriReference {
+        if self.scheme.is_some() {
+            return UriReference {
+                path: remove_dot_segments(&self.path),
+                ..self.clone()
+            };
+        }
+
+        let (authority, path, query) = if self.authority.is_some() {
+            (self.authority.clone(), remove_dot_segments(&self.path), self.query.clone())
+        } else if self.path.is_empty() {
+            (base.authority.clone(),
+             base.path.clone(),
+             self.query.clone().or_else(|| base.query.clone()))
+        } else if self.path.starts_with('/') {
+            (base.authority.clone(), remove_dot_segments(&self.path), self.query.clone())
+        } else {
+            (base.authority.clone(),
+             remove_dot_segments(&merge(base, &self.path)),
+             self.query.clone())
+        };
+
+        UriReference {
+            scheme: base.scheme.clone(),
+            authority,
+            path,
+            query,
+            fragment: self.fragment.clone(),
+        }
+    }
+}
+
+/// [RFC3986, Section 5.2.3](http://www.iana.org/go/rfc3986)
+fn merge(base: &UriReference, path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        return format!("/{}", path);
+    }
+
+    match base.path.rfind('/') {
+        Some(index) => format!("{}{}", &base.path[..index + 1], path),
+        None => path.to_string(),
+    }
+}
+
+/// [RFC3986, Section 5.2.4](http://www.iana.org/go/rfc3986)
+fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output = String::with_capacity(path.len());
+    let pop_segment = |output: &mut String| {
+        let index = output.rfind('/').unwrap_or(0);
+
+        output.truncate(index);
+    };
+
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            pop_segment(&mut output);
+        } else if input == "/.." {
+            input = "/";
+            pop_segment(&mut output);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = input[start..].find('/').map_or(input.len(), |index| index + start);
+
+            output.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+
+    output
+}
+
+fn is_uri_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~:/?#[]@!$&'()*+,;=%".contains(c)
+}
+
+fn is_valid_scheme(scheme: &str) -> bool {
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic()) &&
+    scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+}
+
+fn has_valid_percent_encoding(value: &str) -> bool {
+    let bytes = value.as_bytes();
+
+    bytes.iter().enumerate().all(|(index, &byte)| {
+        byte != b'%' ||
+        (index + 2 < bytes.len() &&
+         bytes[index + 1].is_ascii_hexdigit() && bytes[index + 2].is_ascii_hexdigit())
+    })
+}
+
+impl FromStr for UriReference {
+    type Err = InvalidUri;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.chars().all(is_uri_char) || !has_valid_percent_encoding(s) {
+            return Err(InvalidUri);
+        }
+
+        let (rest, fragment) = match s.find('#') {
+            Some(index) => (&s[..index], Some(&s[index + 1..])),
+            None => (s, None),
+        };
+        let (rest, query) = match rest.find('?') {
+            Some(index) => (&rest[..index], Some(&rest[index + 1..])),
+            None => (rest, None),
+        };
+        let (scheme, rest) = match rest.find([':', '/']) {
+            Some(index) if rest[index..].starts_with(':') => {
+                (Some(&rest[..index]), &rest[index + 1..])
+            }
+            _ => (None, rest),
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let index = rest.find('/').unwrap_or(rest.len());
+
+                (Some(&rest[..index]), &rest[index..])
+            }
+            None => (None, rest),
+        };
+
+        if !scheme.map_or(true, is_valid_scheme) {
+            return Err(InvalidUri);
+        }
+
+        Ok(UriReference {
+            scheme: scheme.map(str::to_string),
+            authority: authority.map(str::to_string),
+            path: path.to_string(),
+            query: query.map(str::to_string),
+            fragment: fragment.map(str::to_string),
+        })
+    }
+}
+
+impl fmt::Display for UriReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref scheme) = self.scheme {
+            write!(f, "{}:", scheme)?;
+        }
+
+        if let Some(ref authority) = self.authority {
+            write!(f, "//{}", authority)?;
+        }
+
+        write!(f, "{}", self.path)?;
+
+        if let Some(ref query) = self.query {
+            write!(f, "?{}", query)?;
+        }
+
+        if let Some(ref fragment) = self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let uri: UriReference = "https://user@example.com:8080/a/b?x=1#y".parse().unwrap();
+
+        assert_eq!(Some("https"), uri.scheme());
+        assert_eq!(Some("user@example.com:8080"), uri.authority());
+        assert_eq!("/a/b", uri.path());
+        assert_eq!(Some("x=1"), uri.query());
+        assert_eq!(Some("y"), uri.fragment());
+        assert_eq!("https://user@example.com:8080/a/b?x=1#y", uri.to_string());
+
+        let relative: UriReference = "a/b:c?".parse().unwrap();
+
+        assert!(relative.is_relative());
+        assert_eq!("a/b:c", relative.path());
+        assert_eq!(Some(""), relative.query());
+        assert_eq!(Ok(UriReference {
+                       scheme: Some("urn".to_string()),
+                       authority: None,
+                       path: "isbn:0451450523".to_string(),
+                       query: None,
+                       fragment: None,
+                   }),
+                   "urn:isbn:0451450523".parse());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(Err(InvalidUri), "/a b".parse::<UriReference>());
+        assert_eq!(Err(InvalidUri), "/%zz".parse::<UriReference>());
+        assert_eq!(Err(InvalidUri), "/%a".parse::<UriReference>());
+        assert_eq!(Err(InvalidUri), "1http://a".parse::<UriReference>());
+        assert_eq!(Err(InvalidUri), "/ä".parse::<UriReference>());
+    }
+
+    /// [RFC3986, Section 5.4](http://www.iana.org/go/rfc3986)
+    #[test]
+    fn test_resolve() {
+        let base: UriReference = "http://a/b/c/d;p?q".parse().unwrap();
+        let examples = [("g:h", "g:h"),
+                        ("g", "http://a/b/c/g"),
+                        ("./g", "http://a/b/c/g"),
+                        ("g/", "http://a/b/c/g/"),
+                        ("/g", "http://a/g"),
+                        ("//g", "http://g"),
+                        ("?y", "http://a/b/c/d;p?y"),
+                        ("g?y", "http://a/b/c/g?y"),
+                        ("#s", "http://a/b/c/d;p?q#s"),
+                        ("g#s", "http://a/b/c/g#s"),
+                        ("g?y#s", "http://a/b/c/g?y#s"),
+                        (";x", "http://a/b/c/;x"),
+                        ("g;x", "http://a/b/c/g;x"),
+                        ("", "http://a/b/c/d;p?q"),
+                        (".", "http://a/b/c/"),
+                        ("./", "http://a/b/c/"),
+                        ("..", "http://a/b/"),
+                        ("../", "http://a/b/"),
+                        ("../g", "http://a/b/g"),
+                        ("../..", "http://a/"),
+                        ("../../", "http://a/"),
+                        ("../../g", "http://a/g"),
+                        ("../../../g", "http://a/g"),
+                        ("../../../../g", "http://a/g"),
+                        ("/./g", "http://a/g"),
+                        ("/../g", "http://a/g"),
+                        ("g.", "http://a/b/c/g."),
+                        (".g", "http://a/b/c/.g"),
+                        ("g..", "http://a/b/c/g.."),
+                        ("..g", "http://a/b/c/..g"),
+                        ("./../g", "http://a/b/g"),
+                        ("./g/.", "http://a/b/c/g/"),
+                        ("g/./h", "http://a/b/c/g/h"),
+                        ("g/../h", "http://a/b/c/h"),
+                        ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+                        ("g;x=1/../y", "http://a/b/c/y"),
+                        ("g?y/./x", "http://a/b/c/g?y/./x"),
+                        ("g?y/../x", "http://a/b/c/g?y/../x"),
+                        ("g#s/./x", "http://a/b/c/g#s/./x"),
+                        ("g#s/../x", "http://a/b/c/g#s/../x"),
+                        ("http:g", "http:g")];
+
+        for &(reference, expected) in examples.iter() {
+            let reference: UriReference = reference.parse().unwrap();
+
+            assert_eq!(expected, reference.resolve(&base).to_string());
+        }
+
+        let base: UriReference = "http://a".parse().unwrap();
+
+        assert_eq!("http://a/g", "g".parse::<UriReference>().unwrap().resolve(&base).to_string());
+    }
+}
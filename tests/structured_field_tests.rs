@@ -0,0 +1,165 @@
+//! Runs parsing tests in the format of https://github.com/httpwg/structured-field-tests
+//! from `tests/structured-field-tests`, see the README there for their upstream version.
+
+extern crate serde_json;
+extern crate teapot;
+
+use std::fs;
+use std::path::Path;
+use serde_json::{json, Value};
+use teapot::http::headers::{BareItem, Dictionary, InnerList, Item, List, Member, Parameters,
+                            StructuredValue};
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Encodes `bytes` in padded base32, which the tests use for byte sequences.
+fn base32(bytes: &[u8]) -> String {
+    let mut output = String::new();
+
+    for chunk in bytes.chunks(5) {
+        let mut buffer = [0u8; 5];
+
+        buffer[..chunk.len()].copy_from_slice(chunk);
+
+        let bits = buffer.iter().fold(0u64, |bits, &byte| bits << 8 | u64::from(byte));
+        let symbols = (chunk.len() * 8).div_ceil(5);
+
+        for index in 0..8 {
+            if index < symbols {
+                let symbol = (bits >> (35 - index * 5)) & 0x1f;
+
+                output.push(BASE32_ALPHABET[symbol as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+fn bare_item_to_json(bare_item: &BareItem) -> Value {
+    match *bare_item {
+        BareItem::Integer(integer) => json!(integer),
+        BareItem::Decimal(decimal) => json!(decimal.thousandths() as f64 / 1000.0),
+        BareItem::String(ref string) => json!(string),
+        BareItem::Token(ref token) => json!({ "__type": "token", "value": token }),
+        BareItem::ByteSequence(ref bytes) => json!({ "__type": "binary", "value": base32(bytes) }),
+        BareItem::Boolean(boolean) => json!(boolean),
+    }
+}
+
+fn params_to_json(params: &Parameters) -> Value {
+    Value::Array(params.iter()
+        .map(|(key, value)| json!([key, bare_item_to_json(value)]))
+        .collect())
+}
+
+fn item_to_json(item: &Item) -> Value {
+    json!([bare_item_to_json(item.bare_item()), params_to_json(item.params())])
+}
+
+fn inner_list_to_json(inner_list: &InnerList) -> Value {
+    let items: Vec<Value> = inner_list.items().iter().map(item_to_json).collect();
+
+    json!([items, params_to_json(inner_list.params())])
+}
+
+fn member_to_json(member: &Member) -> Value {
+    match *member {
+        Member::Item(ref item) => item_to_json(item),
+        Member::InnerList(ref inner_list) => inner_list_to_json(inner_list),
+    }
+}
+
+/// Parses `raw` as `header_type`, returning the value in the notation
+/// of the tests and its serialization.
+fn parse(header_type: &str, raw: &str) -> Option<(Value, String)> {
+    match header_type {
+        "item" => {
+            let item: Item = raw.parse().ok()?;
+
+            Some((item_to_json(&item), item.serialize().ok()?))
+        }
+        "list" => {
+            let list: List = raw.parse().ok()?;
+            let members = list.members().iter().map(member_to_json).collect();
+
+            Some((Value::Array(members), list.serialize().ok()?))
+        }
+        "dictionary" => {
+            let dictionary: Dictionary = raw.parse().ok()?;
+            let members = dictionary.iter()
+                .map(|(key, member)| json!([key, member_to_json(member)]))
+                .collect();
+
+            Some((Value::Array(members), dictionary.serialize().ok()?))
+        }
+        header_type => panic!("unknown header type {}", header_type),
+    }
+}
+
+/// Field lines are combined like multiple header lines of the same field.
+fn join_lines(lines: &Value) -> String {
+    let lines: Vec<&str> = lines.as_array()
+        .expect("lines must be an array")
+        .iter()
+        .map(|line| line.as_str().expect("line must be a string"))
+        .collect();
+
+    lines.join(", ")
+}
+
+fn run_test(file: &str, test: &Value) {
+    let name = format!("{}: {}", file, test["name"].as_str().expect("test needs a name"));
+    let header_type = test["header_type"].as_str().expect("test needs a header type");
+    let raw = join_lines(&test["raw"]);
+    let must_fail = test["must_fail"].as_bool().unwrap_or(false);
+    let can_fail = test["can_fail"].as_bool().unwrap_or(false);
+
+    match parse(header_type, &raw) {
+        None if must_fail || can_fail => {}
+        None => panic!("{}: failed to parse {:?}", name, raw),
+        Some(_) if must_fail => panic!("{}: parsed {:?}, which must fail", name, raw),
+        Some((value, serialized)) => {
+            let canonical = match test.get("canonical") {
+                Some(canonical) => join_lines(canonical),
+                None => raw.clone(),
+            };
+
+            assert_eq!(test["expected"], value, "{}", name);
+            assert_eq!(canonical, serialized, "{}", name);
+        }
+    }
+}
+
+#[test]
+fn test_structured_field_tests() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/structured-field-tests");
+    let mut count = 0;
+
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+
+        let file = path.file_name().unwrap().to_string_lossy().into_owned();
+        let tests: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+
+        for test in tests.as_array().expect("a test file must contain an array") {
+            run_test(&file, test);
+            count += 1;
+        }
+    }
+
+    assert!(count > 0, "no structured field tests found");
+}
+
+#[test]
+fn test_base32() {
+    assert_eq!("", base32(b""));
+    assert_eq!("MY======", base32(b"f"));
+    assert_eq!("MZXW6YTBOI======", base32(b"foobar"));
+}